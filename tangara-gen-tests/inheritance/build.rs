@@ -0,0 +1,56 @@
+use std::path::Path;
+use tangara_gen::*;
+use tangara_highlevel::{generate_type_id, MethodKind, NamingConventions, TypeRef};
+use tangara_highlevel::primitives::Primitive;
+use tangara_highlevel::builder::*;
+
+fn main() {
+    // package with open class `Shape` and class `Square` overriding its virtual method
+    let builder = PackageBuilder::new("inheritance", NamingConventions::rust());
+    builder.borrow_mut().set_namespace("inheritance");
+    let mut shape = create_class(builder.clone(), "Shape");
+    shape.open()
+        .add_constructor()
+            .add_attribute(RUST_STD_LIB.constructor_name_attribute("new"))
+            .build()
+        .add_method("sides")
+            .set_kind(MethodKind::Virtual)
+            .add_attribute(RUST_STD_LIB.reference_attribute())
+            .return_type(TypeRef::from("u32"))
+            .build()
+        .add_method("name")
+            .add_attribute(RUST_STD_LIB.reference_attribute())
+            .return_type(TypeRef::from("String"))
+            .build();
+    shape.build();
+    let mut square = create_class(builder.clone(), "Square");
+    // types can be referenced by ids too, bindings resolve them to names
+    square.inherits(TypeRef::Id(generate_type_id("inheritance.Shape")))
+        .add_constructor()
+            .add_attribute(RUST_STD_LIB.constructor_name_attribute("new"))
+            .arg(TypeRef::Id(Primitive::UInt.type_id()), "size")
+            .build()
+        // sealed class overrides method of base class by method with the same name
+        .add_method("sides")
+            .add_attribute(RUST_STD_LIB.reference_attribute())
+            .return_type(TypeRef::from("u32"))
+            .build()
+        .add_method("size")
+            .add_attribute(RUST_STD_LIB.reference_attribute())
+            .return_type(TypeRef::from("u32"))
+            .build();
+    square.build();
+    let pkg = builder.borrow().build();
+
+    // library side and host side of the same package
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    RustGenerator::new(pkg.clone(), RustGenConfig::default())
+        .generate_entrypoint()
+        .custom_use("crate::shapes::*")
+        .write_to(Path::new(&out_dir).join("entrypoint.rs"))
+        .unwrap();
+    RustGenerator::new(pkg, RustGenConfig::default())
+        .generate_bindings()
+        .write_to(Path::new(&out_dir).join("bindings.rs"))
+        .unwrap();
+}
@@ -0,0 +1,70 @@
+/// Library types. Derived class keeps its base class as first field,
+/// so pointer to derived object is also pointer to base object.
+pub mod shapes {
+    use std::ops::Deref;
+
+    #[repr(C)]
+    pub struct Shape {
+        name: String
+    }
+
+    impl Shape {
+        pub fn new() -> Self {
+            Self { name: "shape".to_string() }
+        }
+
+        pub fn sides(&self) -> u32 {
+            0
+        }
+
+        pub fn name(&self) -> String {
+            self.name.clone()
+        }
+    }
+
+    impl Default for Shape {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    #[repr(C)]
+    pub struct Square {
+        base: Shape,
+        size: u32
+    }
+
+    impl Square {
+        pub fn new(size: u32) -> Self {
+            Self { base: Shape { name: "square".to_string() }, size }
+        }
+
+        pub fn sides(&self) -> u32 {
+            4
+        }
+
+        pub fn size(&self) -> u32 {
+            self.size
+        }
+    }
+
+    impl Deref for Square {
+        type Target = Shape;
+
+        fn deref(&self) -> &Self::Target {
+            &self.base
+        }
+    }
+}
+
+/// Generated `tgLoad` of library
+#[allow(non_snake_case, non_upper_case_globals, unused, clippy::all)]
+pub mod entrypoint {
+    include!(concat!(env!("OUT_DIR"), "/entrypoint.rs"));
+}
+
+/// Generated host bindings of library
+#[allow(non_snake_case, non_upper_case_globals, unused, clippy::all)]
+pub mod bindings {
+    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+}
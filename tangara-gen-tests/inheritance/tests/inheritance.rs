@@ -0,0 +1,36 @@
+use inheritance::bindings::{load_inheritance, Shape, Square};
+use inheritance::entrypoint::tgLoad;
+use tangara::allocator::Allocator;
+use tangara::context::Ptr;
+use tangara::runtime::Runtime;
+
+extern "C" fn other_alloc(size: usize, align: usize) -> Ptr {
+    (Allocator::SYSTEM.alloc)(size, align)
+}
+
+#[test]
+fn override_through_base() {
+    let mut rt = Runtime::new();
+    tgLoad(rt.use_context());
+    load_inheritance(&rt.publish()).unwrap();
+
+    let square = Square::new(3);
+    // inherited method is called through base class
+    assert_eq!(square.name(), "square");
+    assert_eq!(square.size(), 3);
+    let shape: Shape = square.into();
+    // overridden method is found by runtime type of object
+    assert_eq!(shape.sides(), 4);
+    let square = Square::try_from(shape).ok().unwrap();
+    assert_eq!(square.sides(), 4);
+
+    let shape = Shape::new();
+    assert_eq!(shape.sides(), 0);
+    assert_eq!(shape.name(), "shape");
+    assert!(Square::try_from(shape).is_err());
+
+    // objects of library are already made by allocator of first runtime, so it can't be loaded with another one
+    let mut other = Runtime::with_allocator(Allocator { alloc: other_alloc, ..Allocator::SYSTEM });
+    tgLoad(other.use_context());
+    assert!(load_inheritance(&other.publish()).is_err());
+}
@@ -1,9 +1,11 @@
 use tangara_gen::{RustGenConfig, RustGenerator};
-use tangara_highlevel::Package;
+use tangara_highlevel::{Package, TypeRef};
 
 fn main() {
     let p: Package = serde_json::from_str(&std::fs::read_to_string("../mylib.tgjson").unwrap()).unwrap();
-    RustGenerator::new(p, RustGenConfig::default())
+    let mut config = RustGenConfig::default();
+    config.instances.push(TypeRef::Generic(Box::new(TypeRef::from("mylib.GenericsTest")), vec![TypeRef::from("mylib.MyStruct")]));
+    RustGenerator::new(p, config)
         .generate_bindings()
         .write_to("src/bindings.rs")
         .unwrap();
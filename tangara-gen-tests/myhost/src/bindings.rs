@@ -1,33 +1,328 @@
 // This file was generated by tangara-gen
 // All changes in this file will discard after rebuilding project
-use tangara::context::{FnDtor, Context, Ptr, Fn};
+use tangara::allocator::Allocator;
+use tangara::context::{FnDtor, FnTypeOf, Context, Implementation, Ptr, Fn, Variants, Version};
+use tangara::error::TangaraError;
 
-static mut MyStruct_ctor0: Option<Fn> = None;
-static mut MyStruct_repeat_name: Option<Fn> = None;
-static mut MyStruct_set_name: Option<Fn> = None;
-static mut MyStruct_get_name: Option<Fn> = None;
-static mut MyStruct_dtor: Option<FnDtor> = None;
+/// Allocator of context from which bindings were loaded
+static mut TANGARA_ALLOCATOR: Allocator = Allocator::SYSTEM;
+
+static mut EnumUnit_variants: Option<Variants> = None;
+static mut EnumUnit_dtor: Option<FnDtor> = None;
+static mut EnumUnit_Variant: Option<Fn> = None;
+static mut EnumTuple_variants: Option<Variants> = None;
+static mut EnumTuple_dtor: Option<FnDtor> = None;
+static mut EnumTuple_Variant: Option<Fn> = None;
+static mut EnumStruct_variants: Option<Variants> = None;
+static mut EnumStruct_dtor: Option<FnDtor> = None;
+static mut EnumStruct_Variant: Option<Fn> = None;
+static mut EnumMixed_variants: Option<Variants> = None;
+static mut EnumMixed_dtor: Option<FnDtor> = None;
+static mut EnumMixed_Unit: Option<Fn> = None;
+static mut EnumMixed_Tuple: Option<Fn> = None;
+static mut EnumComplex_variants: Option<Variants> = None;
+static mut EnumComplex_dtor: Option<FnDtor> = None;
+static mut EnumComplex_Unit: Option<Fn> = None;
+static mut EnumComplex_Tuple: Option<Fn> = None;
+static mut EnumComplex_Struct: Option<Fn> = None;
 static mut TestStruct_ctor0: Option<Fn> = None;
 static mut TestStruct_ctor1: Option<Fn> = None;
+static mut TestStruct_reversed_id_getter: Option<extern "C" fn(Ptr) -> Ptr> = None;
+static mut TestStruct_reversed_id_setter: Option<extern "C" fn(Ptr, Ptr)> = None;
 static mut TestStruct_id_getter: Option<extern "C" fn(Ptr) -> Ptr> = None;
 static mut TestStruct_id_setter: Option<extern "C" fn(Ptr, Ptr)> = None;
+static mut TestStruct_max_id_static_getter: Option<extern "C" fn() -> Ptr> = None;
+static mut TestStruct_name_static_getter: Option<extern "C" fn() -> Ptr> = None;
+static mut TestStruct_add_ids: Option<Fn> = None;
+static mut TestStruct_set_pair: Option<Fn> = None;
+static mut TestStruct_set_bytes: Option<Fn> = None;
+static mut TestStruct_map_id: Option<Fn> = None;
+static mut TestStruct_set_or_default: Option<Fn> = None;
+static mut TestStruct_add_len_u8: Option<Fn> = None;
+static mut TestStruct_add_len_string: Option<Fn> = None;
 static mut TestStruct_dtor: Option<FnDtor> = None;
+static mut GenericsTestMyStruct_ctor0: Option<Fn> = None;
+static mut GenericsTestMyStruct_set_t: Option<Fn> = None;
+static mut GenericsTestMyStruct_print_t_bar: Option<Fn> = None;
+static mut GenericsTestMyStruct_dtor: Option<FnDtor> = None;
+static mut MyStruct_ctor0: Option<Fn> = None;
+static mut MyStruct_repeat_name: Option<Fn> = None;
+static mut MyStruct_set_name: Option<Fn> = None;
+static mut MyStruct_get_name: Option<Fn> = None;
+static mut MyStruct_greet_host: Option<Fn> = None;
+static mut MyStruct_MyTrait_foo: Option<Fn> = None;
+static mut MyStruct_MyTrait_bar: Option<Fn> = None;
+static mut MyStruct_dtor: Option<FnDtor> = None;
 
 #[derive(Ord, PartialOrd, Hash, Eq, PartialEq, Debug, Copy, Clone)]
 pub enum EnumUnit {
 	Variant = 0,
 }
 
+impl EnumUnit {
+    /// Create object of this enum in library, it must be deleted by `delete_ptr`
+    pub fn into_ptr(self) -> Ptr {
+        unsafe {
+            match self {
+				EnumUnit::Variant => {
+					EnumUnit_Variant.expect("Constructor wasn't loaded")(0, std::ptr::null_mut())
+				}
+            }
+        }
+    }
+
+    /// Read value of enum object from library, object isn't deleted
+    pub unsafe fn from_ptr(ptr: Ptr) -> Self {
+        let variants = EnumUnit_variants.expect("Variants weren't loaded");
+        match (variants.tag)(ptr) {
+			837492378273562681 => EnumUnit::Variant,
+            _ => panic!("Unknown variant of EnumUnit")
+        }
+    }
+
+    /// Delete enum object created in library
+    pub unsafe fn delete_ptr(ptr: Ptr) {
+        EnumUnit_dtor.expect("Destructor wasn't loaded from library")(ptr);
+    }
+}
+
 pub enum EnumTuple {
+	Variant(i32),
+}
+
+impl EnumTuple {
+    /// Create object of this enum in library, it must be deleted by `delete_ptr`
+    pub fn into_ptr(self) -> Ptr {
+        unsafe {
+            match self {
+				EnumTuple::Variant(field0) => {
+                let args_size = std::mem::size_of::<i32>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut i32) = field0;
+                }
+					EnumTuple_Variant.expect("Constructor wasn't loaded")(args_size, args_ptr)
+				}
+            }
+        }
+    }
+
+    /// Read value of enum object from library, object isn't deleted
+    pub unsafe fn from_ptr(ptr: Ptr) -> Self {
+        let variants = EnumTuple_variants.expect("Variants weren't loaded");
+        match (variants.tag)(ptr) {
+			837492378273562681 => EnumTuple::Variant(((variants.field)(ptr, 14300287532767007308) as *const i32).as_ref().expect("Field of variant wasn't found").clone()),
+            _ => panic!("Unknown variant of EnumTuple")
+        }
+    }
+
+    /// Delete enum object created in library
+    pub unsafe fn delete_ptr(ptr: Ptr) {
+        EnumTuple_dtor.expect("Destructor wasn't loaded from library")(ptr);
+    }
+}
+
+/// Borrowed view of EnumTuple object from library, it's valid while object is alive
+pub enum EnumTupleRef<'a> {
+	Variant(&'a i32),
+}
+
+impl<'a> EnumTupleRef<'a> {
+    /// View enum object from library, object must outlive the view
+    pub unsafe fn from_ptr(ptr: Ptr) -> Self {
+        let variants = EnumTuple_variants.expect("Variants weren't loaded");
+        match (variants.tag)(ptr) {
+			837492378273562681 => EnumTupleRef::Variant(((variants.field)(ptr, 14300287532767007308) as *const i32).as_ref().expect("Field of variant wasn't found")),
+            _ => panic!("Unknown variant of EnumTuple")
+        }
+    }
 }
 
 pub enum EnumStruct {
+	Variant { a: i32 },
+}
+
+impl EnumStruct {
+    /// Create object of this enum in library, it must be deleted by `delete_ptr`
+    pub fn into_ptr(self) -> Ptr {
+        unsafe {
+            match self {
+				EnumStruct::Variant { a } => {
+                let args_size = std::mem::size_of::<i32>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut i32) = a;
+                }
+					EnumStruct_Variant.expect("Constructor wasn't loaded")(args_size, args_ptr)
+				}
+            }
+        }
+    }
+
+    /// Read value of enum object from library, object isn't deleted
+    pub unsafe fn from_ptr(ptr: Ptr) -> Self {
+        let variants = EnumStruct_variants.expect("Variants weren't loaded");
+        match (variants.tag)(ptr) {
+			837492378273562681 => EnumStruct::Variant { a: ((variants.field)(ptr, 16589621723077738341) as *const i32).as_ref().expect("Field of variant wasn't found").clone() },
+            _ => panic!("Unknown variant of EnumStruct")
+        }
+    }
+
+    /// Delete enum object created in library
+    pub unsafe fn delete_ptr(ptr: Ptr) {
+        EnumStruct_dtor.expect("Destructor wasn't loaded from library")(ptr);
+    }
+}
+
+/// Borrowed view of EnumStruct object from library, it's valid while object is alive
+pub enum EnumStructRef<'a> {
+	Variant { a: &'a i32 },
+}
+
+impl<'a> EnumStructRef<'a> {
+    /// View enum object from library, object must outlive the view
+    pub unsafe fn from_ptr(ptr: Ptr) -> Self {
+        let variants = EnumStruct_variants.expect("Variants weren't loaded");
+        match (variants.tag)(ptr) {
+			837492378273562681 => EnumStructRef::Variant { a: ((variants.field)(ptr, 16589621723077738341) as *const i32).as_ref().expect("Field of variant wasn't found") },
+            _ => panic!("Unknown variant of EnumStruct")
+        }
+    }
 }
 
 pub enum EnumMixed {
+	Unit,
+	Tuple(i32),
+}
+
+impl EnumMixed {
+    /// Create object of this enum in library, it must be deleted by `delete_ptr`
+    pub fn into_ptr(self) -> Ptr {
+        unsafe {
+            match self {
+				EnumMixed::Unit => {
+					EnumMixed_Unit.expect("Constructor wasn't loaded")(0, std::ptr::null_mut())
+				}
+				EnumMixed::Tuple(field0) => {
+                let args_size = std::mem::size_of::<i32>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut i32) = field0;
+                }
+					EnumMixed_Tuple.expect("Constructor wasn't loaded")(args_size, args_ptr)
+				}
+            }
+        }
+    }
+
+    /// Read value of enum object from library, object isn't deleted
+    pub unsafe fn from_ptr(ptr: Ptr) -> Self {
+        let variants = EnumMixed_variants.expect("Variants weren't loaded");
+        match (variants.tag)(ptr) {
+			9260626685794967516 => EnumMixed::Unit,
+			8975276260061643599 => EnumMixed::Tuple(((variants.field)(ptr, 6045555171727804651) as *const i32).as_ref().expect("Field of variant wasn't found").clone()),
+            _ => panic!("Unknown variant of EnumMixed")
+        }
+    }
+
+    /// Delete enum object created in library
+    pub unsafe fn delete_ptr(ptr: Ptr) {
+        EnumMixed_dtor.expect("Destructor wasn't loaded from library")(ptr);
+    }
+}
+
+/// Borrowed view of EnumMixed object from library, it's valid while object is alive
+pub enum EnumMixedRef<'a> {
+	Unit,
+	Tuple(&'a i32),
+}
+
+impl<'a> EnumMixedRef<'a> {
+    /// View enum object from library, object must outlive the view
+    pub unsafe fn from_ptr(ptr: Ptr) -> Self {
+        let variants = EnumMixed_variants.expect("Variants weren't loaded");
+        match (variants.tag)(ptr) {
+			9260626685794967516 => EnumMixedRef::Unit,
+			8975276260061643599 => EnumMixedRef::Tuple(((variants.field)(ptr, 6045555171727804651) as *const i32).as_ref().expect("Field of variant wasn't found")),
+            _ => panic!("Unknown variant of EnumMixed")
+        }
+    }
 }
 
 pub enum EnumComplex {
+	Unit,
+	Tuple(i32),
+	Struct { a: i32 },
+}
+
+impl EnumComplex {
+    /// Create object of this enum in library, it must be deleted by `delete_ptr`
+    pub fn into_ptr(self) -> Ptr {
+        unsafe {
+            match self {
+				EnumComplex::Unit => {
+					EnumComplex_Unit.expect("Constructor wasn't loaded")(0, std::ptr::null_mut())
+				}
+				EnumComplex::Tuple(field0) => {
+                let args_size = std::mem::size_of::<i32>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut i32) = field0;
+                }
+					EnumComplex_Tuple.expect("Constructor wasn't loaded")(args_size, args_ptr)
+				}
+				EnumComplex::Struct { a } => {
+                let args_size = std::mem::size_of::<i32>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut i32) = a;
+                }
+					EnumComplex_Struct.expect("Constructor wasn't loaded")(args_size, args_ptr)
+				}
+            }
+        }
+    }
+
+    /// Read value of enum object from library, object isn't deleted
+    pub unsafe fn from_ptr(ptr: Ptr) -> Self {
+        let variants = EnumComplex_variants.expect("Variants weren't loaded");
+        match (variants.tag)(ptr) {
+			9260626685794967516 => EnumComplex::Unit,
+			8975276260061643599 => EnumComplex::Tuple(((variants.field)(ptr, 6045555171727804651) as *const i32).as_ref().expect("Field of variant wasn't found").clone()),
+			12225383099421259715 => EnumComplex::Struct { a: ((variants.field)(ptr, 12537007794890514693) as *const i32).as_ref().expect("Field of variant wasn't found").clone() },
+            _ => panic!("Unknown variant of EnumComplex")
+        }
+    }
+
+    /// Delete enum object created in library
+    pub unsafe fn delete_ptr(ptr: Ptr) {
+        EnumComplex_dtor.expect("Destructor wasn't loaded from library")(ptr);
+    }
+}
+
+/// Borrowed view of EnumComplex object from library, it's valid while object is alive
+pub enum EnumComplexRef<'a> {
+	Unit,
+	Tuple(&'a i32),
+	Struct { a: &'a i32 },
+}
+
+impl<'a> EnumComplexRef<'a> {
+    /// View enum object from library, object must outlive the view
+    pub unsafe fn from_ptr(ptr: Ptr) -> Self {
+        let variants = EnumComplex_variants.expect("Variants weren't loaded");
+        match (variants.tag)(ptr) {
+			9260626685794967516 => EnumComplexRef::Unit,
+			8975276260061643599 => EnumComplexRef::Tuple(((variants.field)(ptr, 6045555171727804651) as *const i32).as_ref().expect("Field of variant wasn't found")),
+			12225383099421259715 => EnumComplexRef::Struct { a: ((variants.field)(ptr, 12537007794890514693) as *const i32).as_ref().expect("Field of variant wasn't found") },
+            _ => panic!("Unknown variant of EnumComplex")
+        }
+    }
 }
 
 pub trait MyTrait {
@@ -35,8 +330,348 @@ pub trait MyTrait {
 	fn bar(&self) -> String;
 }
 
+extern "C" fn MyTrait_foo_host<T: MyTrait>(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *mut T = *(args_ptr as *mut Ptr) as *mut T;
+        args_ptr = args_ptr.add(std::mem::size_of::<Ptr>());
+        let a: String = std::ptr::read(args_ptr as *const String);
+        args_ptr = args_ptr.add(std::mem::size_of::<String>());
+        (*this).foo(a);
+        std::ptr::null_mut()
+    }
+}
+
+extern "C" fn MyTrait_bar_host<T: MyTrait>(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *const T = *(args_ptr as *mut Ptr) as *const T;
+        args_ptr = args_ptr.add(std::mem::size_of::<Ptr>());
+        TANGARA_ALLOCATOR.new_object((*this).bar())
+    }
+}
+
+extern "C" fn MyTrait_host_dtor<T>(value: Ptr) {
+    unsafe {
+        drop(Box::from_raw(value as *mut T));
+    }
+}
+
+/// Register `value` as implementation of MyTrait for libraries. It must be done before loading them.
+/// Libraries can call methods of `value` from any thread, so it must be thread safe.
+pub fn register_my_trait<T: MyTrait + Send + Sync + 'static>(ctx: &mut Context, value: T) {
+	// `T` is `Send + Sync`, so `value` can be used and dropped on any thread
+	let mut implementation = unsafe { Implementation::new(1235733093494375210, Box::into_raw(Box::new(value)) as Ptr, Some(MyTrait_host_dtor::<T>)) };
+	implementation.add_method(13574490469189226774, MyTrait_foo_host::<T>);
+	implementation.add_method(10297498164471647047, MyTrait_bar_host::<T>);
+	ctx.add_implementation(implementation);
+}
+
 pub type BoxedStr = Box<str>;
 
+pub struct TestStruct {
+    ptr: Ptr
+}
+
+impl TestStruct {
+	pub fn test_empty_ctor() -> Self {
+		unsafe {
+			if let Some(ctor_func) = TestStruct_ctor0 {
+                let this = ctor_func(0, std::ptr::null_mut());
+                if !this.is_null() {
+                    Self {
+                        ptr: this
+                    }
+                } else {
+                    panic!("Pointer of constructor result is null")
+                }
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
+	pub fn new(id:u64) -> Self {
+		unsafe {
+			if let Some(ctor_func) = TestStruct_ctor1 {
+                let args_size = std::mem::size_of::<u64>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut u64) = id;
+                }
+                let this = ctor_func(args_size, args_ptr);
+                if !this.is_null() {
+                    Self {
+                        ptr: this
+                    }
+                } else {
+                    panic!("Pointer of constructor result is null")
+                }
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
+	pub fn get_reversed_id(&self) -> u64 {
+		unsafe {
+			let raw_ptr: *mut u64 = TestStruct_reversed_id_getter.unwrap()(self.ptr) as *mut u64;
+			if !raw_ptr.is_null() {
+				TANGARA_ALLOCATOR.take_object(raw_ptr as Ptr)
+			} else {
+				panic!("Pointer of gotten property is null")
+			}
+		}
+	}
+	pub fn set_reversed_id(&mut self, value: u64) {
+		unsafe { TestStruct_reversed_id_setter.unwrap()(self.ptr, &value as *const u64 as Ptr); }
+	}
+	pub fn get_id(&self) -> u64 {
+		unsafe {
+			let raw_ptr: *mut u64 = TestStruct_id_getter.unwrap()(self.ptr) as *mut u64;
+			if !raw_ptr.is_null() {
+				TANGARA_ALLOCATOR.take_object(raw_ptr as Ptr)
+			} else {
+				panic!("Pointer of gotten property is null")
+			}
+		}
+	}
+	pub fn set_id(&mut self, value: u64) {
+		unsafe { TestStruct_id_setter.unwrap()(self.ptr, &value as *const u64 as Ptr); }
+	}
+	pub fn get_max_id() -> u64 {
+		unsafe {
+			let raw_ptr: *mut u64 = TestStruct_max_id_static_getter.expect("Static property wasn't loaded")() as *mut u64;
+			if !raw_ptr.is_null() {
+				TANGARA_ALLOCATOR.take_object(raw_ptr as Ptr)
+			} else {
+				panic!("Pointer of gotten static property is null")
+			}
+		}
+	}
+	pub fn get_name() -> &'static str {
+		unsafe {
+			let raw_ptr: *mut &'static str = TestStruct_name_static_getter.expect("Static property wasn't loaded")() as *mut &'static str;
+			if !raw_ptr.is_null() {
+				TANGARA_ALLOCATOR.take_object(raw_ptr as Ptr)
+			} else {
+				panic!("Pointer of gotten static property is null")
+			}
+		}
+	}
+	pub fn add_ids(&mut self, ids:Vec<u64>) {
+		unsafe {
+			if let Some(method_func) = TestStruct_add_ids {
+                let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<Vec<u64>>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut Ptr) = self.ptr;
+					*(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut Vec<u64>) = ids;
+                }
+				method_func(args_size, args_ptr);
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
+	pub fn set_pair(&mut self, pair:(u32,u32)) {
+		unsafe {
+			if let Some(method_func) = TestStruct_set_pair {
+                let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<(u32,u32)>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut Ptr) = self.ptr;
+					*(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut (u32,u32)) = pair;
+                }
+				method_func(args_size, args_ptr);
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
+	pub fn set_bytes(&mut self, bytes:[u8; 8]) {
+		unsafe {
+			if let Some(method_func) = TestStruct_set_bytes {
+                let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<[u8; 8]>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut Ptr) = self.ptr;
+					*(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut [u8; 8]) = bytes;
+                }
+				method_func(args_size, args_ptr);
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
+	pub fn map_id(&mut self, f:fn(u64) -> u64) {
+		unsafe {
+			if let Some(method_func) = TestStruct_map_id {
+                let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<fn(u64) -> u64>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut Ptr) = self.ptr;
+					*(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut fn(u64) -> u64) = f;
+                }
+				method_func(args_size, args_ptr);
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
+	pub fn set_or_default(&mut self, id:Option<u64>) {
+		unsafe {
+			if let Some(method_func) = TestStruct_set_or_default {
+                let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<Option<u64>>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut Ptr) = self.ptr;
+					*(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut Option<u64>) = id;
+                }
+				method_func(args_size, args_ptr);
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
+	pub fn add_len_u8(&mut self, items:Vec<u8>) {
+		unsafe {
+			if let Some(method_func) = TestStruct_add_len_u8 {
+                let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<Vec<u8>>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut Ptr) = self.ptr;
+					*(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut Vec<u8>) = items;
+                }
+				method_func(args_size, args_ptr);
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
+	pub fn add_len_string(&mut self, items:Vec<String>) {
+		unsafe {
+			if let Some(method_func) = TestStruct_add_len_string {
+                let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<Vec<String>>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut Ptr) = self.ptr;
+					*(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut Vec<String>) = items;
+                }
+				method_func(args_size, args_ptr);
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
+}
+
+impl Drop for TestStruct {
+	fn drop(&mut self) {
+		unsafe {
+			TestStruct_dtor.expect("Destructor wasn't loaded from library")(self.ptr);
+		}
+	}
+}
+
+impl Default for TestStruct {
+	fn default() -> Self {
+		unsafe {
+			TestStruct::test_empty_ctor()
+		}
+	}
+}
+
+pub struct GenericsTestMyStruct {
+    ptr: Ptr
+}
+
+impl GenericsTestMyStruct {
+	pub fn new() -> Self {
+		unsafe {
+			if let Some(ctor_func) = GenericsTestMyStruct_ctor0 {
+                let this = ctor_func(0, std::ptr::null_mut());
+                if !this.is_null() {
+                    Self {
+                        ptr: this
+                    }
+                } else {
+                    panic!("Pointer of constructor result is null")
+                }
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
+	pub fn set_t(&mut self, t:MyStruct) {
+		unsafe {
+			if let Some(method_func) = GenericsTestMyStruct_set_t {
+                let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<MyStruct>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut Ptr) = self.ptr;
+					*(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut MyStruct) = t;
+                }
+				method_func(args_size, args_ptr);
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
+	pub fn print_t_bar(&self) {
+		unsafe {
+			if let Some(method_func) = GenericsTestMyStruct_print_t_bar {
+                let args_size = std::mem::size_of::<Ptr>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut Ptr) = self.ptr;
+                }
+				method_func(args_size, args_ptr);
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
+}
+
+impl Drop for GenericsTestMyStruct {
+	fn drop(&mut self) {
+		unsafe {
+			GenericsTestMyStruct_dtor.expect("Destructor wasn't loaded from library")(self.ptr);
+		}
+	}
+}
+
+impl Default for GenericsTestMyStruct {
+	fn default() -> Self {
+		unsafe {
+			GenericsTestMyStruct::new()
+		}
+	}
+}
+
 pub struct MyStruct {
     ptr: Ptr
 }
@@ -77,7 +712,7 @@ impl MyStruct {
                 }
                 let raw_ptr = method_func(args_size, args_ptr);
                 if !raw_ptr.is_null() {
-                    *Box::from_raw(raw_ptr as *mut ())
+                    TANGARA_ALLOCATOR.take_object::<()>(raw_ptr)
                 } else {
                     panic!("Pointer of method result is null")
                 }
@@ -115,7 +750,7 @@ impl MyStruct {
                 }
                 let raw_ptr = method_func(args_size, args_ptr);
                 if !raw_ptr.is_null() {
-                    *Box::from_raw(raw_ptr as *mut &str)
+                    TANGARA_ALLOCATOR.take_object::<&str>(raw_ptr)
                 } else {
                     panic!("Pointer of method result is null")
                 }
@@ -125,54 +760,61 @@ impl MyStruct {
             }
         }
     }
-}
-
-impl Drop for MyStruct {
-	fn drop(&mut self) {
+	pub fn greet_host(&self) -> String {
 		unsafe {
-			MyStruct_dtor.expect("Destructor wasn't loaded from library")(self.ptr);
-		}
-	}
-}
-
-pub struct TestStruct {
-    ptr: Ptr
+			if let Some(method_func) = MyStruct_greet_host {
+                let args_size = std::mem::size_of::<Ptr>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut Ptr) = self.ptr;
+                }
+                let raw_ptr = method_func(args_size, args_ptr);
+                if !raw_ptr.is_null() {
+                    TANGARA_ALLOCATOR.take_object::<String>(raw_ptr)
+                } else {
+                    panic!("Pointer of method result is null")
+                }
+            }
+            else {
+                panic!("Constructor wasn't loaded")
+            }
+        }
+    }
 }
 
-impl TestStruct {
-	pub fn test_empty_ctor() -> Self {
+impl MyTrait for MyStruct {
+	fn foo(&mut self, a:String) {
 		unsafe {
-			if let Some(ctor_func) = TestStruct_ctor0 {
-                let this = ctor_func(0, std::ptr::null_mut());
-                if !this.is_null() {
-                    Self {
-                        ptr: this
-                    }
-                } else {
-                    panic!("Pointer of constructor result is null")
+			if let Some(method_func) = MyStruct_MyTrait_foo {
+                let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<String>();
+                let mut args_buf = vec![0u8; args_size];
+                let args_ptr = args_buf.as_mut_ptr();
+                unsafe {
+                    *(args_ptr as *mut Ptr) = self.ptr;
+					*(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut String) = a;
                 }
+				method_func(args_size, args_ptr);
             }
             else {
                 panic!("Constructor wasn't loaded")
             }
         }
     }
-	pub fn new(id:u64) -> Self {
+	fn bar(&self) -> String {
 		unsafe {
-			if let Some(ctor_func) = TestStruct_ctor1 {
-                let args_size = std::mem::size_of::<u64>();
+			if let Some(method_func) = MyStruct_MyTrait_bar {
+                let args_size = std::mem::size_of::<Ptr>();
                 let mut args_buf = vec![0u8; args_size];
                 let args_ptr = args_buf.as_mut_ptr();
                 unsafe {
-                    *(args_ptr as *mut u64) = id;
+                    *(args_ptr as *mut Ptr) = self.ptr;
                 }
-                let this = ctor_func(args_size, args_ptr);
-                if !this.is_null() {
-                    Self {
-                        ptr: this
-                    }
+                let raw_ptr = method_func(args_size, args_ptr);
+                if !raw_ptr.is_null() {
+                    TANGARA_ALLOCATOR.take_object::<String>(raw_ptr)
                 } else {
-                    panic!("Pointer of constructor result is null")
+                    panic!("Pointer of method result is null")
                 }
             }
             else {
@@ -180,57 +822,139 @@ impl TestStruct {
             }
         }
     }
-	pub fn get_id(&self) -> u64 {
-		unsafe {
-			let raw_ptr: *mut u64 = TestStruct_id_getter.unwrap()(self.ptr) as *mut u64;
-			if !raw_ptr.is_null() {
-				*Box::from_raw(raw_ptr)
-			} else {
-				panic!("Pointer of gotten property is null")
-			}
-		}
-	}
-	pub fn set_id(&mut self, value: u64) {
-		unsafe { TestStruct_id_setter.unwrap()(self.ptr, &value as *const u64 as Ptr); }
-	}
 }
 
-impl Drop for TestStruct {
+impl Drop for MyStruct {
 	fn drop(&mut self) {
 		unsafe {
-			TestStruct_dtor.expect("Destructor wasn't loaded from library")(self.ptr);
-		}
-	}
-}
-
-impl Default for TestStruct {
-	fn default() -> Self {
-		unsafe {
-			TestStruct::test_empty_ctor()
+			MyStruct_dtor.expect("Destructor wasn't loaded from library")(self.ptr);
 		}
 	}
 }
 
 
-pub fn load_mylib(ctx: &Context) {
+pub fn load_mylib(ctx: &Context) -> Result<(), TangaraError> {
 	unsafe {
-		let mylib_package = ctx.get_package(14252210530948059848);
-		let EnumTuple_type = mylib_package.get_type(5703501090477233855);
-		let EnumStruct_type = mylib_package.get_type(4061653529057324328);
-		let EnumMixed_type = mylib_package.get_type(6533684593556827468);
-		let EnumComplex_type = mylib_package.get_type(5514888211111417365);
-		let MyStruct_type = mylib_package.get_type(11184697179514631841);
-		MyStruct_ctor0 = Some(MyStruct_type.get_ctor(0).clone());
-		MyStruct_repeat_name = Some(MyStruct_type.get_method(17567713076779176127).clone());
-		MyStruct_set_name = Some(MyStruct_type.get_method(1641961565049420977).clone());
-		MyStruct_get_name = Some(MyStruct_type.get_method(552281434682100053).clone());
-		MyStruct_dtor = Some(MyStruct_type.get_dtor());
-		let TestStruct_type = mylib_package.get_type(5562349104188291914);
-		TestStruct_ctor0 = Some(TestStruct_type.get_ctor(0).clone());
-		TestStruct_ctor1 = Some(TestStruct_type.get_ctor(1).clone());
-		let TestStruct_id_prop = TestStruct_type.get_property(5824848936401749885);
+		let mylib_package = ctx.try_get_package(14252210530948059848)?;
+		TANGARA_ALLOCATOR = ctx.get_allocator();
+		mylib_package.check_compatibility(Version::new(0, 1, 0), 15907298558750183676)?;
+		let EnumUnit_type = mylib_package.try_get_type(7402352672274323071)?;
+		EnumUnit_variants = Some(*EnumUnit_type.try_get_variants()?);
+		EnumUnit_dtor = Some(EnumUnit_type.try_get_dtor()?);
+		EnumUnit_Variant = Some(EnumUnit_type.try_get_method(837492378273562681)?.clone());
+		let EnumTuple_type = mylib_package.try_get_type(5703501090477233855)?;
+		EnumTuple_variants = Some(*EnumTuple_type.try_get_variants()?);
+		EnumTuple_dtor = Some(EnumTuple_type.try_get_dtor()?);
+		EnumTuple_Variant = Some(EnumTuple_type.try_get_method(837492378273562681)?.clone());
+		let EnumStruct_type = mylib_package.try_get_type(4061653529057324328)?;
+		EnumStruct_variants = Some(*EnumStruct_type.try_get_variants()?);
+		EnumStruct_dtor = Some(EnumStruct_type.try_get_dtor()?);
+		EnumStruct_Variant = Some(EnumStruct_type.try_get_method(837492378273562681)?.clone());
+		let EnumMixed_type = mylib_package.try_get_type(6533684593556827468)?;
+		EnumMixed_variants = Some(*EnumMixed_type.try_get_variants()?);
+		EnumMixed_dtor = Some(EnumMixed_type.try_get_dtor()?);
+		EnumMixed_Unit = Some(EnumMixed_type.try_get_method(9260626685794967516)?.clone());
+		EnumMixed_Tuple = Some(EnumMixed_type.try_get_method(8975276260061643599)?.clone());
+		let EnumComplex_type = mylib_package.try_get_type(5514888211111417365)?;
+		EnumComplex_variants = Some(*EnumComplex_type.try_get_variants()?);
+		EnumComplex_dtor = Some(EnumComplex_type.try_get_dtor()?);
+		EnumComplex_Unit = Some(EnumComplex_type.try_get_method(9260626685794967516)?.clone());
+		EnumComplex_Tuple = Some(EnumComplex_type.try_get_method(8975276260061643599)?.clone());
+		EnumComplex_Struct = Some(EnumComplex_type.try_get_method(12225383099421259715)?.clone());
+		let TestStruct_type = mylib_package.try_get_type(5562349104188291914)?;
+		TestStruct_ctor0 = Some(TestStruct_type.try_get_ctor(0)?.clone());
+		TestStruct_ctor1 = Some(TestStruct_type.try_get_ctor(1)?.clone());
+		let TestStruct_reversed_id_prop = TestStruct_type.try_get_property(469904608152883657)?;
+		TestStruct_reversed_id_getter = Some(TestStruct_reversed_id_prop.getter);
+		TestStruct_reversed_id_setter = Some(TestStruct_reversed_id_prop.setter.unwrap());
+		let TestStruct_id_prop = TestStruct_type.try_get_property(5824848936401749885)?;
 		TestStruct_id_getter = Some(TestStruct_id_prop.getter);
 		TestStruct_id_setter = Some(TestStruct_id_prop.setter.unwrap());
-		TestStruct_dtor = Some(TestStruct_type.get_dtor());
+		let TestStruct_max_id_static = TestStruct_type.try_get_static(9617686031262059114)?;
+		TestStruct_max_id_static_getter = Some(TestStruct_max_id_static.getter);
+		let TestStruct_name_static = TestStruct_type.try_get_static(286033042563501636)?;
+		TestStruct_name_static_getter = Some(TestStruct_name_static.getter);
+		TestStruct_add_ids = Some(TestStruct_type.try_get_method(4404243644579629786)?.clone());
+		TestStruct_set_pair = Some(TestStruct_type.try_get_method(13996285759242225994)?.clone());
+		TestStruct_set_bytes = Some(TestStruct_type.try_get_method(45789680781445359)?.clone());
+		TestStruct_map_id = Some(TestStruct_type.try_get_method(94426053285291843)?.clone());
+		TestStruct_set_or_default = Some(TestStruct_type.try_get_method(675139452480418901)?.clone());
+		TestStruct_add_len_u8 = Some(TestStruct_type.try_get_method(10454108122437039164)?.clone());
+		TestStruct_add_len_string = Some(TestStruct_type.try_get_method(8115058914181328304)?.clone());
+		TestStruct_dtor = Some(TestStruct_type.try_get_dtor()?);
+		let GenericsTestMyStruct_type = mylib_package.try_get_type(7052219503811506409)?;
+		GenericsTestMyStruct_ctor0 = Some(GenericsTestMyStruct_type.try_get_ctor(0)?.clone());
+		GenericsTestMyStruct_set_t = Some(GenericsTestMyStruct_type.try_get_method(984204454997340031)?.clone());
+		GenericsTestMyStruct_print_t_bar = Some(GenericsTestMyStruct_type.try_get_method(10467088130972289378)?.clone());
+		GenericsTestMyStruct_dtor = Some(GenericsTestMyStruct_type.try_get_dtor()?);
+		let MyStruct_type = mylib_package.try_get_type(11184697179514631841)?;
+		MyStruct_ctor0 = Some(MyStruct_type.try_get_ctor(0)?.clone());
+		MyStruct_repeat_name = Some(MyStruct_type.try_get_method(17567713076779176127)?.clone());
+		MyStruct_set_name = Some(MyStruct_type.try_get_method(1641961565049420977)?.clone());
+		MyStruct_get_name = Some(MyStruct_type.try_get_method(552281434682100053)?.clone());
+		MyStruct_greet_host = Some(MyStruct_type.try_get_method(17123653915292042787)?.clone());
+		let MyStruct_MyTrait_vtable = MyStruct_type.try_get_interface(1235733093494375210)?;
+		MyStruct_MyTrait_foo = Some(MyStruct_MyTrait_vtable.try_get_method(13574490469189226774)?.clone());
+		MyStruct_MyTrait_bar = Some(MyStruct_MyTrait_vtable.try_get_method(10297498164471647047)?.clone());
+		MyStruct_dtor = Some(MyStruct_type.try_get_dtor()?);
 	}
+	Ok(())
+}
+
+/// Forget all functions loaded by `load_mylib`. Call it before library is unloaded.
+pub fn unload_mylib() {
+	unsafe {
+		EnumUnit_variants = None;
+		EnumUnit_dtor = None;
+		EnumUnit_Variant = None;
+		EnumTuple_variants = None;
+		EnumTuple_dtor = None;
+		EnumTuple_Variant = None;
+		EnumStruct_variants = None;
+		EnumStruct_dtor = None;
+		EnumStruct_Variant = None;
+		EnumMixed_variants = None;
+		EnumMixed_dtor = None;
+		EnumMixed_Unit = None;
+		EnumMixed_Tuple = None;
+		EnumComplex_variants = None;
+		EnumComplex_dtor = None;
+		EnumComplex_Unit = None;
+		EnumComplex_Tuple = None;
+		EnumComplex_Struct = None;
+		TestStruct_ctor0 = None;
+		TestStruct_ctor1 = None;
+		TestStruct_reversed_id_getter = None;
+		TestStruct_reversed_id_setter = None;
+		TestStruct_id_getter = None;
+		TestStruct_id_setter = None;
+		TestStruct_max_id_static_getter = None;
+		TestStruct_name_static_getter = None;
+		TestStruct_add_ids = None;
+		TestStruct_set_pair = None;
+		TestStruct_set_bytes = None;
+		TestStruct_map_id = None;
+		TestStruct_set_or_default = None;
+		TestStruct_add_len_u8 = None;
+		TestStruct_add_len_string = None;
+		TestStruct_dtor = None;
+		GenericsTestMyStruct_ctor0 = None;
+		GenericsTestMyStruct_set_t = None;
+		GenericsTestMyStruct_print_t_bar = None;
+		GenericsTestMyStruct_dtor = None;
+		MyStruct_ctor0 = None;
+		MyStruct_repeat_name = None;
+		MyStruct_set_name = None;
+		MyStruct_get_name = None;
+		MyStruct_greet_host = None;
+		MyStruct_MyTrait_foo = None;
+		MyStruct_MyTrait_bar = None;
+		MyStruct_dtor = None;
+	}
+}
+
+/// Refresh all functions from context after library was reloaded
+pub fn reload_mylib(ctx: &Context) -> Result<(), TangaraError> {
+	unload_mylib();
+	load_mylib(ctx)
 }
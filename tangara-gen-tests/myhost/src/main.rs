@@ -1,32 +1,97 @@
-use libloading::Symbol;
-use tangara::context::Context;
+use tangara::metadata::read_library_metadata;
 use tangara::runtime::Runtime;
-use crate::bindings::{load_mylib, MyStruct, TestStruct};
+use crate::bindings::{load_mylib, register_my_trait, EnumComplex, EnumComplexRef, EnumUnit, GenericsTestMyStruct, MyStruct, MyTrait, TestStruct};
 
 mod bindings;
 
+fn double_id(id: u64) -> u64 {
+    id * 2
+}
+
+/// Implementation of library's interface which library calls back
+struct Greeter {
+    name: String
+}
+
+impl MyTrait for Greeter {
+    fn foo(&mut self, a: String) {
+        self.name = a;
+    }
+
+    fn bar(&self) -> String {
+        format!("Hello from host, {}!", self.name)
+    }
+}
+
 fn main() {
+    // runtime owns loaded dynamic library and keeps it loaded while its packages are used
+    let runtime = Runtime::new();
+    // implementations of interfaces for library are registered before loading it
+    runtime.register(|ctx| register_my_trait(ctx, Greeter { name: String::new() }));
     unsafe {
-        // load dynamic library and tangara functions from them
-        let lib = libloading::Library::new("mylib").expect("Loading mylib dynamic library gets errors");
-        let tgload: Symbol<unsafe extern fn(&mut Context)> = lib.get(b"tgLoad").unwrap();
-        {
-            // bindings can works further even without keeping tangara context in memory
-            let mut runtime = Runtime::new(); // runtime need to be more enhanced to support custom allocators and etc.
-            let mut context = runtime.use_context();
-            tgload(&mut context); // load lib from dll to context
-            load_mylib(&context); // load bindings from context
+        runtime.load_library("mylib").expect("Loading mylib dynamic library gets errors");
+    }
+    // library describes itself, so no mylib.tgjson is needed at runtime
+    let metadata = unsafe { read_library_metadata("mylib") }.expect("Reading mylib metadata gets errors");
+    println!("Loaded {} v{} with {} types", metadata.name, metadata.version, metadata.types.len());
+    // load bindings from published context
+    if let Err(err) = load_mylib(&runtime.publish()) {
+        panic!("Failed to load mylib bindings: {err}");
+    }
+    // use bindings
+    // Note: don't drop runtime before this code because lib gets unloaded and
+    // every function pointer in bindings become invalid
+    let mut test = TestStruct::new(64);
+    println!("Id #1: {}", test.get_id());
+    test.set_id(164);
+    println!("Id #2: {}", test.get_id());
+    // arguments of generic, tuple, array and function types
+    test.add_ids(vec![1, 2, 3]);
+    println!("Id #3: {}", test.get_id());
+    test.set_pair((1, 2));
+    println!("Id #4: {}", test.get_id());
+    test.set_bytes([5, 0, 0, 0, 0, 0, 0, 0]);
+    test.map_id(double_id);
+    println!("Id #5: {}", test.get_id());
+    test.set_or_default(None);
+    println!("Id #6: {}", test.get_id());
+    // generic method is called through its instantiations
+    test.add_len_u8(vec![1, 2, 3]);
+    test.add_len_string(vec!["Tangara".to_string()]);
+    println!("Id #7: {}", test.get_id());
+    // property made from accessors marked by #[tangara(property)]
+    test.set_reversed_id(990);
+    println!("Id #8: {} (reversed {})", test.get_id(), test.get_reversed_id());
+    // associated constants are read by static properties
+    println!("{} ids are less than {}", TestStruct::get_name(), TestStruct::get_max_id());
+    // enums are copied into library objects and back
+    for value in [EnumComplex::Unit, EnumComplex::Tuple(42), EnumComplex::Struct { a: 7 }] {
+        let object = value.into_ptr();
+        // library object can be matched without copying it
+        match unsafe { EnumComplexRef::from_ptr(object) } {
+            EnumComplexRef::Unit => println!("EnumComplex::Unit"),
+            EnumComplexRef::Tuple(value) => println!("EnumComplex::Tuple({value})"),
+            EnumComplexRef::Struct { a } => println!("EnumComplex::Struct {{ a: {a} }}")
+        }
+        if let EnumComplex::Tuple(value) = unsafe { EnumComplex::from_ptr(object) } {
+            println!("Copied EnumComplex::Tuple({value})");
         }
-        // use bindings
-        // Note: don't move this code out of unsafe because lib gets unloaded and
-        // every function pointer in bindings become invalid
-        let mut test = TestStruct::new(64);
-        println!("Id #1: {}", test.get_id());
-        test.set_id(164);
-        println!("Id #2: {}", test.get_id());
-        let mut snaulx = MyStruct::new("snaulX");
-        println!("{} is author of this library", snaulx.get_name());
-        snaulx.set_name("https://github.com/snaulX");
-        snaulx.repeat_name(5);
+        unsafe { EnumComplex::delete_ptr(object) };
     }
+    let object = EnumUnit::Variant.into_ptr();
+    println!("{:?}", unsafe { EnumUnit::from_ptr(object) });
+    unsafe { EnumUnit::delete_ptr(object) };
+    // generic type is used through its instantiation declared in build script
+    let generics_test = GenericsTestMyStruct::new();
+    generics_test.print_t_bar();
+    let mut snaulx = MyStruct::new("snaulX");
+    println!("{} is author of this library", snaulx.get_name());
+    snaulx.set_name("https://github.com/snaulX");
+    snaulx.repeat_name(5);
+    // library calls methods of host's implementation
+    println!("{}", snaulx.greet_host());
+    // library object is used through interface implemented by its type
+    let mut object: Box<dyn MyTrait> = Box::new(snaulx);
+    object.foo("Tangara".to_string());
+    println!("{} is called through MyTrait", object.bar());
 }
@@ -1,16 +1,10 @@
 use tangara_gen::*;
-use tangara_highlevel::Package;
+use tangara_highlevel::{Package, TypeRef};
+use tangara_highlevel::tangara_std::TANGARA_STD;
+use tangara_highlevel::universe::Universe;
+use tangara_highlevel::validation::validate;
 
 fn main() {
-    // generate Tangara reflection data for this lib
-    let mut config = PkgGenConfig::default();
-    config.ctor_names.push("test_empty_ctor".to_string());
-    let pkg = PackageGenerator::new("mylib", config)
-        .parse_file("src/lib.rs")
-        .generate();
-    let pkg_json = serde_json::to_string_pretty(&pkg).expect("Convert tangara package to json");
-    std::fs::write("../mylib.tgjson", pkg_json).expect("Error with writing to mylib.tgjson");
-
     // generate Tangara reflection data for.. Tangara
     let tangara = PackageGenerator::new("tangara", PkgGenConfig::default())
         .parse_file("../../tangara/src/lib.rs")
@@ -22,11 +16,31 @@ fn main() {
     let tg_json = serde_json::to_string_pretty(&tangara).expect("Convert tangara package to json");
     std::fs::write("../tangara.tgjson", tg_json).expect("Error with writing to tangara.tgjson");
 
-    // generate 'tgLoad' dll entrypoint for this lib
+    // generate Tangara reflection data for this lib, it can reference types of tangara
+    let mut config = PkgGenConfig::default();
+    config.method_instances.insert("TestStruct::add_len".to_string(), vec!["u8".to_string(), "String".to_string()]);
+    let pkg = PackageGenerator::new("mylib", config)
+        .set_version(&std::env::var("CARGO_PKG_VERSION").unwrap())
+        .add_dependency(&tangara)
+        .parse_file("src/lib.rs")
+        .generate();
+    let universe = Universe::load(vec![TANGARA_STD.get_package(), RUST_STD_LIB.get_package(), tangara, pkg.clone()])
+        .expect("Dependencies of mylib are inconsistent");
+    let deps = universe.get_dependencies(pkg.id).into_iter().cloned().collect::<Vec<Package>>();
+    for diagnostic in validate(&pkg, &deps) {
+        println!("cargo:warning={diagnostic}");
+    }
+    let pkg_json = serde_json::to_string_pretty(&pkg).expect("Convert tangara package to json");
+    std::fs::write("../mylib.tgjson", pkg_json).expect("Error with writing to mylib.tgjson");
+
+    // generate 'tgLoad' dll entrypoint for this lib with embedded metadata
     let p: Package = serde_json::from_str(&std::fs::read_to_string("../mylib.tgjson").unwrap()).unwrap();
-    RustGenerator::new(p, RustGenConfig::default())
+    let mut config = RustGenConfig::default();
+    config.instances.push(TypeRef::Generic(Box::new(TypeRef::from("mylib.GenericsTest")), vec![TypeRef::from("mylib.MyStruct")]));
+    RustGenerator::new(p, config)
         .generate_entrypoint()
+        .embed_metadata()
         .custom_use("crate::*")
         .write_to("src/bindings.rs")
         .unwrap();
-}
\ No newline at end of file
+}
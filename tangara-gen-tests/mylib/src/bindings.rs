@@ -1,14 +1,100 @@
 // This file was generated by tangara-gen
 // All changes in this file will discard after rebuilding project
 use std::ptr;
-use std::alloc::{dealloc, Layout};
-use tangara::context::{Context, Ptr, Property};
+use tangara::allocator::Allocator;
+use tangara::context::{Context, Implementation, Ptr, Property, StaticProperty, Variants, Version};
+
+/// Allocator of context which loaded this library
+static mut TANGARA_ALLOCATOR: Allocator = Allocator::SYSTEM;
 use crate::*;
+use tangara::metadata::Metadata;
+
+/// Implementation of MyTrait registered by host
+static MyTrait_host: std::sync::RwLock<Option<std::sync::Arc<Implementation>>> = std::sync::RwLock::new(None);
+
+pub struct MyTraitHost {
+    implementation: std::sync::Arc<Implementation>
+}
+
+impl MyTraitHost {
+    /// Get implementation of MyTrait registered by host as trait object or `None` if host didn't register it
+    pub fn get() -> Option<Box<dyn MyTrait>> {
+        let implementation = MyTrait_host.read().unwrap_or_else(|err| err.into_inner()).clone()?;
+        Some(Box::new(Self { implementation }))
+    }
+}
+
+impl MyTrait for MyTraitHost {
+    fn foo(&mut self, a: String) {
+        unsafe {
+            let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<String>();
+            let mut args_buf = vec![0u8; args_size];
+            let args_ptr = args_buf.as_mut_ptr();
+            *(args_ptr as *mut Ptr) = self.implementation.get_data();
+            ptr::write(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut String, a);
+            let method = self.implementation.get_vtable().get_method(13574490469189226774);
+            method(args_size, args_ptr);
+        }
+    }
+    fn bar(&self) -> String {
+        unsafe {
+            let args_size = std::mem::size_of::<Ptr>();
+            let mut args_buf = vec![0u8; args_size];
+            let args_ptr = args_buf.as_mut_ptr();
+            *(args_ptr as *mut Ptr) = self.implementation.get_data();
+            let method = self.implementation.get_vtable().get_method(10297498164471647047);
+            TANGARA_ALLOCATOR.take_object::<String>(method(args_size, args_ptr))
+        }
+    }
+}
+
+pub extern "C" fn EnumUnit_dtor(value: Ptr) {
+    unsafe {
+        TANGARA_ALLOCATOR.delete_object::<EnumUnit>(value);
+    }
+}
+
+pub extern "C" fn EnumUnit_variant_tag(this: Ptr) -> u64 {
+    unsafe {
+        let this: *const EnumUnit = this as *const EnumUnit;
+        match &*this {
+            EnumUnit::Variant => 837492378273562681,
+        }
+    }
+}
+
+pub extern "C" fn EnumUnit_variant_field(_: Ptr, _: u64) -> Ptr {
+    ptr::null_mut()
+}
+
+pub extern "C" fn EnumUnit_Variant(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        TANGARA_ALLOCATOR.new_object(EnumUnit::Variant)
+    }
+}
 
 pub extern "C" fn EnumTuple_dtor(value: Ptr) {
     unsafe {
-        ptr::drop_in_place(value);
-        dealloc(value, Layout::new::<EnumTuple>());
+        TANGARA_ALLOCATOR.delete_object::<EnumTuple>(value);
+    }
+}
+
+pub extern "C" fn EnumTuple_variant_tag(this: Ptr) -> u64 {
+    unsafe {
+        let this: *const EnumTuple = this as *const EnumTuple;
+        match &*this {
+            EnumTuple::Variant(_) => 837492378273562681,
+        }
+    }
+}
+
+pub extern "C" fn EnumTuple_variant_field(this: Ptr, id: u64) -> Ptr {
+    unsafe {
+        let this: *const EnumTuple = this as *const EnumTuple;
+        match (&*this, id) {
+            (EnumTuple::Variant(field0), 14300287532767007308) => field0 as *const i32 as Ptr,
+            _ => ptr::null_mut()
+        }
     }
 }
 
@@ -18,15 +104,32 @@ pub extern "C" fn EnumTuple_Variant(args_size: usize, args: *mut u8) -> Ptr {
         let mut args_ptr = args_slice.as_mut_ptr();
         let field0: i32 = ptr::read(args_ptr as *const i32);
         args_ptr = args_ptr.add(std::mem::size_of::<i32>());
-        let return_value = Box::new(EnumTuple::Variant(field0));
-		Box::into_raw(return_value) as Ptr
+        TANGARA_ALLOCATOR.new_object(EnumTuple::Variant(field0))
     }
 }
 
 pub extern "C" fn EnumStruct_dtor(value: Ptr) {
     unsafe {
-        ptr::drop_in_place(value);
-        dealloc(value, Layout::new::<EnumStruct>());
+        TANGARA_ALLOCATOR.delete_object::<EnumStruct>(value);
+    }
+}
+
+pub extern "C" fn EnumStruct_variant_tag(this: Ptr) -> u64 {
+    unsafe {
+        let this: *const EnumStruct = this as *const EnumStruct;
+        match &*this {
+            EnumStruct::Variant { .. } => 837492378273562681,
+        }
+    }
+}
+
+pub extern "C" fn EnumStruct_variant_field(this: Ptr, id: u64) -> Ptr {
+    unsafe {
+        let this: *const EnumStruct = this as *const EnumStruct;
+        match (&*this, id) {
+            (EnumStruct::Variant { a, .. }, 16589621723077738341) => a as *const i32 as Ptr,
+            _ => ptr::null_mut()
+        }
     }
 }
 
@@ -36,22 +139,39 @@ pub extern "C" fn EnumStruct_Variant(args_size: usize, args: *mut u8) -> Ptr {
         let mut args_ptr = args_slice.as_mut_ptr();
         let a: i32 = ptr::read(args_ptr as *const i32);
         args_ptr = args_ptr.add(std::mem::size_of::<i32>());
-        let return_value = Box::new(EnumStruct::Variant { a });
-		Box::into_raw(return_value) as Ptr
+        TANGARA_ALLOCATOR.new_object(EnumStruct::Variant { a })
     }
 }
 
 pub extern "C" fn EnumMixed_dtor(value: Ptr) {
     unsafe {
-        ptr::drop_in_place(value);
-        dealloc(value, Layout::new::<EnumMixed>());
+        TANGARA_ALLOCATOR.delete_object::<EnumMixed>(value);
+    }
+}
+
+pub extern "C" fn EnumMixed_variant_tag(this: Ptr) -> u64 {
+    unsafe {
+        let this: *const EnumMixed = this as *const EnumMixed;
+        match &*this {
+            EnumMixed::Unit => 9260626685794967516,
+            EnumMixed::Tuple(_) => 8975276260061643599,
+        }
+    }
+}
+
+pub extern "C" fn EnumMixed_variant_field(this: Ptr, id: u64) -> Ptr {
+    unsafe {
+        let this: *const EnumMixed = this as *const EnumMixed;
+        match (&*this, id) {
+            (EnumMixed::Tuple(field0), 6045555171727804651) => field0 as *const i32 as Ptr,
+            _ => ptr::null_mut()
+        }
     }
 }
 
 pub extern "C" fn EnumMixed_Unit(args_size: usize, args: *mut u8) -> Ptr {
     unsafe {
-        let return_value = Box::new(EnumMixed::Unit);
-		Box::into_raw(return_value) as Ptr
+        TANGARA_ALLOCATOR.new_object(EnumMixed::Unit)
     }
 }
 
@@ -61,22 +181,41 @@ pub extern "C" fn EnumMixed_Tuple(args_size: usize, args: *mut u8) -> Ptr {
         let mut args_ptr = args_slice.as_mut_ptr();
         let field0: i32 = ptr::read(args_ptr as *const i32);
         args_ptr = args_ptr.add(std::mem::size_of::<i32>());
-        let return_value = Box::new(EnumMixed::Tuple(field0));
-		Box::into_raw(return_value) as Ptr
+        TANGARA_ALLOCATOR.new_object(EnumMixed::Tuple(field0))
     }
 }
 
 pub extern "C" fn EnumComplex_dtor(value: Ptr) {
     unsafe {
-        ptr::drop_in_place(value);
-        dealloc(value, Layout::new::<EnumComplex>());
+        TANGARA_ALLOCATOR.delete_object::<EnumComplex>(value);
+    }
+}
+
+pub extern "C" fn EnumComplex_variant_tag(this: Ptr) -> u64 {
+    unsafe {
+        let this: *const EnumComplex = this as *const EnumComplex;
+        match &*this {
+            EnumComplex::Unit => 9260626685794967516,
+            EnumComplex::Tuple(_) => 8975276260061643599,
+            EnumComplex::Struct { .. } => 12225383099421259715,
+        }
+    }
+}
+
+pub extern "C" fn EnumComplex_variant_field(this: Ptr, id: u64) -> Ptr {
+    unsafe {
+        let this: *const EnumComplex = this as *const EnumComplex;
+        match (&*this, id) {
+            (EnumComplex::Tuple(field0), 6045555171727804651) => field0 as *const i32 as Ptr,
+            (EnumComplex::Struct { a, .. }, 12537007794890514693) => a as *const i32 as Ptr,
+            _ => ptr::null_mut()
+        }
     }
 }
 
 pub extern "C" fn EnumComplex_Unit(args_size: usize, args: *mut u8) -> Ptr {
     unsafe {
-        let return_value = Box::new(EnumComplex::Unit);
-		Box::into_raw(return_value) as Ptr
+        TANGARA_ALLOCATOR.new_object(EnumComplex::Unit)
     }
 }
 
@@ -86,8 +225,7 @@ pub extern "C" fn EnumComplex_Tuple(args_size: usize, args: *mut u8) -> Ptr {
         let mut args_ptr = args_slice.as_mut_ptr();
         let field0: i32 = ptr::read(args_ptr as *const i32);
         args_ptr = args_ptr.add(std::mem::size_of::<i32>());
-        let return_value = Box::new(EnumComplex::Tuple(field0));
-		Box::into_raw(return_value) as Ptr
+        TANGARA_ALLOCATOR.new_object(EnumComplex::Tuple(field0))
     }
 }
 
@@ -97,15 +235,206 @@ pub extern "C" fn EnumComplex_Struct(args_size: usize, args: *mut u8) -> Ptr {
         let mut args_ptr = args_slice.as_mut_ptr();
         let a: i32 = ptr::read(args_ptr as *const i32);
         args_ptr = args_ptr.add(std::mem::size_of::<i32>());
-        let return_value = Box::new(EnumComplex::Struct { a });
-		Box::into_raw(return_value) as Ptr
+        TANGARA_ALLOCATOR.new_object(EnumComplex::Struct { a })
+    }
+}
+
+pub extern "C" fn TestStruct_dtor(value: Ptr) {
+    unsafe {
+        TANGARA_ALLOCATOR.delete_object::<TestStruct>(value);
+    }
+}
+
+pub extern "C" fn TestStruct_ctor0(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        TANGARA_ALLOCATOR.new_object(TestStruct::test_empty_ctor())
+    }
+}
+
+pub extern "C" fn TestStruct_ctor1(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let id: u64 = ptr::read(args_ptr as *const u64);
+        args_ptr = args_ptr.add(std::mem::size_of::<u64>());
+        TANGARA_ALLOCATOR.new_object(TestStruct::new(id))
+    }
+}
+
+pub extern "C" fn TestStruct_get_reverse(this: Ptr) -> Ptr {
+    unsafe {
+        let this: *const TestStruct = this as *const TestStruct;
+        TANGARA_ALLOCATOR.new_object((*this).get_reverse())
+    }
+}
+
+pub extern "C" fn TestStruct_set_reverse(this: Ptr, object: Ptr) {
+    unsafe {
+        let this: *mut TestStruct = this as *mut TestStruct;
+        let reverse: u64 = ptr::read(object as *const u64);
+        (*this).set_reverse(reverse);
+    }
+}
+
+pub extern "C" fn TestStruct_get_id(this: Ptr) -> Ptr {
+    unsafe {
+        let this: *const TestStruct = this as *const TestStruct;
+        TANGARA_ALLOCATOR.new_object((*this).id)
+    }
+}
+
+pub extern "C" fn TestStruct_set_id(this: Ptr, object: Ptr) {
+    unsafe {
+        let this: *mut TestStruct = this as *mut TestStruct;
+        let id: u64 = ptr::read(object as *const u64);
+        (*this).id = id;
+    }
+}
+
+pub extern "C" fn TestStruct_get_static_MAX_ID() -> Ptr {
+    unsafe {
+        TANGARA_ALLOCATOR.new_object(TestStruct::MAX_ID)
+    }
+}
+
+pub extern "C" fn TestStruct_get_static_NAME() -> Ptr {
+    unsafe {
+        TANGARA_ALLOCATOR.new_object(TestStruct::NAME)
+    }
+}
+
+pub extern "C" fn TestStruct_add_ids(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *mut TestStruct = *(args_ptr as *mut Ptr) as *mut TestStruct;
+        args_ptr = args_ptr.add(std::mem::size_of::<*mut TestStruct>());
+        let ids: Vec<u64> = ptr::read(args_ptr as *const Vec<u64>);
+        args_ptr = args_ptr.add(std::mem::size_of::<Vec<u64>>());
+        (*this).add_ids(ids);
+		ptr::null_mut()
+    }
+}
+
+pub extern "C" fn TestStruct_set_pair(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *mut TestStruct = *(args_ptr as *mut Ptr) as *mut TestStruct;
+        args_ptr = args_ptr.add(std::mem::size_of::<*mut TestStruct>());
+        let pair: (u32, u32) = ptr::read(args_ptr as *const (u32, u32));
+        args_ptr = args_ptr.add(std::mem::size_of::<(u32, u32)>());
+        (*this).set_pair(pair);
+		ptr::null_mut()
+    }
+}
+
+pub extern "C" fn TestStruct_set_bytes(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *mut TestStruct = *(args_ptr as *mut Ptr) as *mut TestStruct;
+        args_ptr = args_ptr.add(std::mem::size_of::<*mut TestStruct>());
+        let bytes: [u8; 8] = ptr::read(args_ptr as *const [u8; 8]);
+        args_ptr = args_ptr.add(std::mem::size_of::<[u8; 8]>());
+        (*this).set_bytes(bytes);
+		ptr::null_mut()
+    }
+}
+
+pub extern "C" fn TestStruct_map_id(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *mut TestStruct = *(args_ptr as *mut Ptr) as *mut TestStruct;
+        args_ptr = args_ptr.add(std::mem::size_of::<*mut TestStruct>());
+        let f: fn(u64) -> u64 = ptr::read(args_ptr as *const fn(u64) -> u64);
+        args_ptr = args_ptr.add(std::mem::size_of::<fn(u64) -> u64>());
+        (*this).map_id(f);
+		ptr::null_mut()
+    }
+}
+
+pub extern "C" fn TestStruct_set_or_default(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *mut TestStruct = *(args_ptr as *mut Ptr) as *mut TestStruct;
+        args_ptr = args_ptr.add(std::mem::size_of::<*mut TestStruct>());
+        let id: Option<u64> = ptr::read(args_ptr as *const Option<u64>);
+        args_ptr = args_ptr.add(std::mem::size_of::<Option<u64>>());
+        (*this).set_or_default(id);
+		ptr::null_mut()
+    }
+}
+
+pub extern "C" fn TestStruct_add_len_u8(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *mut TestStruct = *(args_ptr as *mut Ptr) as *mut TestStruct;
+        args_ptr = args_ptr.add(std::mem::size_of::<*mut TestStruct>());
+        let items: Vec<u8> = ptr::read(args_ptr as *const Vec<u8>);
+        args_ptr = args_ptr.add(std::mem::size_of::<Vec<u8>>());
+        (*this).add_len::<u8>(items);
+		ptr::null_mut()
+    }
+}
+
+pub extern "C" fn TestStruct_add_len_string(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *mut TestStruct = *(args_ptr as *mut Ptr) as *mut TestStruct;
+        args_ptr = args_ptr.add(std::mem::size_of::<*mut TestStruct>());
+        let items: Vec<String> = ptr::read(args_ptr as *const Vec<String>);
+        args_ptr = args_ptr.add(std::mem::size_of::<Vec<String>>());
+        (*this).add_len::<String>(items);
+		ptr::null_mut()
+    }
+}
+
+pub type GenericsTestMyStruct = GenericsTest<MyStruct>;
+
+pub extern "C" fn GenericsTestMyStruct_dtor(value: Ptr) {
+    unsafe {
+        TANGARA_ALLOCATOR.delete_object::<GenericsTestMyStruct>(value);
+    }
+}
+
+pub extern "C" fn GenericsTestMyStruct_ctor0(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        TANGARA_ALLOCATOR.new_object(GenericsTestMyStruct::new())
+    }
+}
+
+pub extern "C" fn GenericsTestMyStruct_set_t(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *mut GenericsTestMyStruct = *(args_ptr as *mut Ptr) as *mut GenericsTestMyStruct;
+        args_ptr = args_ptr.add(std::mem::size_of::<*mut GenericsTestMyStruct>());
+        let t: MyStruct = ptr::read(args_ptr as *const MyStruct);
+        args_ptr = args_ptr.add(std::mem::size_of::<MyStruct>());
+        (*this).set_t(t);
+		ptr::null_mut()
+    }
+}
+
+pub extern "C" fn GenericsTestMyStruct_print_t_bar(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *const GenericsTestMyStruct = *(args_ptr as *mut Ptr) as *const GenericsTestMyStruct;
+        args_ptr = args_ptr.add(std::mem::size_of::<*const GenericsTestMyStruct>());
+        (*this).print_t_bar();
+		ptr::null_mut()
     }
 }
 
 pub extern "C" fn MyStruct_dtor(value: Ptr) {
     unsafe {
-        ptr::drop_in_place(value);
-        dealloc(value, Layout::new::<MyStruct>());
+        TANGARA_ALLOCATOR.delete_object::<MyStruct>(value);
     }
 }
 
@@ -115,8 +444,7 @@ pub extern "C" fn MyStruct_ctor0(args_size: usize, args: *mut u8) -> Ptr {
         let mut args_ptr = args_slice.as_mut_ptr();
         let name: &str = ptr::read(args_ptr as *const &str);
         args_ptr = args_ptr.add(std::mem::size_of::<&str>());
-        let value = Box::new(MyStruct::new(name));
-        Box::into_raw(value) as Ptr
+        TANGARA_ALLOCATOR.new_object(MyStruct::new(name))
     }
 }
 
@@ -128,8 +456,7 @@ pub extern "C" fn MyStruct_repeat_name(args_size: usize, args: *mut u8) -> Ptr {
         args_ptr = args_ptr.add(std::mem::size_of::<*const MyStruct>());
         let times: u32 = ptr::read(args_ptr as *const u32);
         args_ptr = args_ptr.add(std::mem::size_of::<u32>());
-        let to_return = Box::new((*this).repeat_name(times));
-		Box::into_raw(to_return) as Ptr
+        TANGARA_ALLOCATOR.new_object((*this).repeat_name(times))
     }
 }
 
@@ -152,78 +479,175 @@ pub extern "C" fn MyStruct_get_name(args_size: usize, args: *mut u8) -> Ptr {
         let mut args_ptr = args_slice.as_mut_ptr();
         let this: *const MyStruct = *(args_ptr as *mut Ptr) as *const MyStruct;
         args_ptr = args_ptr.add(std::mem::size_of::<*const MyStruct>());
-        let to_return = Box::new((*this).get_name());
-		Box::into_raw(to_return) as Ptr
-    }
-}
-
-pub extern "C" fn TestStruct_dtor(value: Ptr) {
-    unsafe {
-        ptr::drop_in_place(value);
-        dealloc(value, Layout::new::<TestStruct>());
-    }
-}
-
-pub extern "C" fn TestStruct_ctor0(args_size: usize, args: *mut u8) -> Ptr {
-    unsafe {
-        let value = Box::new(TestStruct::test_empty_ctor());
-        Box::into_raw(value) as Ptr
+        TANGARA_ALLOCATOR.new_object((*this).get_name())
     }
 }
 
-pub extern "C" fn TestStruct_ctor1(args_size: usize, args: *mut u8) -> Ptr {
+pub extern "C" fn MyStruct_greet_host(args_size: usize, args: *mut u8) -> Ptr {
     unsafe {
         let args_slice = std::slice::from_raw_parts_mut(args, args_size);
         let mut args_ptr = args_slice.as_mut_ptr();
-        let id: u64 = ptr::read(args_ptr as *const u64);
-        args_ptr = args_ptr.add(std::mem::size_of::<u64>());
-        let value = Box::new(TestStruct::new(id));
-        Box::into_raw(value) as Ptr
+        let this: *const MyStruct = *(args_ptr as *mut Ptr) as *const MyStruct;
+        args_ptr = args_ptr.add(std::mem::size_of::<*const MyStruct>());
+        TANGARA_ALLOCATOR.new_object((*this).greet_host())
     }
 }
 
-pub extern "C" fn TestStruct_get_id(this: Ptr) -> Ptr {
+pub extern "C" fn MyStruct_MyTrait_foo(args_size: usize, args: *mut u8) -> Ptr {
     unsafe {
-        let this: *const TestStruct = this as *const TestStruct;
-        let to_return = Box::new((*this).id);
-        Box::into_raw(to_return) as Ptr
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *mut MyStruct = *(args_ptr as *mut Ptr) as *mut MyStruct;
+        args_ptr = args_ptr.add(std::mem::size_of::<*mut MyStruct>());
+        let a: String = ptr::read(args_ptr as *const String);
+        args_ptr = args_ptr.add(std::mem::size_of::<String>());
+        <MyStruct as MyTrait>::foo(&mut *this, a);
+		ptr::null_mut()
     }
 }
 
-pub extern "C" fn TestStruct_set_id(this: Ptr, object: Ptr) {
+pub extern "C" fn MyStruct_MyTrait_bar(args_size: usize, args: *mut u8) -> Ptr {
     unsafe {
-        let this: *mut TestStruct = this as *mut TestStruct;
-        let id: u64 = ptr::read(object as *const u64);
-        (*this).id = id;
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: *const MyStruct = *(args_ptr as *mut Ptr) as *const MyStruct;
+        args_ptr = args_ptr.add(std::mem::size_of::<*const MyStruct>());
+        TANGARA_ALLOCATOR.new_object(<MyStruct as MyTrait>::bar(&*this))
     }
 }
 #[no_mangle]
 pub extern "C" fn tgLoad(ctx: &mut Context) {
+	unsafe { TANGARA_ALLOCATOR = ctx.get_allocator(); }
+	*MyTrait_host.write().unwrap_or_else(|err| err.into_inner()) = ctx.try_get_implementation(1235733093494375210).ok().cloned();
 	let mut mylib_package = ctx.add_package(14252210530948059848);
+	mylib_package.set_version(Version::new(0, 1, 0));
+	mylib_package.set_abi(15907298558750183676);
+	let mut EnumUnit_type = mylib_package.add_type(7402352672274323071);
+	EnumUnit_type.set_dtor(EnumUnit_dtor);
+	EnumUnit_type.set_variants(Variants { tag: EnumUnit_variant_tag, field: EnumUnit_variant_field });
+	EnumUnit_type.add_method(837492378273562681, EnumUnit_Variant);
 	let mut EnumTuple_type = mylib_package.add_type(5703501090477233855);
 	EnumTuple_type.set_dtor(EnumTuple_dtor);
+	EnumTuple_type.set_variants(Variants { tag: EnumTuple_variant_tag, field: EnumTuple_variant_field });
 	EnumTuple_type.add_method(837492378273562681, EnumTuple_Variant);
 	let mut EnumStruct_type = mylib_package.add_type(4061653529057324328);
 	EnumStruct_type.set_dtor(EnumStruct_dtor);
+	EnumStruct_type.set_variants(Variants { tag: EnumStruct_variant_tag, field: EnumStruct_variant_field });
 	EnumStruct_type.add_method(837492378273562681, EnumStruct_Variant);
 	let mut EnumMixed_type = mylib_package.add_type(6533684593556827468);
 	EnumMixed_type.set_dtor(EnumMixed_dtor);
+	EnumMixed_type.set_variants(Variants { tag: EnumMixed_variant_tag, field: EnumMixed_variant_field });
 	EnumMixed_type.add_method(9260626685794967516, EnumMixed_Unit);
 	EnumMixed_type.add_method(8975276260061643599, EnumMixed_Tuple);
 	let mut EnumComplex_type = mylib_package.add_type(5514888211111417365);
 	EnumComplex_type.set_dtor(EnumComplex_dtor);
+	EnumComplex_type.set_variants(Variants { tag: EnumComplex_variant_tag, field: EnumComplex_variant_field });
 	EnumComplex_type.add_method(9260626685794967516, EnumComplex_Unit);
 	EnumComplex_type.add_method(8975276260061643599, EnumComplex_Tuple);
 	EnumComplex_type.add_method(12225383099421259715, EnumComplex_Struct);
+	let mut TestStruct_type = mylib_package.add_type(5562349104188291914);
+	TestStruct_type.set_dtor(TestStruct_dtor);
+	TestStruct_type.add_ctor(TestStruct_ctor0);
+	TestStruct_type.add_ctor(TestStruct_ctor1);
+	TestStruct_type.add_property(469904608152883657, Property { getter: TestStruct_get_reverse, setter: Some(TestStruct_set_reverse) });
+	TestStruct_type.add_property(5824848936401749885, Property { getter: TestStruct_get_id, setter: Some(TestStruct_set_id) });
+	TestStruct_type.add_static(9617686031262059114, StaticProperty { getter: TestStruct_get_static_MAX_ID, setter: None });
+	TestStruct_type.add_static(286033042563501636, StaticProperty { getter: TestStruct_get_static_NAME, setter: None });
+	TestStruct_type.add_method(4404243644579629786, TestStruct_add_ids);
+	TestStruct_type.add_method(13996285759242225994, TestStruct_set_pair);
+	TestStruct_type.add_method(45789680781445359, TestStruct_set_bytes);
+	TestStruct_type.add_method(94426053285291843, TestStruct_map_id);
+	TestStruct_type.add_method(675139452480418901, TestStruct_set_or_default);
+	TestStruct_type.add_method(10454108122437039164, TestStruct_add_len_u8);
+	TestStruct_type.add_method(8115058914181328304, TestStruct_add_len_string);
+	let mut GenericsTestMyStruct_type = mylib_package.add_instance(15422756983782746501, 7052219503811506409);
+	GenericsTestMyStruct_type.set_dtor(GenericsTestMyStruct_dtor);
+	GenericsTestMyStruct_type.add_ctor(GenericsTestMyStruct_ctor0);
+	GenericsTestMyStruct_type.add_method(984204454997340031, GenericsTestMyStruct_set_t);
+	GenericsTestMyStruct_type.add_method(10467088130972289378, GenericsTestMyStruct_print_t_bar);
 	let mut MyStruct_type = mylib_package.add_type(11184697179514631841);
 	MyStruct_type.set_dtor(MyStruct_dtor);
 	MyStruct_type.add_ctor(MyStruct_ctor0);
 	MyStruct_type.add_method(17567713076779176127, MyStruct_repeat_name);
 	MyStruct_type.add_method(1641961565049420977, MyStruct_set_name);
 	MyStruct_type.add_method(552281434682100053, MyStruct_get_name);
-	let mut TestStruct_type = mylib_package.add_type(5562349104188291914);
-	TestStruct_type.set_dtor(TestStruct_dtor);
-	TestStruct_type.add_ctor(TestStruct_ctor0);
-	TestStruct_type.add_ctor(TestStruct_ctor1);
-	TestStruct_type.add_property(5824848936401749885, Property { getter: TestStruct_get_id, setter: Some(TestStruct_set_id) });
+	MyStruct_type.add_method(17123653915292042787, MyStruct_greet_host);
+	let MyStruct_MyTrait_vtable = MyStruct_type.add_interface(1235733093494375210);
+	MyStruct_MyTrait_vtable.add_method(13574490469189226774, MyStruct_MyTrait_foo);
+	MyStruct_MyTrait_vtable.add_method(10297498164471647047, MyStruct_MyTrait_bar);
+}
+
+/// Package of this library in Tangara binary format
+static TANGARA_METADATA: &[u8] = b"TGPK\x02\x00F\x04Rust\x05mylib\x0bTangara\x2eStd\x0cT\
+	angara\x2eRust\x07tangara\x07MyTrait\x03foo\x06\
+	String\x01a\x03bar\x0aEnumStruct\x07Variant\x03\
+	i32\x0bEnumComplex\x04Unit\x05Tuple\x06field\
+	0\x06Struct\x0aTestStruct\x0ftest\x5fempty\x5fc\
+	tor\x03new\x03u64\x02id\x07reverse\x0breversed\x5f\
+	id\x06MAX\x5fID\x06static\x03str\x04NAME\x07add\x5fid\
+	s\x03Vec\x03ids\x08set\x5fpair\x03u32\x04pair\x09set\x5f\
+	bytes\x11Tangara\x2eStd\x2eArray\x02u8\x05bytes\
+	\x06map\x5fid\x01f\x0eset\x5for\x5fdefault\x12Tangara\
+	\x2eStd\x2eOption\x07add\x5flen\x01T\x05items\x0aadd\x5f\
+	len\x5fu8\x0eadd\x5flen\x5fstring\x09EnumTuple\x09\
+	EnumMixed\x08EnumUnit\x08BoxedStr\x03Box\x08\
+	MyStruct\x04name\x0brepeat\x5fname\x05times\x08\
+	set\x5fname\x01\x26\x08get\x5fname\x0agreet\x5fhost\x0cG\
+	enericsTest\x0asome\x5ffield\x05set\x5ft\x01t\x0bp\
+	rint\x5ft\x5fbar\x00\x01\x5f\x01\x2d\x02\x3a\x3a\x01\x01\xc2\x2f\x8b\xa1\xf4\xb35N\x01\x0c\x00\x01\
+	\xc8\x92\x3di\xf7\x05\xca\xc5\x00\x01\x00\xfcPc\x3a\xc2\x13\xc2\xdc\x03\x02\xa1\xfbd\xf8\xcc\xd2\xe1\xb9\x00\x00\x00\
+	\x01\x00\x01\x00\x03\xab2\xd1\xf7\x2dy\xe2\xb2\x00\x00\x00\x01\x00\x01\x00\x045\x81\x84\x15\x1fM\xc6\xd4\x00\x00\x00\
+	\x01\x00\x01\x00\x0a\x00\x03\x01\x05\x2aC\xed\x8a\xb34\x26\x11\x00\x00\x03\x00\x02\x02\x01ON\x20\x7c\x2a\xd07J\
+	\x00\x01a\x9ci\x2c\xbb\x06\x97z\x00\x03\x06\x16\x11\xf36\x29Gb\xbc\x00\x00\x01\x00\x00\x07\x08\x00\x00\x01\x01\
+	\x01a\x9ci\x2c\xbb\x06\x97z\x00\x03\x09G\xcb\x91\xa8\x05\x10\xe8\x8e\x00\x00\x00\x01\x00\x07\x01\x00\x00\x03\x01\x0a\
+	\x28m\x2cx\x5d\xe4\x5d8\x00\x00\x02\x01\x00\x03\x0b9\xc0\x17\x7d\xe1\x5e\x9f\x0b\x01\x01\x01O\x2a\x2b\x1b\x8ff\
+	\x26\x01\x00\x00\x00\x0c\x08\x00\xa2l\x93\x14\x92\x07\xc4\x18\x00\x00\x03\x01\x0d\x15\x16I\xdf\x9b\xd1\x88L\x00\x00\x02\
+	\x03\x00\x03\x0e\xdc\xdfO\xe4\xe7Z\x84\x80\x00\x01\x01\xf3\x96\xe3\xd9\xae\x5d\x98\xf8\x00\x03\x0fO\x83\xf8\x994\x96\
+	\x8e\x7c\x01\x02\x01j\xa5\x80\xee\x0a\x25\x9f\xac\x01\x07\x00\x00\x01O\x2a\x2b\x1b\x8ff\x26\x01\x00\x00\x00\x0c\x10\x00\
+	\x2c\xe5\xe9E\x81\xcf\x0c\xe6\x00\x03\x11\xc3w\x11\xf4\x27I\xa9\xa9\x01\x01\x01O\x2a\x2b\x1b\x8ff\x26\x01\x00\x00\
+	\x00\x0c\x08\x00\xa2l\x93\x14\x92\x07\xc4\x18\x00\x00\x03\x01\x12J\xfb\xda\x27\x0ao1M\x00\x00\x00\x01\x02\x01\x01\
+	eL\xc8\xda\x5c9Y\x2b\x01\x0c\x13\x03\x00\x01\x01eL\xc8\xda\x5c9Y\x2b\x01\x0c\x14\x03\x01\x00\x00\x15\x16\
+	\x00\x01\x01\x01o\xca\x0d\xb2bW\xa1S\x01\x0c\x17\x03\x01\x03\x00\x15\x18\xc9\x91E\x5f\xc0o\x85\x06\x01\x01\x01\
+	O\x2a\x2b\x1b\x8ff\x26\x01\x00\x03\x00\x15\x16\x00\x7d\xc7\x89\x0c\x3f\x05\xd6P\x00\x02\x01\x01\x40\xc0V\xc5\x1av\
+	d\x0c\x00\x03\x00\x15\x19\x00j\x8a\x9c\x1e\x7d\xe2x\x85\x03\x01a\x9ci\x2c\xbb\x06\x97z\x00\x01\xc8e\xab\xda\
+	X\xc8\x80\x86\x01\x0c\x1a\x01\x40\xc0V\xc5\x1avd\x0c\x00\x03\x00\x1b\x1c\x00D\x8e\x05h\x891\xf8\x03\x08\x02\
+	\x01ON\x20\x7c\x2a\xd07J\x00\x01a\x9ci\x2c\xbb\x06\x97z\x00\x03\x1d\xda\xcap\x93B\x04\x1f\x3d\x00\x00\
+	\x01\x00\x02\x00\x1e\x01\x00\x15\x1f\x00\x00\x00\x02\x01ON\x20\x7c\x2a\xd07J\x00\x01a\x9ci\x2c\xbb\x06\x97z\
+	\x00\x03\x20J\xc9\x7d\x16\xbe\xcb\x3c\xc2\x00\x00\x01\x00\x03\x02\x00\x21\x00\x21\x22\x00\x00\x00\x02\x01ON\x20\x7c\x2a\
+	\xd07J\x00\x01a\x9ci\x2c\xbb\x06\x97z\x00\x03\x23\xefH\x5b\xd9x\xad\xa2\x00\x00\x00\x01\x01\x01\x2e\x1c\xbf\
+	\x0c\x02\x0d\xbbg\x01\x04\x08\x00\x00\x00\x02\x00\x24\x01\x00\x25\x26\x00\x00\x00\x02\x01ON\x20\x7c\x2a\xd07J\x00\
+	\x01a\x9ci\x2c\xbb\x06\x97z\x00\x03\x27C\xa7\x9c\xc3\xfewO\x01\x00\x00\x01\x00\x04\x01\x00\x15\x01\x00\x15\x28\
+	\x00\x00\x00\x02\x01ON\x20\x7c\x2a\xd07J\x00\x01a\x9ci\x2c\xbb\x06\x97z\x00\x03\x29Ut\xf2F\xc1\x93\
+	\x5e\x09\x00\x00\x01\x00\x02\x00\x2a\x01\x00\x15\x16\x00\x00\x00\x02\x01ON\x20\x7c\x2a\xd07J\x00\x01a\x9ci\x2c\
+	\xbb\x06\x97z\x00\x03\x2b\xd3r\x1e\xfb\xdcSo\xf7\x01\x2c\x00\x01\x00\x02\x00\x1e\x01\x00\x2c\x2d\x00\x00\x00\x03\x01\
+	k\xdc\x3d\xdc\xad\x2c\xe9\x98\x02\x0c\x2b\x0c\x25\x01ON\x20\x7c\x2a\xd07J\x00\x01a\x9ci\x2c\xbb\x06\x97z\
+	\x00\x03\x2e\x3c\xb4b\xf9\xf2s\x14\x91\x00\x00\x01\x00\x02\x00\x1e\x01\x00\x25\x2d\x00\x00\x00\x03\x01k\xdc\x3d\xdc\xad\
+	\x2c\xe9\x98\x02\x0c\x2b\x0c\x07\x01ON\x20\x7c\x2a\xd07J\x00\x01a\x9ci\x2c\xbb\x06\x97z\x00\x03\x2f\xb0\x2d\
+	\x25G\x16\x7b\x9ep\x00\x00\x01\x00\x02\x00\x1e\x01\x00\x07\x2d\x00\x00\x00\x00\x00\x03\x010\xbf\xd6\xf2\xf9\x05\xe8\x26\
+	O\x00\x00\x02\x01\x01\x01\xf3\x96\xe3\xd9\xae\x5d\x98\xf8\x00\x03\x0b9\xc0\x17\x7d\xe1\x5e\x9f\x0b\x01\x02\x01j\xa5\x80\
+	\xee\x0a\x25\x9f\xac\x01\x07\x00\x00\x01O\x2a\x2b\x1b\x8ff\x26\x01\x00\x00\x00\x0c\x10\x00\x2c\xe5\xe9E\x81\xcf\x0c\xe6\
+	\x00\x00\x03\x011L\xadeY\x85O\xacZ\x00\x00\x02\x02\x00\x03\x0e\xdc\xdfO\xe4\xe7Z\x84\x80\x00\x01\x01\xf3\
+	\x96\xe3\xd9\xae\x5d\x98\xf8\x00\x03\x0fO\x83\xf8\x994\x96\x8e\x7c\x01\x02\x01j\xa5\x80\xee\x0a\x25\x9f\xac\x01\x07\x00\
+	\x00\x01O\x2a\x2b\x1b\x8ff\x26\x01\x00\x00\x00\x0c\x10\x00\x2c\xe5\xe9E\x81\xcf\x0c\xe6\x00\x00\x03\x012\x7f\x16\x13\
+	\x3d\x89p\xbaf\x00\x00\x01\x01\x0b\x04\x00\x00\x00\x00\x00\x03\x013\x24\xbe\xdd\xb3\xf3\xfa\xe3j\x00\x00\x05\x02\x00\
+	4\x01\x00\x1b\x00\x03\x015\xa1vHa\xd3\x068\x9b\x00\x00\x00\x01\x01\x01\x01eL\xc8\xda\x5c9Y\x2b\x01\
+	\x0c\x14\x03\x01\x01\x01a\x9ci\x2c\xbb\x06\x97z\x00\x00\x1b6\x04\x00\x01\x01\x01O\x2a\x2b\x1b\x8ff\x26\x01\x00\
+	\x00\x00\x076\x00\xd8\x3e0vH\x18\x89\xb0\x00\x00\x06\x02\x01ON\x20\x7c\x2a\xd07J\x00\x01a\x9ci\x2c\
+	\xbb\x06\x97z\x00\x00\x06\x16\x11\xf36\x29Gb\xbc\x00\x00\x01\x00\x00\x07\x08\x00\x00\x00\x01\x01a\x9ci\x2c\xbb\
+	\x06\x97z\x00\x00\x09G\xcb\x91\xa8\x05\x10\xe8\x8e\x00\x00\x00\x01\x00\x07\x00\x01\x01a\x9ci\x2c\xbb\x06\x97z\x00\
+	\x037\xbfPh1\xf7\x0d\xcd\xf3\x00\x00\x01\x00\x00\x218\x00\x01\x03\x00\x00\x02\x01ON\x20\x7c\x2a\xd07J\
+	\x00\x01a\x9ci\x2c\xbb\x06\x97z\x00\x039\xb1\x7c\x9d\x0bXk\xc9\x16\x00\x00\x01\x01\x01a\x9ci\x2c\xbb\x06\
+	\x97z\x00\x00\x1b6\x04\x00\x00\x02\x01\x95\x89p\xa7E\xa6\xe7\xed\x01\x0c\x3a\x01a\x9ci\x2c\xbb\x06\x97z\x00\
+	\x03\x3bU\x09E\xea\x09\x19\xaa\x07\x00\x00\x00\x01\x00\x1b\x00\x01\x01a\x9ci\x2c\xbb\x06\x97z\x00\x03\x3c\x23\x86\
+	\xb5\xcd\x82p\xa3\xed\x00\x00\x00\x01\x00\x07\x00\x01\x00\x05\x00\x03\x01\x3d\x85\xa9\x8bs\xc3\xa3\x08\xd6\x01\x2c\x01\x2c\
+	\x00\x05\x00\x01\x01\x01\x01eL\xc8\xda\x5c9Y\x2b\x01\x0c\x14\x03\x00\x00\x01\x01\x01O\x2a\x2b\x1b\x8ff\x26\x01\
+	\x00\x00\x02\x00\x2a\x01\x00\x2c\x3e\x00\xee\x5b\xc7\xe3\x12\xdd\xdf\x28\x00\x00\x02\x02\x01ON\x20\x7c\x2a\xd07J\x00\
+	\x01a\x9ci\x2c\xbb\x06\x97z\x00\x03\x3f\x7f\x2fy\xbe\xbc\x98\xa8\x0d\x00\x00\x01\x00\x00\x2c\x40\x00\x00\x00\x01\x01\
+	a\x9ci\x2c\xbb\x06\x97z\x00\x03Ab\x89O\xb72\x91B\x91\x00\x00\x00\x00\x00\x00BBC\x00DBB\
+	C\x00EBBB\x02BBB\x02BBC\x00BBC\x01BBC\x00BBC\x00BBB\x02B\
+	BC\x00";
+
+#[no_mangle]
+pub extern "C" fn tgMetadata() -> Metadata {
+	Metadata::new(TANGARA_METADATA)
 }
@@ -1,5 +1,8 @@
 mod bindings;
 
+use tangara_macros::tangara;
+
+#[tangara(skip)]
 trait WarningTrait {
     fn kekov() -> i32;
 }
@@ -51,6 +54,10 @@ pub struct MyStruct {
 pub type BoxedStr = Box<str>;
 
 impl TestStruct {
+    pub const MAX_ID: u64 = 1000;
+    pub const NAME: &'static str = "TestStruct";
+
+    #[tangara(ctor)]
     pub fn test_empty_ctor() -> Self {
         Self {
             id: 0
@@ -62,6 +69,40 @@ impl TestStruct {
             id
         }
     }
+
+    pub fn add_ids(&mut self, ids: Vec<u64>) {
+        self.id += ids.iter().sum::<u64>();
+    }
+
+    pub fn set_pair(&mut self, pair: (u32, u32)) {
+        self.id = ((pair.0 as u64) << 32) | pair.1 as u64;
+    }
+
+    pub fn set_bytes(&mut self, bytes: [u8; 8]) {
+        self.id = u64::from_le_bytes(bytes);
+    }
+
+    pub fn map_id(&mut self, f: fn(u64) -> u64) {
+        self.id = f(self.id);
+    }
+
+    pub fn set_or_default(&mut self, id: Option<u64>) {
+        self.id = id.unwrap_or_default();
+    }
+
+    pub fn add_len<T>(&mut self, items: Vec<T>) {
+        self.id += items.len() as u64;
+    }
+
+    #[tangara(property, rename = "reversed_id")]
+    pub fn get_reverse(&self) -> u64 {
+        Self::MAX_ID - self.id
+    }
+
+    #[tangara(property, rename = "reversed_id")]
+    pub fn set_reverse(&mut self, reverse: u64) {
+        self.id = Self::MAX_ID - reverse;
+    }
 }
 
 impl<T: MyTrait> GenericsTest<T> {
@@ -115,4 +156,15 @@ impl MyStruct {
     pub fn get_name(&self) -> &str {
         &self.name
     }
+
+    /// Give name to implementation of MyTrait registered by host and get its answer
+    pub fn greet_host(&self) -> String {
+        match bindings::MyTraitHost::get() {
+            Some(mut host) => {
+                host.foo(self.name.clone());
+                host.bar()
+            }
+            None => String::new()
+        }
+    }
 }
\ No newline at end of file
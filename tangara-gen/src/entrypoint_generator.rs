@@ -1,7 +1,9 @@
 use std::path::Path;
 use tangara_highlevel::*;
+use tangara_highlevel::primitives::Primitive;
+use tangara_highlevel::tangara_std::TANGARA_STD;
 use crate::rust_generator::Config;
-use crate::RUST_STD_LIB;
+use crate::{find_type, get_instances, RUST_STD_LIB, with_rust_names};
 
 pub struct EntrypointGenerator {
     config: Config,
@@ -9,13 +11,48 @@ pub struct EntrypointGenerator {
     use_block: String,
     tgload_body: String,
     bindings_block: String,
-    package_name: String
+    package_name: String,
+    embed_metadata: bool,
+    export_symbols: bool
 }
 
 fn get_type_name(t: &Type) -> String {
     format!("{}_type", t.name)
 }
 
+/// Pattern matching variant of enum, only field with name `bind` is bound to variable
+fn get_variant_pattern(variant: &Variant, t: &Type, bind: Option<&str>) -> String {
+    if variant.fields.is_empty() {
+        format!("{}::{}", t.name, variant.name)
+    } else if RUST_STD_LIB.is_tuple_variant(&variant.attrs) {
+        let fields = variant.fields.iter()
+            .map(|field| if bind == Some(field.name.as_str()) { field.name.as_str() } else { "_" })
+            .collect::<Vec<&str>>();
+        format!("{}::{}({})", t.name, variant.name, fields.join(", "))
+    } else {
+        match bind {
+            Some(field) => format!("{}::{} {{ {}, .. }}", t.name, variant.name, field),
+            None => format!("{}::{} {{ .. }}", t.name, variant.name)
+        }
+    }
+}
+
+/// Array size written as Rust expression: integer literal or name of constant
+fn get_array_size(size: &Value) -> Option<String> {
+    match size {
+        Value::Byte(size) => Some(size.to_string()),
+        Value::Short(size) => Some(size.to_string()),
+        Value::Int(size) => Some(size.to_string()),
+        Value::Long(size) => Some(size.to_string()),
+        Value::SByte(size) => Some(size.to_string()),
+        Value::UShort(size) => Some(size.to_string()),
+        Value::UInt(size) => Some(size.to_string()),
+        Value::ULong(size) => Some(size.to_string()),
+        Value::String(constant) => Some(constant.clone()),
+        _ => None
+    }
+}
+
 impl EntrypointGenerator {
     pub(crate) fn new(package: Package, config: Config) -> Self {
         let package_name = format!("{}_package", package.name);
@@ -25,7 +62,9 @@ impl EntrypointGenerator {
             use_block: String::new(),
             tgload_body: String::new(),
             bindings_block: String::new(),
-            package_name
+            package_name,
+            embed_metadata: false,
+            export_symbols: true
         }
     }
 
@@ -34,19 +73,138 @@ impl EntrypointGenerator {
         self
     }
 
+    /// Embed package in binary format into library and export function returning it,
+    /// so library can be reflected without `.tgjson` file
+    pub fn embed_metadata(mut self) -> Self {
+        self.embed_metadata = true;
+        self
+    }
+
+    /// Don't export load and metadata functions from library, so library itself decides how to call them.
+    /// For example, `#[tangara::export]` combines entrypoints of several modules into one.
+    pub fn hide_symbols(mut self) -> Self {
+        self.export_symbols = false;
+        self
+    }
+
+    /// Attribute exporting function from library with unmangled name
+    fn no_mangle(&self) -> &'static str {
+        if self.export_symbols {
+            "#[no_mangle]\n"
+        } else {
+            ""
+        }
+    }
+
     fn pass_vis(&self, vis: &Visibility) -> bool {
         let vis = vis.clone();
         vis == Visibility::Public || (self.config.enable_internal && vis == Visibility::Internal)
     }
 
-    fn get_type_name(&self, type_ref: &TypeRef) -> Option<String> {
-        if let TypeRef::Name(name) = type_ref {
-            Some(name.clone()) // TODO remake this so we can use not only name type references
+    /// Base class of class from this package, other parents are interfaces
+    fn get_parent_class(&self, t: &Type) -> Option<&Type> {
+        if let TypeKind::Class { parents, .. } = &t.kind {
+            parents.iter()
+                .filter_map(|parent| find_type(&self.package, parent, &t.namespace))
+                .find(|parent| matches!(parent.kind, TypeKind::Class { .. }))
         } else {
             None
         }
     }
 
+    /// Check that class has base class or descendants in this package,
+    /// so its objects can be used as objects of other classes and need runtime type
+    fn is_in_hierarchy(&self, t: &Type) -> bool {
+        self.get_parent_class(t).is_some() || self.package.types.iter()
+            .any(|descendant| self.get_parent_class(descendant).map(|parent| parent.id) == Some(t.id))
+    }
+
+    /// Id of class from class hierarchy which type reference points to
+    fn get_hierarchy_id(&self, type_ref: &TypeRef, namespace: &str) -> Option<u64> {
+        find_type(&self.package, type_ref, namespace)
+            .filter(|t| t.generics.0.is_empty() && self.is_in_hierarchy(t))
+            .map(|t| t.id)
+    }
+
+    /// Code moving `value` into object. Objects of classes from hierarchy keep their runtime type in header.
+    fn gen_new_object(&self, value: &str, type_ref: &TypeRef, namespace: &str) -> String {
+        match self.get_hierarchy_id(type_ref, namespace) {
+            Some(id) => format!("TANGARA_ALLOCATOR.new_class_object({}, {})", value, id),
+            None => format!("TANGARA_ALLOCATOR.new_object({})", value)
+        }
+    }
+
+    /// Rust type for type reference. Attributes of member are used for getting sizes of arrays
+    /// and mutability of pointers. Returns `None` if type can't be found by id.
+    fn get_type_name(&self, type_ref: &TypeRef, attrs: &[Attribute]) -> Option<String> {
+        let mut array_sizes = TANGARA_STD.get_array_sizes(attrs).into_iter();
+        self.get_typeref_name(type_ref, attrs, &mut array_sizes)
+    }
+
+    fn get_typeref_name(&self, type_ref: &TypeRef, attrs: &[Attribute],
+                        array_sizes: &mut impl Iterator<Item = Value>) -> Option<String> {
+        if let Some(primitive) = Primitive::from_typeref(type_ref) {
+            return Some(primitive.rust_type().to_string());
+        }
+        match type_ref {
+            TypeRef::Name(name) => {
+                // types of this package are used by their names, others by their Rust paths
+                match self.package.types.iter().find(|t| format!("{}.{}", t.namespace, t.name) == *name) {
+                    Some(t) => Some(t.name.clone()),
+                    None => Some(name.replace('.', "::"))
+                }
+            }
+            TypeRef::Id(id) => self.package.types.iter().find(|t| t.id == *id).map(|t| t.name.clone()),
+            TypeRef::Generic(base, generics) => {
+                let mut generic_names = Vec::with_capacity(generics.len());
+                for generic in generics {
+                    generic_names.push(self.get_typeref_name(generic, attrs, array_sizes)?);
+                }
+                if TANGARA_STD.is_array(base) && generic_names.len() == 1 {
+                    Some(match array_sizes.next().and_then(|size| get_array_size(&size)) {
+                        Some(size) => format!("[{}; {}]", generic_names[0], size),
+                        None => format!("[{}]", generic_names[0])
+                    })
+                } else if TANGARA_STD.is_ptr(base) && generic_names.len() == 1 {
+                    let ptr_type = if RUST_STD_LIB.is_mutable(attrs) { "mut" } else { "const" };
+                    Some(format!("*{} {}", ptr_type, generic_names[0]))
+                } else if TANGARA_STD.is_option(base) {
+                    Some(format!("Option<{}>", generic_names.join(", ")))
+                } else if TANGARA_STD.is_result(base) {
+                    Some(format!("Result<{}>", generic_names.join(", ")))
+                } else {
+                    let base_name = self.get_typeref_name(base, attrs, array_sizes)?;
+                    Some(format!("{}<{}>", base_name, generic_names.join(", ")))
+                }
+            }
+            TypeRef::Tuple(types) => {
+                let mut type_names = Vec::with_capacity(types.len());
+                for t in types {
+                    type_names.push(self.get_typeref_name(t, attrs, array_sizes)?);
+                }
+                // tuple with one element must have trailing comma
+                if type_names.len() == 1 {
+                    Some(format!("({},)", type_names[0]))
+                } else {
+                    Some(format!("({})", type_names.join(", ")))
+                }
+            }
+            TypeRef::Fn(return_type, args) => {
+                let mut arg_names = Vec::with_capacity(args.len());
+                for arg in args {
+                    arg_names.push(self.get_typeref_name(arg, attrs, array_sizes)?);
+                }
+                match return_type {
+                    Some(return_type) => {
+                        let return_name = self.get_typeref_name(return_type, attrs, array_sizes)?;
+                        Some(format!("fn({}) -> {}", arg_names.join(", "), return_name))
+                    }
+                    None => Some(format!("fn({})", arg_names.join(", ")))
+                }
+            }
+        }
+    }
+
     /// `this` parameter - Some: we have `self` param,
     /// `bool` inside it: is it mutable or not and `String` is name of type of `self`.
     /// Returns code for body and names of args with comma separator
@@ -82,7 +240,7 @@ impl EntrypointGenerator {
             let let_postfix = if ptr_type_prefix == "mut" { " mut" } else { "" };
             let arg_type = [
                 ref_prefix,
-                &self.get_type_name(&arg.1).unwrap_or("<ERROR TYPE GENERATOR>".to_string())
+                &self.get_type_name(&arg.1, &arg.0).unwrap_or("<ERROR TYPE GENERATOR>".to_string())
             ].concat();
             args_code.push_str(
                 &format!(r#"
@@ -102,15 +260,19 @@ impl EntrypointGenerator {
         } else {
             String::new()
         };*/
+        let delete_object = if self.get_hierarchy_id(&TypeRef::Id(t.id), &t.namespace).is_some() {
+            "TANGARA_ALLOCATOR.delete_class_object"
+        } else {
+            "TANGARA_ALLOCATOR.delete_object"
+        };
         self.bindings_block.push_str(
             &format!(r#"
 pub extern "C" fn {}_dtor(value: Ptr) {{
     unsafe {{
-        ptr::drop_in_place(value);
-        dealloc(value, Layout::new::<{}>());
+        {}::<{}>(value);
     }}
 }}
-"#, t.name, t.name));
+"#, t.name, delete_object, t.name));
 
         self.tgload_body.push_str(
             &format!("{}.set_dtor({}_dtor);\n", get_type_name(t), t.name)
@@ -123,15 +285,15 @@ pub extern "C" fn {}_dtor(value: Ptr) {{
                 let ctor_name = format!("{}_ctor{}", t.name, count);
                 let (args_code, arg_names) = self.gen_args(&ctor.args, None);
                 let ctor_call = format!("{}::{}({})", t.name, fn_name, arg_names);
+                let new_object = self.gen_new_object(&ctor_call, &TypeRef::Id(t.id), &t.namespace);
                 self.bindings_block.push_str(
                     &format!(r#"
 pub extern "C" fn {}(args_size: usize, args: *mut u8) -> Ptr {{
     unsafe {{{}
-        let value = Box::new({});
-        Box::into_raw(value) as Ptr
+        {}
     }}
 }}
-"#, ctor_name, args_code, ctor_call));
+"#, ctor_name, args_code, new_object));
 
                 self.tgload_body.push_str(
                     &format!("{}.add_ctor({});\n", get_type_name(t), ctor_name)
@@ -147,57 +309,113 @@ pub extern "C" fn {}(args_size: usize, args: *mut u8) -> Ptr {{
     fn gen_method(&mut self, method: &Method, t: &Type) {
         if self.pass_vis(&method.vis) {
             let this_arg = match &method.kind {
-                MethodKind::Default => Some((RUST_STD_LIB.is_mutable(&method.attrs), t.name.clone())),
+                MethodKind::Default | MethodKind::Virtual => Some((RUST_STD_LIB.is_mutable(&method.attrs), t.name.clone())),
                 MethodKind::Static => None,
-                _ => {
+                // abstract method has no implementation in this type, it's found in derived ones
+                MethodKind::Abstract => {
                     return;
                 }
             };
+            if !method.generics.0.is_empty() {
+                // only instantiations of generic method can be called through `extern "C"` function
+                return;
+            }
             let fn_name = format!("{}_{}", t.name, method.name);
             let (args_code, arg_names) = self.gen_args(&method.args, this_arg.clone());
-            let fn_call = if this_arg.is_some() {
-                format!("(*this).{}({})", method.name, arg_names)
-            } else {
-                format!("{}::{}({})", t.name, method.name, arg_names)
+            // instantiation of generic method calls it with generic arguments
+            let method_call = match RUST_STD_LIB.get_method_instance(&method.attrs) {
+                Some((method_name, generics)) => format!("{}::<{}>", method_name, generics),
+                None => method.name.clone()
             };
-            let final_code = if method.return_type.is_some() {
-                format!("let to_return = Box::new({});\n\t\tBox::into_raw(to_return) as Ptr", fn_call)
+            let fn_call = if this_arg.is_some() {
+                format!("(*this).{}({})", method_call, arg_names)
             } else {
-                format!("{};\n\t\tptr::null_mut()", fn_call)
+                format!("{}::{}({})", t.name, method_call, arg_names)
             };
-            self.bindings_block.push_str(
-                &format!(r#"
+            let final_code = method.return_type.as_ref()
+                .map(|return_type| self.gen_new_object(&fn_call, return_type, &t.namespace));
+            self.gen_method_fn(&fn_name, &args_code, &fn_call, final_code);
+
+            self.tgload_body.push_str(
+                &format!("{}.add_method({}, {});\n", get_type_name(t), method.id, fn_name)
+            );
+        }
+    }
+
+    /// Generate function with [Fn] signature which calls `fn_call` and returns its result
+    /// by `new_object` code or null if method returns nothing
+    fn gen_method_fn(&mut self, fn_name: &str, args_code: &str, fn_call: &str, new_object: Option<String>) {
+        let final_code = match new_object {
+            Some(new_object) => new_object,
+            None => format!("{};\n\t\tptr::null_mut()", fn_call)
+        };
+        self.bindings_block.push_str(
+            &format!(r#"
 pub extern "C" fn {}(args_size: usize, args: *mut u8) -> Ptr {{
     unsafe {{{}
         {}
     }}
 }}
 "#, fn_name, args_code, final_code));
+    }
 
-            self.tgload_body.push_str(
-                &format!("{}.add_method({}, {});\n", get_type_name(t), method.id, fn_name)
-            );
+    /// Methods of interfaces implemented by type are registered in vtables of these interfaces.
+    /// Only interfaces of this package are handled, other parents are skipped.
+    fn gen_interfaces(&mut self, parents: &[TypeRef], t: &Type) {
+        for parent in parents {
+            let interface = match find_type(&self.package, parent, &t.namespace) {
+                Some(interface) if matches!(interface.kind, TypeKind::Interface { .. }) => with_rust_names(interface),
+                _ => continue
+            };
+            if !self.pass_vis(&interface.vis) {
+                continue;
+            }
+            if let TypeKind::Interface { methods, .. } = &interface.kind {
+                let vtable_name = format!("{}_{}_vtable", t.name, interface.name);
+                self.tgload_body.push_str(
+                    &format!("let {} = {}.add_interface({});\n", vtable_name, get_type_name(t), interface.id)
+                );
+                for method in methods {
+                    let fn_name = format!("{}_{}_{}", t.name, interface.name, method.name);
+                    let is_mutable = RUST_STD_LIB.is_mutable(&method.attrs);
+                    let (args_code, arg_names) = self.gen_args(&method.args, Some((is_mutable, t.name.clone())));
+                    // method is called by trait path, so it doesn't conflict with inherent method with the same name
+                    let this_ref = if is_mutable { "&mut *this" } else { "&*this" };
+                    let fn_call = if arg_names.is_empty() {
+                        format!("<{} as {}>::{}({})", t.name, interface.name, method.name, this_ref)
+                    } else {
+                        format!("<{} as {}>::{}({}, {})", t.name, interface.name, method.name, this_ref, arg_names)
+                    };
+                    let final_code = method.return_type.as_ref()
+                        .map(|return_type| self.gen_new_object(&fn_call, return_type, &interface.namespace));
+                    self.gen_method_fn(&fn_name, &args_code, &fn_call, final_code);
+
+                    self.tgload_body.push_str(
+                        &format!("{}.add_method({}, {});\n", vtable_name, method.id, fn_name)
+                    );
+                }
+            }
         }
     }
 
     fn gen_property(&mut self, prop: &Property, t: &Type) {
         if self.pass_vis(&prop.getter_visibility) {
             let getter_name = format!("{}_get_{}", t.name, prop.name);
+            let new_object = self.gen_new_object(&format!("(*this).get_{}()", prop.name), &prop.prop_type, &t.namespace);
             self.bindings_block.push_str(
                 &format!(r#"
 pub extern "C" fn {0}(this: Ptr) -> Ptr {{
     unsafe {{
         let this: *const {1} = this as *const {1};
-        let to_return = Box::new((*this).get_{2}());
-        Box::into_raw(to_return) as Ptr
+        {2}
     }}
 }}
-"#, getter_name, t.name, prop.name));
+"#, getter_name, t.name, new_object));
 
             let setter = if let Some(setter_vis) = prop.setter_visibility {
                 if self.pass_vis(&setter_vis) {
                     let setter_name = format!("{}_set_{}", t.name, prop.name);
-                    let prop_type = self.get_type_name(&prop.prop_type)
+                    let prop_type = self.get_type_name(&prop.prop_type, &prop.attrs)
                         .unwrap_or("<ERROR TYPE GENERATOR>".to_string());
                     self.bindings_block.push_str(
                         &format!(r#"
@@ -228,22 +446,22 @@ pub extern "C" fn {0}(this: Ptr, object: Ptr) {{
     fn gen_field(&mut self, field: &Field, t: &Type) {
         if self.pass_vis(&field.vis) {
             let getter_name = format!("{}_get_{}", t.name, field.name);
+            let new_object = self.gen_new_object(&format!("(*this).{}", field.name), &field.field_type, &t.namespace);
             self.bindings_block.push_str(
                 &format!(r#"
 pub extern "C" fn {0}(this: Ptr) -> Ptr {{
     unsafe {{
         let this: *const {1} = this as *const {1};
-        let to_return = Box::new((*this).{2});
-        Box::into_raw(to_return) as Ptr
+        {2}
     }}
 }}
-"#, getter_name, t.name, field.name));
+"#, getter_name, t.name, new_object));
 
-                let setter_name = format!("{}_set_{}", t.name, field.name);
-                let field_type = self.get_type_name(&field.field_type)
-                    .unwrap_or("<ERROR TYPE GENERATOR>".to_string());
-                self.bindings_block.push_str(
-                    &format!(r#"
+            let setter_name = format!("{}_set_{}", t.name, field.name);
+            let field_type = self.get_type_name(&field.field_type, &field.attrs)
+                .unwrap_or("<ERROR TYPE GENERATOR>".to_string());
+            self.bindings_block.push_str(
+                &format!(r#"
 pub extern "C" fn {0}(this: Ptr, object: Ptr) {{
     unsafe {{
         let this: *mut {1} = this as *mut {1};
@@ -254,7 +472,7 @@ pub extern "C" fn {0}(this: Ptr, object: Ptr) {{
 "#, setter_name, t.name, field.name, field_type));
 
             self.tgload_body.push_str(
-                &format!("{}.add_property({}, Property {{ getter: {}, setter: {} }});\n",
+                &format!("{}.add_property({}, Property {{ getter: {}, setter: Some({}) }});\n",
                          get_type_name(t), field.id, getter_name, setter_name)
             );
         }
@@ -263,20 +481,20 @@ pub extern "C" fn {0}(this: Ptr, object: Ptr) {{
     fn gen_static_property(&mut self, prop: &Property, t: &Type) {
         if self.pass_vis(&prop.getter_visibility) {
             let getter_name = format!("{}_get_static_{}", t.name, prop.name);
+            let new_object = self.gen_new_object(&format!("{}::get_{}()", t.name, prop.name), &prop.prop_type, &t.namespace);
             self.bindings_block.push_str(
                 &format!(r#"
-pub extern "C" fn {0}() -> Ptr {{
+pub extern "C" fn {}() -> Ptr {{
     unsafe {{
-        let to_return = Box::new({1}::get_{2}());
-        Box::into_raw(to_return) as Ptr
+        {}
     }}
 }}
-"#, getter_name, t.name, prop.name));
+"#, getter_name, new_object));
 
             let setter = if let Some(setter_vis) = prop.setter_visibility {
                 if self.pass_vis(&setter_vis) {
                     let setter_name = format!("{}_set_static_{}", t.name, prop.name);
-                    let prop_type = self.get_type_name(&prop.prop_type)
+                    let prop_type = self.get_type_name(&prop.prop_type, &prop.attrs)
                         .unwrap_or("<ERROR TYPE GENERATOR>".to_string());
                     self.bindings_block.push_str(
                         &format!(r#"
@@ -303,40 +521,28 @@ pub extern "C" fn {0}(object: Ptr) {{
         }
     }
 
-    // TODO
+    /// Static field of Rust type is associated constant which can't be assigned, so it's read-only
     fn gen_static_field(&mut self, field: &Field, t: &Type) {
         if self.pass_vis(&field.vis) {
             let getter_name = format!("{}_get_static_{}", t.name, field.name);
+            let new_object = self.gen_new_object(&format!("{}::{}", t.name, field.name), &field.field_type, &t.namespace);
             self.bindings_block.push_str(
                 &format!(r#"
 pub extern "C" fn {}() -> Ptr {{
     unsafe {{
-        let to_return = Box::new({}::{});
-        Box::into_raw(to_return) as Ptr
-    }}
-}}
-"#, getter_name, t.name, field.name));
-
-                let setter_name = format!("{}_set_static_{}", t.name, field.name);
-                let field_type = self.get_type_name(&field.field_type)
-                    .unwrap_or("<ERROR TYPE GENERATOR>".to_string());
-                self.bindings_block.push_str(
-                    &format!(r#"
-pub extern "C" fn {0}(object: Ptr) {{
-    unsafe {{
-        let {2}: {3} = ptr::read(object as *const {3});
-        {1}::{2} = {2};
+        {}
     }}
 }}
-"#, setter_name, t.name, field.name, field_type));
+"#, getter_name, new_object));
 
             self.tgload_body.push_str(
-                &format!("{}.add_static({}, StaticProperty {{ getter: {}, setter: {} }});\n",
-                         get_type_name(t), field.id, getter_name, setter_name)
+                &format!("{}.add_static({}, StaticProperty {{ getter: {}, setter: None }});\n",
+                         get_type_name(t), field.id, getter_name)
             );
         }
     }
 
+    /// Variant constructor is registered as method
     fn gen_variant(&mut self, variant: &Variant, t: &Type) {
         if self.pass_vis(&variant.vis) {
             let fn_name = format!("{}_{}", t.name, variant.name);
@@ -357,8 +563,7 @@ pub extern "C" fn {0}(object: Ptr) {{
             } else {
                 (format!("{}::{}", t.name, variant.name), String::new())
             };
-            let final_code = format!("let return_value = Box::new({});\n\t\tBox::into_raw(return_value) as Ptr",
-                                     enum_variant);
+            let final_code = format!("TANGARA_ALLOCATOR.new_object({})", enum_variant);
             self.bindings_block.push_str(
                 &format!(r#"
 pub extern "C" fn {}(args_size: usize, args: *mut u8) -> Ptr {{
@@ -374,18 +579,199 @@ pub extern "C" fn {}(args_size: usize, args: *mut u8) -> Ptr {{
         }
     }
 
+    /// Register variants protocol: tag of current variant and borrowed fields of variants
+    fn gen_variants(&mut self, variants: &[Variant], t: &Type) {
+        let tag_name = format!("{}_variant_tag", t.name);
+        let field_name = format!("{}_variant_field", t.name);
+        let tag_arms = variants.iter()
+            .map(|variant| format!("\n            {} => {},", get_variant_pattern(variant, t, None), variant.id))
+            .collect::<String>();
+        let mut field_arms = String::new();
+        for variant in variants {
+            for field in &variant.fields {
+                let field_type = self.get_type_name(&field.field_type, &field.attrs)
+                    .expect("Type of variant field can't be resolved");
+                field_arms.push_str(&format!(
+                    "\n            ({}, {}) => {} as *const {} as Ptr,",
+                    get_variant_pattern(variant, t, Some(&field.name)),
+                    generate_variant_field_id(&variant.name, &field.name),
+                    field.name, field_type
+                ));
+            }
+        }
+        self.bindings_block.push_str(
+            &format!(r#"
+pub extern "C" fn {0}(this: Ptr) -> u64 {{
+    unsafe {{
+        let this: *const {1} = this as *const {1};
+        match &*this {{{2}
+        }}
+    }}
+}}
+"#, tag_name, t.name, tag_arms));
+        if field_arms.is_empty() {
+            // no variant has fields
+            self.bindings_block.push_str(&format!(r#"
+pub extern "C" fn {}(_: Ptr, _: u64) -> Ptr {{
+    ptr::null_mut()
+}}
+"#, field_name));
+        } else {
+            self.bindings_block.push_str(&format!(r#"
+pub extern "C" fn {0}(this: Ptr, id: u64) -> Ptr {{
+    unsafe {{
+        let this: *const {1} = this as *const {1};
+        match (&*this, id) {{{2}
+            _ => ptr::null_mut()
+        }}
+    }}
+}}
+"#, field_name, t.name, field_arms));
+        }
+
+        self.tgload_body.push_str(
+            &format!("{}.set_variants(Variants {{ tag: {}, field: {} }});\n", get_type_name(t), tag_name, field_name)
+        );
+    }
+
+    /// Generate type implementing interface by calling methods of implementation registered by host.
+    /// Implementation is taken from context in `tgLoad`, so host must register it before loading library.
+    fn gen_host_implementation(&mut self, methods: &[Method], t: &Type) {
+        let host_name = format!("{}_host", t.name);
+        self.bindings_block.push_str(&format!(r#"
+/// Implementation of {0} registered by host
+static {1}: std::sync::RwLock<Option<std::sync::Arc<Implementation>>> = std::sync::RwLock::new(None);
+
+pub struct {0}Host {{
+    implementation: std::sync::Arc<Implementation>
+}}
+
+impl {0}Host {{
+    /// Get implementation of {0} registered by host as trait object or `None` if host didn't register it
+    pub fn get() -> Option<Box<dyn {0}>> {{
+        let implementation = {1}.read().unwrap_or_else(|err| err.into_inner()).clone()?;
+        Some(Box::new(Self {{ implementation }}))
+    }}
+}}
+
+impl {0} for {0}Host {{"#, t.name, host_name));
+        for method in methods {
+            let self_arg = if RUST_STD_LIB.is_reference(&method.attrs) {
+                if RUST_STD_LIB.is_mutable(&method.attrs) { "&mut self" } else { "&self" }
+            } else if RUST_STD_LIB.is_mutable(&method.attrs) {
+                "mut self"
+            } else {
+                "self"
+            };
+            let mut args = vec![self_arg.to_string()];
+            let mut args_size = vec!["std::mem::size_of::<Ptr>()".to_string()];
+            let mut args_write = String::new();
+            for arg in &method.args {
+                let ref_prefix = match &arg.3 {
+                    ArgumentKind::Default | ArgumentKind::DefaultValue(_) => "",
+                    ArgumentKind::Out | ArgumentKind::Ref => "&mut ",
+                    ArgumentKind::In => "&"
+                };
+                let arg_type = [
+                    ref_prefix,
+                    &self.get_type_name(&arg.1, &arg.0).unwrap_or("<ERROR TYPE GENERATOR>".to_string())
+                ].concat();
+                args_write.push_str(&format!(
+                    "\n            ptr::write(args_ptr.add({}) as *mut {}, {});", args_size.join(" + "), arg_type, arg.2
+                ));
+                args.push(format!("{}: {}", arg.2, arg_type));
+                args_size.push(format!("std::mem::size_of::<{}>()", arg_type));
+            }
+            let (return_block, final_code) = match &method.return_type {
+                Some(return_type) => {
+                    let prefix = RUST_STD_LIB.get_return_prefix(&method.attrs).unwrap_or_default();
+                    let return_type = [
+                        prefix,
+                        self.get_type_name(return_type, &method.attrs).unwrap_or("<ERROR TYPE GENERATOR>".to_string())
+                    ].concat();
+                    (
+                        format!(" -> {}", return_type),
+                        format!("TANGARA_ALLOCATOR.take_object::<{}>(method(args_size, args_ptr))", return_type)
+                    )
+                }
+                None => (String::new(), "method(args_size, args_ptr);".to_string())
+            };
+            self.bindings_block.push_str(&format!(r#"
+    fn {}({}){} {{
+        unsafe {{
+            let args_size = {};
+            let mut args_buf = vec![0u8; args_size];
+            let args_ptr = args_buf.as_mut_ptr();
+            *(args_ptr as *mut Ptr) = self.implementation.get_data();{}
+            let method = self.implementation.get_vtable().get_method({});
+            {}
+        }}
+    }}"#, method.name, args.join(", "), return_block, args_size.join(" + "), args_write, method.id, final_code));
+        }
+        self.bindings_block.push_str("\n}\n");
+
+        self.tgload_body.push_str(&format!(
+            "*{}.write().unwrap_or_else(|err| err.into_inner()) = ctx.try_get_implementation({}).ok().cloned();\n",
+            host_name, t.id
+        ));
+    }
+
+    /// Register type in package, instances of generic types are registered with id of generic type
+    fn gen_add_type(&mut self, type_name: &str, t: &Type, generic_id: Option<u64>) {
+        let add_type = match generic_id {
+            Some(generic_id) => format!("add_instance({}, {})", generic_id, t.id),
+            None => format!("add_type({})", t.id)
+        };
+        self.tgload_body.push_str(&format!("let mut {} = {}.{};\n", type_name, self.package_name, add_type));
+    }
+
     fn generate(&mut self) {
+        // objects are freed by allocator of any context, so library can't be loaded into contexts with different ones
+        self.tgload_body.push_str(
+            "if let Err(err) = TANGARA_ALLOCATOR.set(ctx.get_allocator()) {\n\tctx.reject_load(err);\n\treturn;\n}\n"
+        );
+        // implementations are taken before adding package because it borrows context until the end of `tgLoad`
+        for t in self.package.types.iter().map(with_rust_names).collect::<Vec<Type>>() {
+            // renamed type is used in bindings by its new name
+            if let Some(rust_name) = RUST_STD_LIB.get_rust_name(&t.attrs) {
+                self.bindings_block.push_str(&format!("\nuse {} as {};\n", rust_name, t.name));
+            }
+            if let TypeKind::Interface { methods, .. } = &t.kind {
+                if t.generics.0.is_empty() && self.pass_vis(&t.vis) {
+                    self.gen_host_implementation(methods, &t);
+                }
+            }
+        }
         self.tgload_body.push_str(
             &format!("let mut {} = ctx.add_package({});\n", self.package_name, self.package.id)
         );
-        let types = self.package.types.to_vec();
-        for t in types {
-            if t.generics.0.len() > 0 {
-                // while we cannot handle generics so skip it
-                println!("[Warning] Skip {} type because EntrypointGenerator cannot handle generics", t.name);
+        let version = &self.package.version;
+        self.tgload_body.push_str(&format!(
+            "{}.set_version(Version::new({}, {}, {}));\n{}.set_abi({});\n",
+            self.package_name, version.major, version.minor, version.patch, self.package_name, self.package.abi
+        ));
+        // generic types are generated only as their declared instantiations
+        let mut types = Vec::with_capacity(self.package.types.len());
+        for t in &self.package.types {
+            if t.generics.0.is_empty() {
+                types.push((with_rust_names(t), None));
                 continue;
             }
+            let instances = get_instances(&self.package, &self.config.instances, t);
+            if instances.is_empty() {
+                println!("[Warning] Skip {} type because it's generic and has no declared instances", t.name);
+            }
+            for (instance, type_ref) in instances {
+                types.push((with_rust_names(&instance), Some((t.id, type_ref))));
+            }
+        }
+        for (t, generic) in types {
             if self.pass_vis(&t.vis) {
+                if let Some((_, type_ref)) = &generic {
+                    let instance_name = self.get_type_name(type_ref, &[]).unwrap_or_default();
+                    self.bindings_block.push_str(&format!("\npub type {} = {};\n", t.name, instance_name));
+                }
+                let generic_id = generic.map(|(generic_id, _)| generic_id);
                 match &t.kind {
                     TypeKind::Class {
                         is_sealed,
@@ -398,9 +784,14 @@ pub extern "C" fn {}(args_size: usize, args: *mut u8) -> Ptr {{
                         parents
                     } => {
                         let type_name = get_type_name(&t);
-                        self.tgload_body.push_str(
-                            &format!("let mut {} = {}.add_type({});\n", type_name, self.package_name, t.id)
-                        );
+                        self.gen_add_type(&type_name, &t, generic_id);
+                        if self.get_hierarchy_id(&TypeRef::Id(t.id), &t.namespace).is_some() {
+                            // inherited methods are resolved through base class
+                            if let Some(parent_id) = self.get_parent_class(&t).map(|parent| parent.id) {
+                                self.tgload_body.push_str(&format!("{}.set_parent({});\n", type_name, parent_id));
+                            }
+                            self.tgload_body.push_str(&format!("{}.set_type_of(tangara::allocator::class_type_of);\n", type_name));
+                        }
                         self.gen_dtor(&t);
                         let mut count = 0usize;
                         for ctor in constructors {
@@ -411,27 +802,46 @@ pub extern "C" fn {}(args_size: usize, args: *mut u8) -> Ptr {{
                             self.gen_property(prop, &t);
                         }
                         for static_prop in static_properties {
-                            self.gen_property(static_prop, &t); // TODO implement static properties
+                            self.gen_static_property(static_prop, &t);
                         }
                         for field in fields {
                             self.gen_field(field, &t);
                         }
                         for static_field in static_fields {
-                            self.gen_field(static_field, &t); // TODO implement static field
+                            self.gen_static_field(static_field, &t);
                         }
                         for method in methods {
                             self.gen_method(method, &t);
                         }
+                        self.gen_interfaces(parents, &t);
+                    }
+                    TypeKind::Enum { variants } => {
+                        let type_name = get_type_name(&t);
+                        self.gen_add_type(&type_name, &t, generic_id);
+                        self.gen_dtor(&t);
+                        // variants of enum are handled as variants of enum class without fields
+                        let variants = variants.iter()
+                            .map(|(name, _)| Variant {
+                                attrs: vec![],
+                                vis: t.vis,
+                                name: name.clone(),
+                                id: generate_member_id(name),
+                                fields: vec![]
+                            })
+                            .collect::<Vec<Variant>>();
+                        self.gen_variants(&variants, &t);
+                        for variant in &variants {
+                            self.gen_variant(variant, &t);
+                        }
                     }
                     TypeKind::EnumClass {
                         variants,
                         methods
                     } => {
                         let type_name = get_type_name(&t);
-                        self.tgload_body.push_str(
-                            &format!("let mut {} = {}.add_type({});\n", type_name, self.package_name, t.id)
-                        );
+                        self.gen_add_type(&type_name, &t, generic_id);
                         self.gen_dtor(&t);
+                        self.gen_variants(variants, &t);
                         for variant in variants {
                             self.gen_variant(variant, &t);
                         }
@@ -445,9 +855,7 @@ pub extern "C" fn {}(args_size: usize, args: *mut u8) -> Ptr {{
                         static_fields
                     } => {
                         let type_name = get_type_name(&t);
-                        self.tgload_body.push_str(
-                            &format!("let mut {} = {}.add_type({});\n", type_name, self.package_name, t.id)
-                        );
+                        self.gen_add_type(&type_name, &t, generic_id);
                         self.gen_dtor(&t);
                         let mut count = 0usize;
                         for ctor in constructors {
@@ -458,7 +866,7 @@ pub extern "C" fn {}(args_size: usize, args: *mut u8) -> Ptr {{
                             self.gen_field(field, &t);
                         }
                         for static_field in static_fields {
-                            self.gen_field(static_field, &t); // TODO implement static field
+                            self.gen_static_field(static_field, &t);
                         }
                     }
                     _ => {
@@ -469,18 +877,58 @@ pub extern "C" fn {}(args_size: usize, args: *mut u8) -> Ptr {{
         }
     }
 
-    pub fn write_to<P: AsRef<Path>>(mut self, path: P) -> std::io::Result<()> {
+    fn gen_metadata(&self) -> String {
+        // types are sorted to get the same bytes for the same package
+        let mut package = self.package.clone();
+        package.types.sort_by_key(|t| t.id);
+        let bytes = binary::package_to_bytes(&package);
+        let mut literal = String::with_capacity(bytes.len() * 2);
+        for (i, byte) in bytes.iter().enumerate() {
+            if i > 0 && i % 32 == 0 {
+                literal.push_str("\\\n\t");
+            }
+            if byte.is_ascii_alphanumeric() {
+                literal.push(*byte as char);
+            } else {
+                literal.push_str(&format!("\\x{:02x}", byte));
+            }
+        }
+        format!(r#"
+/// Package of this library in Tangara binary format
+static TANGARA_METADATA: &[u8] = b"{}";
+
+{}pub extern "C" fn {}() -> Metadata {{
+	Metadata::new(TANGARA_METADATA)
+}}
+"#, literal, self.no_mangle(), self.config.metadata_name)
+    }
+
+    pub fn write_to<P: AsRef<Path>>(self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.generate_code())
+    }
+
+    /// Generate code of entrypoint, for example to insert it by procedural macro
+    pub fn generate_code(mut self) -> String {
         self.generate();
         let disclaimer = r#"// This file was generated by tangara-gen
 // All changes in this file will discard after rebuilding project
 use std::ptr;
-use std::alloc::{dealloc, Layout};
-use tangara::context::{Context, Ptr, Property};
+use tangara::allocator::AllocatorCell;
+use tangara::context::{Context, Implementation, Ptr, Property, StaticProperty, Variants, Version};
+
+/// Allocator of context which loaded this library
+static TANGARA_ALLOCATOR: AllocatorCell = AllocatorCell::new();
 "#.to_string();
         let mut tgload_body = self.tgload_body.replace("\n", "\n\t");
         tgload_body.remove(tgload_body.len() - 1); // remove last extra '\t'
-        let tgload = format!("#[no_mangle]\npub extern \"C\" fn {}(ctx: &mut Context) {{\n\t{}}}\n",
-                             self.config.load_name, tgload_body);
-        std::fs::write(path, String::from_iter([disclaimer, self.use_block, self.bindings_block, tgload]))
+        let tgload = format!("{}pub extern \"C\" fn {}(ctx: &mut Context) {{\n\t{}}}\n",
+                             self.no_mangle(), self.config.load_name, tgload_body);
+        let metadata = if self.embed_metadata {
+            self.use_block.push_str("use tangara::metadata::Metadata;\n");
+            self.gen_metadata()
+        } else {
+            String::new()
+        };
+        String::from_iter([disclaimer, self.use_block, self.bindings_block, tgload, metadata])
     }
 }
\ No newline at end of file
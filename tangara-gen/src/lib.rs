@@ -1,6 +1,11 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 use once_cell::sync::Lazy;
-use tangara_highlevel::{Attribute, get_typeref_bytes, NamingConventions, Package, Type, TypeRef, Value, Visibility};
-use tangara_highlevel::builder::{create_class, PackageBuilder, TypeBuilder};
+use tangara_highlevel::{Attribute, Dependency, generate_instance_id, Generics, get_typeref_bytes, NamingConventions,
+                        Package, Type, TypeKind, TypeRef, Value, Visibility};
+use tangara_highlevel::builder::{create_class, ClassBuilder, PackageBuilder, TypeBuilder};
+use tangara_highlevel::tangara_std::TANGARA_STD;
 
 mod package_generator;
 mod rust_generator;
@@ -14,6 +19,194 @@ pub use rust_generator::Config as RustGenConfig;
 
 pub static RUST_STD_LIB: Lazy<RustStdLib> = Lazy::new(RustStdLib::new);
 
+/// Find type of package which type reference points to: by id, by full name or by name relative
+/// to `namespace`, because packages made by [PackageGenerator] can contain unresolved references
+pub(crate) fn find_type<'a>(package: &'a Package, type_ref: &TypeRef, namespace: &str) -> Option<&'a Type> {
+    package.types.iter().find(|t| match type_ref {
+        TypeRef::Id(id) => t.id == *id,
+        TypeRef::Name(name) => {
+            format!("{}.{}", t.namespace, t.name) == *name || (t.namespace == namespace && t.name == *name)
+        }
+        _ => false
+    })
+}
+
+/// Replace names in type reference by types which `f` returns for them
+pub(crate) fn map_names(type_ref: &TypeRef, f: &impl Fn(&str) -> Option<TypeRef>) -> TypeRef {
+    match type_ref {
+        TypeRef::Name(name) => f(name).unwrap_or_else(|| type_ref.clone()),
+        TypeRef::Id(_) => type_ref.clone(),
+        TypeRef::Generic(base, args) => TypeRef::Generic(
+            Box::new(map_names(base, f)),
+            args.iter().map(|arg| map_names(arg, f)).collect()
+        ),
+        TypeRef::Tuple(types) => TypeRef::Tuple(types.iter().map(|t| map_names(t, f)).collect()),
+        TypeRef::Fn(return_type, args) => TypeRef::Fn(
+            return_type.as_ref().map(|return_type| Box::new(map_names(return_type, f))),
+            args.iter().map(|arg| map_names(arg, f)).collect()
+        )
+    }
+}
+
+/// Replace ids in type reference by types which `f` returns for them
+pub(crate) fn map_ids(type_ref: &TypeRef, f: &impl Fn(u64) -> TypeRef) -> TypeRef {
+    match type_ref {
+        TypeRef::Name(_) => type_ref.clone(),
+        TypeRef::Id(id) => f(*id),
+        TypeRef::Generic(base, args) => TypeRef::Generic(
+            Box::new(map_ids(base, f)),
+            args.iter().map(|arg| map_ids(arg, f)).collect()
+        ),
+        TypeRef::Tuple(types) => TypeRef::Tuple(types.iter().map(|t| map_ids(t, f)).collect()),
+        TypeRef::Fn(return_type, args) => TypeRef::Fn(
+            return_type.as_ref().map(|return_type| Box::new(map_ids(return_type, f))),
+            args.iter().map(|arg| map_ids(arg, f)).collect()
+        )
+    }
+}
+
+/// Name of type reference usable as part of Rust identifier, for example `GenericsTestMyStruct`
+fn get_instance_name(package: &Package, type_ref: &TypeRef) -> String {
+    match type_ref {
+        TypeRef::Name(name) => {
+            let name = name.rsplit('.').next().unwrap_or(name);
+            let mut chars = name.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new()
+            }
+        }
+        TypeRef::Id(id) => match package.types.iter().find(|t| t.id == *id) {
+            Some(t) => t.name.clone(),
+            None => format!("{:x}", id)
+        },
+        TypeRef::Generic(base, args) => {
+            let args = args.iter().map(|arg| get_instance_name(package, arg)).collect::<String>();
+            format!("{}{}", get_instance_name(package, base), args)
+        }
+        TypeRef::Tuple(types) => {
+            let types = types.iter().map(|t| get_instance_name(package, t)).collect::<String>();
+            format!("Tuple{}", types)
+        }
+        TypeRef::Fn(return_type, args) => {
+            let args = args.iter().map(|arg| get_instance_name(package, arg)).collect::<String>();
+            match return_type {
+                Some(return_type) => format!("Fn{}{}", args, get_instance_name(package, return_type)),
+                None => format!("Fn{}", args)
+            }
+        }
+    }
+}
+
+/// Type references of members of type: their arguments, return types, fields, properties and parents
+pub(crate) fn get_member_types(kind: &mut TypeKind) -> Vec<&mut TypeRef> {
+    let mut type_refs = Vec::new();
+    match kind {
+        TypeKind::Class {
+            constructors, properties, fields, static_properties, static_fields, methods, parents, ..
+        } => {
+            type_refs.extend(constructors.iter_mut().flat_map(|ctor| ctor.args.iter_mut().map(|arg| &mut arg.1)));
+            type_refs.extend(properties.iter_mut().chain(static_properties).map(|prop| &mut prop.prop_type));
+            type_refs.extend(fields.iter_mut().chain(static_fields).map(|field| &mut field.field_type));
+            for method in methods {
+                type_refs.extend(method.args.iter_mut().map(|arg| &mut arg.1));
+                type_refs.extend(method.return_type.as_mut());
+            }
+            type_refs.extend(parents.iter_mut());
+        }
+        TypeKind::Enum { .. } => {}
+        TypeKind::EnumClass { variants, methods } => {
+            type_refs.extend(variants.iter_mut().flat_map(|variant| variant.fields.iter_mut().map(|field| &mut field.field_type)));
+            for method in methods {
+                type_refs.extend(method.args.iter_mut().map(|arg| &mut arg.1));
+                type_refs.extend(method.return_type.as_mut());
+            }
+        }
+        TypeKind::Interface { properties, methods, parents } => {
+            type_refs.extend(properties.iter_mut().map(|prop| &mut prop.prop_type));
+            for method in methods {
+                type_refs.extend(method.args.iter_mut().map(|arg| &mut arg.1));
+                type_refs.extend(method.return_type.as_mut());
+            }
+            type_refs.extend(parents.iter_mut());
+        }
+        TypeKind::Struct { constructors, fields, static_fields } => {
+            type_refs.extend(constructors.iter_mut().flat_map(|ctor| ctor.args.iter_mut().map(|arg| &mut arg.1)));
+            type_refs.extend(fields.iter_mut().chain(static_fields).map(|field| &mut field.field_type));
+        }
+        TypeKind::TypeAlias(alias) => type_refs.push(alias.as_mut())
+    }
+    type_refs
+}
+
+/// Type with members named as in Rust code. Members renamed by `#[tangara(rename = "...")]`
+/// keep their Rust names in `RustName` attribute, type itself keeps its new name.
+pub(crate) fn with_rust_names(t: &Type) -> Type {
+    fn restore(name: &mut String, attrs: &[Attribute]) {
+        if let Some(rust_name) = RUST_STD_LIB.get_rust_name(attrs) {
+            *name = rust_name;
+        }
+    }
+    let mut t = t.clone();
+    match &mut t.kind {
+        TypeKind::Class { properties, fields, static_properties, static_fields, methods, .. } => {
+            properties.iter_mut().chain(static_properties).for_each(|prop| restore(&mut prop.name, &prop.attrs));
+            fields.iter_mut().chain(static_fields).for_each(|field| restore(&mut field.name, &field.attrs));
+            methods.iter_mut().for_each(|method| restore(&mut method.name, &method.attrs));
+        }
+        TypeKind::EnumClass { methods, .. } => {
+            methods.iter_mut().for_each(|method| restore(&mut method.name, &method.attrs));
+        }
+        TypeKind::Interface { properties, methods, .. } => {
+            properties.iter_mut().for_each(|prop| restore(&mut prop.name, &prop.attrs));
+            methods.iter_mut().for_each(|method| restore(&mut method.name, &method.attrs));
+        }
+        TypeKind::Struct { fields, static_fields, .. } => {
+            fields.iter_mut().chain(static_fields).for_each(|field| restore(&mut field.name, &field.attrs));
+        }
+        TypeKind::Enum { .. } | TypeKind::TypeAlias(_) => {}
+    }
+    t
+}
+
+/// Make concrete type from generic type `t` by substituting its generic parameters with `args`.
+/// Returns it with reference to instantiation which its id is made from.
+fn instantiate(package: &Package, t: &Type, args: &[TypeRef]) -> (Type, TypeRef) {
+    let find = |name: &str| find_type(package, &TypeRef::Name(name.to_string()), &t.namespace);
+    // types of this package are referenced by ids to get the same id however arguments are written,
+    // but members reference them by names as other members of package do
+    let type_ref = TypeRef::Generic(
+        Box::new(TypeRef::Id(t.id)),
+        args.iter().map(|arg| map_names(arg, &|name| find(name).map(|found| TypeRef::Id(found.id)))).collect()
+    );
+    let substitution = t.generics.0.iter()
+        .cloned()
+        .zip(args.iter().map(|arg| map_names(arg, &|name| find(name).map(|found| TypeRef::Name(found.name.clone())))))
+        .collect::<HashMap<String, TypeRef>>();
+    let mut instance = t.clone();
+    instance.id = generate_instance_id(&type_ref);
+    instance.name = get_instance_name(package, &type_ref);
+    instance.generics = Generics(vec![], vec![]);
+    // ids of members aren't changed, so instances share them with generic type
+    for member_type in get_member_types(&mut instance.kind) {
+        *member_type = map_names(member_type, &|name| substitution.get(name).cloned());
+    }
+    (instance, type_ref)
+}
+
+/// Concrete instantiations of generic type `t` which are declared in `instances` (see [RustGenConfig::instances])
+pub(crate) fn get_instances(package: &Package, instances: &[TypeRef], t: &Type) -> Vec<(Type, TypeRef)> {
+    instances.iter()
+        .filter_map(|instance| match instance {
+            TypeRef::Generic(base, args) => {
+                let base = find_type(package, base, &t.namespace)?;
+                (base.id == t.id && args.len() == t.generics.0.len()).then(|| instantiate(package, t, args))
+            }
+            _ => None
+        })
+        .collect()
+}
+
 pub struct RustStdLib {
     rust_std: Package,
     mutable_attribute: Type,
@@ -25,6 +218,15 @@ pub struct RustStdLib {
     tuple_field_attribute: Type,
     tuple_variant_attribute: Type,
     return_attribute: Type,
+    const_attribute: Type,
+    method_instance_attribute: Type,
+    rust_name_attribute: Type,
+}
+
+fn create_attribute(rust_std: Rc<RefCell<PackageBuilder>>, name: &str) -> ClassBuilder {
+    let mut attribute = create_class(rust_std, name);
+    attribute.inherits(TANGARA_STD.attribute());
+    attribute
 }
 
 impl RustStdLib {
@@ -37,29 +239,38 @@ impl RustStdLib {
             rust_std_ref.method_visibility = Visibility::Public;
             rust_std_ref.constructor_visibility = Visibility::Public;
             rust_std_ref.set_namespace("Tangara.Rust.Metadata");
+            rust_std_ref.add_dependency(Dependency::from(&TANGARA_STD.get_package()));
         }
-        // TODO inherits from Tangara.Std.Attribute
-        let mut struct_field_attribute = create_class(rust_std.clone(), "StructField");
-        let mut mutable_attribute = create_class(rust_std.clone(), "Mutable");
-        let mut reference_attribute = create_class(rust_std.clone(), "Reference");
-        let mut lifetime_attribute = create_class(rust_std.clone(), "Lifetime");
+        let mut struct_field_attribute = create_attribute(rust_std.clone(), "StructField");
+        let mut mutable_attribute = create_attribute(rust_std.clone(), "Mutable");
+        let mut reference_attribute = create_attribute(rust_std.clone(), "Reference");
+        let mut lifetime_attribute = create_attribute(rust_std.clone(), "Lifetime");
         lifetime_attribute.add_property(TypeRef::from("String"), "Lifetime")
             .setter_visibility(Visibility::Public).build();
-        let mut lifetime_generic_attribute = create_class(rust_std.clone(), "LifetimeGeneric");
+        let mut lifetime_generic_attribute = create_attribute(rust_std.clone(), "LifetimeGeneric");
         lifetime_generic_attribute.add_property(TypeRef::from("String"), "Bounded")
             .setter_visibility(Visibility::Public).build();
         lifetime_generic_attribute.add_property(TypeRef::from("String"), "Lifetime")
             .setter_visibility(Visibility::Public).build();
-        let mut constructor_name_attribute = create_class(rust_std.clone(), "ConstructorFnName");
+        let mut constructor_name_attribute = create_attribute(rust_std.clone(), "ConstructorFnName");
         constructor_name_attribute.add_property(TypeRef::from("String"), "FnName")
             .setter_visibility(Visibility::Public).build();
-        let mut tuple_field_attribute = create_class(rust_std.clone(), "TupleField");
+        let mut tuple_field_attribute = create_attribute(rust_std.clone(), "TupleField");
         tuple_field_attribute.add_property(TypeRef::from("UShort"), "Index")
             .setter_visibility(Visibility::Public).build();
-        let mut tuple_variant_attribute = create_class(rust_std.clone(), "TupleVariant");
-        let mut return_attribute = create_class(rust_std.clone(), "Return");
+        let mut tuple_variant_attribute = create_attribute(rust_std.clone(), "TupleVariant");
+        let mut return_attribute = create_attribute(rust_std.clone(), "Return");
         return_attribute.add_property(TypeRef::from("String"), "Prefix")
             .setter_visibility(Visibility::Public).build();
+        let const_attribute = create_attribute(rust_std.clone(), "Const");
+        let mut method_instance_attribute = create_attribute(rust_std.clone(), "MethodInstance");
+        method_instance_attribute.add_property(TypeRef::from("String"), "FnName")
+            .setter_visibility(Visibility::Public).build();
+        method_instance_attribute.add_property(TypeRef::from("String"), "Generics")
+            .setter_visibility(Visibility::Public).build();
+        let mut rust_name_attribute = create_attribute(rust_std.clone(), "RustName");
+        rust_name_attribute.add_property(TypeRef::from("String"), "Name")
+            .setter_visibility(Visibility::Public).build();
 
         // Build classes
         let struct_field_attribute = struct_field_attribute.build();
@@ -71,6 +282,9 @@ impl RustStdLib {
         let tuple_field_attribute = tuple_field_attribute.build();
         let tuple_variant_attribute = tuple_variant_attribute.build();
         let return_attribute = return_attribute.build();
+        let const_attribute = const_attribute.build();
+        let method_instance_attribute = method_instance_attribute.build();
+        let rust_name_attribute = rust_name_attribute.build();
         let rust_std = rust_std.borrow().build();
 
         Self {
@@ -84,6 +298,9 @@ impl RustStdLib {
             tuple_field_attribute,
             tuple_variant_attribute,
             return_attribute,
+            const_attribute,
+            method_instance_attribute,
+            rust_name_attribute,
         }
     }
 
@@ -128,6 +345,21 @@ impl RustStdLib {
         Attribute(TypeRef::from(&self.return_attribute), vec![Value::from(return_prefix)])
     }
 
+    /// Attribute of static field made from associated constant, so it can't be changed
+    pub fn const_attribute(&self) -> Attribute {
+        Attribute(TypeRef::from(&self.const_attribute), vec![])
+    }
+
+    /// Attribute of method which instantiates generic function `fn_name` with `generics` written in Rust
+    pub fn method_instance_attribute(&self, fn_name: &str, generics: &str) -> Attribute {
+        Attribute(TypeRef::from(&self.method_instance_attribute), vec![Value::from(fn_name), Value::from(generics)])
+    }
+
+    /// Attribute of type or member renamed by `#[tangara(rename = "...")]`, it keeps name from Rust code
+    pub fn rust_name_attribute(&self, name: &str) -> Attribute {
+        Attribute(TypeRef::from(&self.rust_name_attribute), vec![Value::from(name)])
+    }
+
     pub fn is_struct_field(&self, attrs: &[Attribute]) -> bool {
         // Cache type data for comparing
         let struct_field_data = get_typeref_bytes(&TypeRef::from(&self.struct_field_attribute));
@@ -146,6 +378,12 @@ impl RustStdLib {
         attrs.iter().any(|attr| get_typeref_bytes(&attr.0) == reference_data)
     }
 
+    pub fn is_const(&self, attrs: &[Attribute]) -> bool {
+        // Cache type data for comparing
+        let const_data = get_typeref_bytes(&TypeRef::from(&self.const_attribute));
+        attrs.iter().any(|attr| get_typeref_bytes(&attr.0) == const_data)
+    }
+
     /// Check if variant of enum class is tuple
     pub fn is_tuple_variant(&self, attrs: &[Attribute]) -> bool {
         // Cache type data for comparing
@@ -232,6 +470,32 @@ impl RustStdLib {
         })
     }
 
+    /// Check attributes on `MethodInstance` attribute and returns its values (`FnName` and `Generics`) if it exists.
+    pub fn get_method_instance(&self, attrs: &[Attribute]) -> Option<(String, String)> {
+        let method_instance_data = get_typeref_bytes(&TypeRef::from(&self.method_instance_attribute));
+        attrs.iter().find_map(|attr| {
+            if get_typeref_bytes(&attr.0) == method_instance_data {
+                if let (Value::String(fn_name), Value::String(generics)) = (&attr.1[0], &attr.1[1]) {
+                    return Some((fn_name.clone(), generics.clone()));
+                }
+            }
+            None
+        })
+    }
+
+    /// Check attributes on `RustName` attribute and returns his 1st value (`Name`) if it exists.
+    pub fn get_rust_name(&self, attrs: &[Attribute]) -> Option<String> {
+        let rust_name_data = get_typeref_bytes(&TypeRef::from(&self.rust_name_attribute));
+        attrs.iter().find_map(|attr| {
+            if get_typeref_bytes(&attr.0) == rust_name_data {
+                if let Value::String(name) = &attr.1[0] {
+                    return Some(name.clone());
+                }
+            }
+            None
+        })
+    }
+
     /// Check attributes on `Return` attribute and returns his 1st value (`Prefix`) if it exists.
     pub fn get_return_prefix(&self, attrs: &[Attribute]) -> Option<String> {
         let return_data = get_typeref_bytes(&TypeRef::from(&self.return_attribute));
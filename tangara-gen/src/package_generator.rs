@@ -4,10 +4,14 @@ use std::ops::Deref;
 use std::path::Path;
 use std::rc::Rc;
 use syn::*;
+use syn::parse::Parser;
 use syn::punctuated::Punctuated;
 use tangara_highlevel::builder::*;
-use tangara_highlevel::{Attribute, MethodKind, NamingConventions, Package, TypeKind, TypeRef, Value, Visibility as TgVis};
-use crate::RUST_STD_LIB;
+use tangara_highlevel::{Attribute, Dependency, generate_abi_fingerprint, generate_type_id, MethodKind, NamingConventions, Package, TypeKind, TypeRef, Value, Version, Visibility as TgVis};
+use tangara_highlevel::resolver::{ResolveMode, Resolver};
+use tangara_highlevel::tangara_std::TANGARA_STD;
+use tangara_highlevel::validation::Diagnostic;
+use crate::{get_member_types, map_names, RUST_STD_LIB};
 
 pub struct Config {
     /// Names of traits which we **don't** need inherit from
@@ -17,11 +21,19 @@ pub struct Config {
     /// Function names that implemented as constructors.
     ///
     /// For example: if we have there name `new`, `MyStruct::new(args)` will added to type as constructor.
+    /// Functions marked by `#[tangara(ctor)]` are constructors too.
     /// Default: `"new"`
     pub ctor_names: Vec<String>,
     /// Generate properties from get_, set_ pair methods
     /// Default: `true`
-    pub generate_properties: bool
+    pub generate_properties: bool,
+    /// Instantiations of generic methods, because generic functions can't be called through `extern "C"`.
+    ///
+    /// Key is path of method like `MyStruct::convert` and value is list of its generic arguments
+    /// written in Rust for every instantiation, for example `"u32"` or `"String, u64"`.
+    /// Every instantiation is added to type as separate method named like `convert_u32`.
+    /// Default: empty
+    pub method_instances: HashMap<String, Vec<String>>
 }
 
 impl Default for Config {
@@ -29,7 +41,8 @@ impl Default for Config {
         Self {
             dont_inherit_traits: vec!["Default".to_string(), "From".to_string()],
             ctor_names: vec!["new".to_string()],
-            generate_properties: true
+            generate_properties: true,
+            method_instances: HashMap::new()
         }
     }
 }
@@ -37,7 +50,13 @@ impl Default for Config {
 pub struct PackageGenerator {
     config: Config,
     package_builder: Rc<RefCell<PackageBuilder>>,
-    structs: HashMap<String, ClassBuilder>
+    structs: HashMap<String, ClassBuilder>,
+    /// Paths from `use` items with `.` divider, used for resolving type references
+    imports: Vec<String>,
+    /// New names of types renamed by `#[tangara(rename = "...")]` by their full Rust names
+    renames: HashMap<String, String>,
+    /// Rust names of structs marked by `#[tangara(skip)]`
+    skipped: Vec<String>
 }
 
 fn get_from_path(syn_path: &syn::Path) -> TypeRef {
@@ -166,22 +185,31 @@ fn get_value(expr: &Expr) -> Option<Value> {
     }
 }
 
+/// Reference to generic type, Rust's `Option` and `Result` are mapped to types of `Tangara.Std`
+fn get_generic_typeref(typeref: TypeRef, mut generics: Vec<TypeRef>) -> TypeRef {
+    if let TypeRef::Name(name) = &typeref {
+        match (name.as_str(), generics.len()) {
+            ("Option" | "std.option.Option" | "core.option.Option", 1) => {
+                return TANGARA_STD.option(generics.remove(0));
+            }
+            ("Result" | "std.result.Result" | "core.result.Result", 2) => {
+                let error = generics.remove(1);
+                return TANGARA_STD.result(generics.remove(0), error);
+            }
+            _ => {}
+        }
+    }
+    TypeRef::Generic(Box::new(typeref), generics)
+}
+
 fn get_typeref(t: &Type) -> Option<(TypeRef, Vec<Attribute>)> {
     match t {
         Type::Array(array_type) => {
             let arr_len = get_value(&array_type.len).expect("Array length value can't be None");
-            let mut attrs = vec![Attribute(
-                TypeRef::from("Tangara.Metadata.ArraySize"), vec![arr_len]
-            )];
+            let mut attrs = vec![TANGARA_STD.array_size_attribute(arr_len)];
             let (array_type, mut arr_attrs) = get_typeref(&array_type.elem).expect("Array type can't be None");
             attrs.append(&mut arr_attrs);
-            Some((
-                TypeRef::Generic(
-                    Box::new(TypeRef::from("Tangara.Std.Array")),
-                    vec![array_type]
-                ),
-                attrs
-            ))
+            Some((TANGARA_STD.array(array_type), attrs))
         },
         Type::BareFn(fn_type) => {
             // Parse return type
@@ -244,7 +272,7 @@ fn get_typeref(t: &Type) -> Option<(TypeRef, Vec<Attribute>)> {
                             }
                         }
                         if generics.len() > 0 {
-                            Some((TypeRef::Generic(Box::new(typeref), generics), attrs))
+                            Some((get_generic_typeref(typeref, generics), attrs))
                         } else {
                             Some((typeref, attrs))
                         }
@@ -265,13 +293,7 @@ fn get_typeref(t: &Type) -> Option<(TypeRef, Vec<Attribute>)> {
             }
             let (ptr_typeref, mut ptr_attrs) = get_typeref(&ptr_type.elem).expect("Pointer type cannot be None");
             attrs.append(&mut ptr_attrs);
-            Some((
-                TypeRef::Generic(
-                    Box::new(TypeRef::from("Tangara.Std.Ptr")),
-                    vec![ptr_typeref]
-                ),
-                attrs
-            ))
+            Some((TANGARA_STD.ptr(ptr_typeref), attrs))
         },
         Type::Reference(ref_type) => {
             let mut attrs = vec![RUST_STD_LIB.reference_attribute()];
@@ -287,13 +309,7 @@ fn get_typeref(t: &Type) -> Option<(TypeRef, Vec<Attribute>)> {
         },
         Type::Slice(slice_type) => {
             let (slice_typeref, attrs) = get_typeref(&slice_type.elem).expect("Slice type cannot be None");
-            Some((
-                TypeRef::Generic(
-                    Box::new(TypeRef::from("Tangara.Std.Array")),
-                    vec![slice_typeref]
-                ),
-                attrs
-            ))
+            Some((TANGARA_STD.array(slice_typeref), attrs))
         },
         Type::TraitObject(_) => None,
         Type::Tuple(tuple_type) => {
@@ -423,15 +439,321 @@ fn parse_generics<T: GenericsCollector + AttributeCollector>(builder: &mut T, ge
     builder.generic_wheres(generic_wheres);
 }
 
+/// Add method made from signature of Rust function to class
+fn parse_method(cb: &mut ClassBuilder, name: &str, vis: &Visibility, fn_sig: &Signature, attrs: Vec<Attribute>) {
+    let mut fn_builder = cb.add_method(name);
+    fn_builder.set_visibility(get_visibility(vis));
+    for attr in attrs {
+        fn_builder.add_attribute(attr);
+    }
+    parse_generics(&mut fn_builder, &fn_sig.generics);
+    parse_return_type(&mut fn_builder, &fn_sig.output);
+
+    // Parse arguments
+    let mut is_self = false;
+    let mut is_self_mut = false;
+    let mut is_self_ref = false;
+    for arg in &fn_sig.inputs {
+        match arg {
+            FnArg::Receiver(self_arg) => {
+                // TODO add handling of lifetime
+                is_self = true;
+                is_self_mut = self_arg.mutability.is_some();
+                is_self_ref = self_arg.reference.is_some();
+            }
+            FnArg::Typed(fn_arg) => {
+                // TODO add checks on Self type
+                parse_arg(&mut fn_builder, fn_arg);
+            }
+        }
+    }
+    if is_self {
+        if is_self_mut {
+            fn_builder.add_attribute(RUST_STD_LIB.mutable_attribute());
+        }
+        if is_self_ref {
+            fn_builder.add_attribute(RUST_STD_LIB.reference_attribute());
+        }
+    } else {
+        fn_builder.set_kind(MethodKind::Static);
+    }
+
+    fn_builder.build();
+}
+
+/// Replace generic parameters in Rust type by concrete types
+fn substitute_type(ty: &Type, substitution: &HashMap<String, Type>) -> Type {
+    let mut ty = ty.clone();
+    match &mut ty {
+        Type::Path(type_path) => {
+            if let Some(concrete) = type_path.path.get_ident().and_then(|ident| substitution.get(&ident.to_string())) {
+                return concrete.clone();
+            }
+            for seg in &mut type_path.path.segments {
+                if let PathArguments::AngleBracketed(angle) = &mut seg.arguments {
+                    for arg in &mut angle.args {
+                        if let GenericArgument::Type(arg_type) = arg {
+                            *arg_type = substitute_type(arg_type, substitution);
+                        }
+                    }
+                }
+            }
+        }
+        Type::Reference(reference) => *reference.elem = substitute_type(&reference.elem, substitution),
+        Type::Ptr(ptr) => *ptr.elem = substitute_type(&ptr.elem, substitution),
+        Type::Slice(slice) => *slice.elem = substitute_type(&slice.elem, substitution),
+        Type::Array(array) => *array.elem = substitute_type(&array.elem, substitution),
+        Type::Paren(paren) => *paren.elem = substitute_type(&paren.elem, substitution),
+        Type::Group(group) => *group.elem = substitute_type(&group.elem, substitution),
+        Type::Tuple(tuple) => {
+            for elem in &mut tuple.elems {
+                *elem = substitute_type(elem, substitution);
+            }
+        }
+        _ => {}
+    }
+    ty
+}
+
+/// Signature of generic function with its type parameters replaced by `generics`
+/// written in Rust like `"String, u64"`. Lifetimes of function are kept.
+fn instantiate_signature(fn_sig: &Signature, generics: &str) -> Signature {
+    let args = Punctuated::<Type, Token![,]>::parse_terminated.parse_str(generics)
+        .unwrap_or_else(|err| panic!("Can't parse generic arguments '{}': {}", generics, err));
+    let params = fn_sig.generics.type_params().map(|param| param.ident.to_string()).collect::<Vec<String>>();
+    if params.len() != args.len() {
+        panic!("Function '{}' has {} generic parameters but {} arguments are given: '{}'",
+               fn_sig.ident, params.len(), args.len(), generics);
+    }
+    let substitution = params.into_iter().zip(args).collect::<HashMap<String, Type>>();
+    let mut instance_sig = fn_sig.clone();
+    instance_sig.generics.params = fn_sig.generics.params.iter()
+        .filter(|param| matches!(param, GenericParam::Lifetime(_)))
+        .cloned()
+        .collect();
+    instance_sig.generics.where_clause = None;
+    for arg in &mut instance_sig.inputs {
+        if let FnArg::Typed(fn_arg) = arg {
+            *fn_arg.ty = substitute_type(&fn_arg.ty, &substitution);
+        }
+    }
+    if let ReturnType::Type(_, return_type) = &mut instance_sig.output {
+        **return_type = substitute_type(return_type, &substitution);
+    }
+    instance_sig
+}
+
+/// Suffix of instantiation's name made from its generic arguments: `"String, Vec<u8>"` becomes `string_vec_u8`
+fn get_instance_suffix(generics: &str) -> String {
+    let mut suffix = String::with_capacity(generics.len());
+    let mut prev = '_';
+    for c in generics.chars() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && prev.is_lowercase() {
+                suffix.push('_');
+            }
+            suffix.extend(c.to_lowercase());
+        } else if !suffix.is_empty() && !suffix.ends_with('_') {
+            suffix.push('_');
+        }
+        prev = c;
+    }
+    suffix.trim_end_matches('_').to_string()
+}
+
+/// Options from `#[tangara(...)]` attributes of item in parsed code
+#[derive(Default)]
+struct TangaraAttrs {
+    /// `skip`: item is not added to package
+    skip: bool,
+    /// `rename = "..."`: name of item in package
+    rename: Option<String>,
+    /// `ctor`: function is constructor
+    ctor: bool,
+    /// `property`: `get_`/`set_` function is accessor of property
+    property: bool,
+    /// `attr(Path, values...)`: attributes added to item in package
+    attrs: Vec<Attribute>
+}
+
+impl TangaraAttrs {
+    /// Parse all `#[tangara(...)]` attributes of item. Panics on unknown or malformed options.
+    fn parse(attrs: &[syn::Attribute]) -> Self {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("tangara")) {
+            if let Meta::Path(_) = attr.meta {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    result.skip = true;
+                } else if meta.path.is_ident("rename") {
+                    result.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("ctor") {
+                    result.ctor = true;
+                } else if meta.path.is_ident("property") {
+                    result.property = true;
+                } else if meta.path.is_ident("attr") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    let attr_type = get_from_path(&content.parse::<syn::Path>()?);
+                    let mut values = vec![];
+                    while content.parse::<Option<Token![,]>>()?.is_some() && !content.is_empty() {
+                        let expr = content.parse::<Expr>()?;
+                        values.push(get_value(&expr).ok_or_else(|| meta.error("attribute value must be literal"))?);
+                    }
+                    result.attrs.push(Attribute(attr_type, values));
+                } else {
+                    return Err(meta.error("unknown tangara option"));
+                }
+                Ok(())
+            }).unwrap_or_else(|err| panic!("Invalid #[tangara] attribute: {}", err));
+        }
+        result
+    }
+
+    /// Name of item in package
+    fn get_name(&self, rust_name: &str) -> String {
+        self.rename.clone().unwrap_or_else(|| rust_name.to_string())
+    }
+
+    /// Attributes added to item in package. Renamed item keeps its name from Rust code in `RustName` attribute.
+    fn get_attrs(&self, rust_name: &str) -> Vec<Attribute> {
+        let mut attrs = self.attrs.clone();
+        if self.rename.is_some() {
+            attrs.push(RUST_STD_LIB.rust_name_attribute(rust_name));
+        }
+        attrs
+    }
+}
+
+/// Reference to type renamed by `#[tangara(rename = "...")]` which is referenced by `name` from `namespace`
+fn get_renamed(renames: &HashMap<String, String>, name: &str, namespace: &str) -> Option<TypeRef> {
+    let full_name = if renames.contains_key(name) {
+        name.to_string()
+    } else {
+        format!("{}.{}", namespace, name)
+    };
+    let new_name = renames.get(&full_name)?;
+    // only last segment of reference is renamed, so it stays relative if it was
+    Some(TypeRef::Name(match name.rsplit_once('.') {
+        Some((prefix, _)) => format!("{}.{}", prefix, new_name),
+        None => new_name.clone()
+    }))
+}
+
+/// Getter and setter of property marked by `#[tangara(property)]` in 'impl' block
+struct PropertyAccessors {
+    /// Type of property taken from getter's return type
+    prop_type: Option<(TypeRef, Vec<Attribute>)>,
+    getter_visibility: TgVis,
+    setter_visibility: Option<TgVis>,
+    /// Accessors don't have `self` argument
+    is_static: bool,
+    /// Options of both accessors
+    tg_attrs: TangaraAttrs
+}
+
+/// Add `get_`/`set_` function to accessors of property named as function without prefix
+fn add_property_accessor(properties: &mut Vec<(String, PropertyAccessors)>, fn_item: &ImplItemFn, tg_attrs: TangaraAttrs) {
+    let fn_name = fn_item.sig.ident.to_string();
+    let (is_getter, prop_name) = if let Some(prop_name) = fn_name.strip_prefix("get_") {
+        (true, prop_name)
+    } else if let Some(prop_name) = fn_name.strip_prefix("set_") {
+        (false, prop_name)
+    } else {
+        panic!("Property accessor '{}' must start with 'get_' or 'set_'", fn_name);
+    };
+    let index = match properties.iter().position(|(name, _)| name == prop_name) {
+        Some(index) => index,
+        None => {
+            properties.push((prop_name.to_string(), PropertyAccessors {
+                prop_type: None,
+                getter_visibility: TgVis::Private,
+                setter_visibility: None,
+                is_static: false,
+                tg_attrs: TangaraAttrs::default()
+            }));
+            properties.len() - 1
+        }
+    };
+    let accessors = &mut properties[index].1;
+    accessors.is_static = !matches!(fn_item.sig.inputs.first(), Some(FnArg::Receiver(_)));
+    if is_getter {
+        match &fn_item.sig.output {
+            ReturnType::Type(_, return_type) => accessors.prop_type = get_typeref(return_type),
+            ReturnType::Default => panic!("Getter '{}' of property must return value", fn_name)
+        }
+        accessors.getter_visibility = get_visibility(&fn_item.vis);
+    } else {
+        accessors.setter_visibility = Some(get_visibility(&fn_item.vis));
+    }
+    if tg_attrs.rename.is_some() {
+        accessors.tg_attrs.rename = tg_attrs.rename;
+    }
+    accessors.tg_attrs.attrs.extend(tg_attrs.attrs);
+}
+
+/// Variants are always exported with their Rust names
+fn warn_variant_attrs(tg_attrs: &TangaraAttrs, ident: &Ident) {
+    if tg_attrs.skip || tg_attrs.rename.is_some() || tg_attrs.ctor || tg_attrs.property {
+        println!("[Warning] (tangara-gen::PackageGenerator) Only 'attr' option of #[tangara] \
+        is supported for enum variant {}. Ignoring others.", ident);
+    }
+}
+
 impl PackageGenerator {
     pub fn new(package_name: &str, config: Config) -> Self {
+        let package_builder = PackageBuilder::new(package_name, NamingConventions::rust());
+        // generated types reference Tangara.Std and are marked by attributes from Tangara.Rust
+        package_builder.borrow_mut()
+            .add_dependency(Dependency::from(&TANGARA_STD.get_package()))
+            .add_dependency(Dependency::from(&RUST_STD_LIB.get_package()));
         Self {
             config,
-            package_builder: PackageBuilder::new(package_name, NamingConventions::rust()),
-            structs: HashMap::new()
+            package_builder,
+            structs: HashMap::new(),
+            imports: vec![],
+            renames: HashMap::new(),
+            skipped: vec![]
         }
     }
 
+    fn parse_use_tree(&mut self, tree: &UseTree, prefix: String) {
+        let join = |name: String| if prefix.is_empty() { name } else { format!("{prefix}.{name}") };
+        match tree {
+            UseTree::Path(use_path) => {
+                let namespace = self.package_builder.borrow().get_namespace();
+                let segment = match use_path.ident.to_string().as_str() {
+                    "crate" => self.package_builder.borrow().get_name(),
+                    "self" => namespace,
+                    "super" => namespace.rsplit_once('.').map(|(parent, _)| parent.to_string()).unwrap_or_default(),
+                    name => join(name.to_string())
+                };
+                self.parse_use_tree(&use_path.tree, segment);
+            }
+            UseTree::Name(use_name) => self.imports.push(join(use_name.ident.to_string())),
+            UseTree::Glob(_) => self.imports.push(join("*".to_string())),
+            UseTree::Group(use_group) => {
+                for item in &use_group.items {
+                    self.parse_use_tree(item, prefix.clone());
+                }
+            }
+            UseTree::Rename(_) => {
+                println!("[Warning] (tangara-gen::PackageGenerator) Renames in 'use' are not supported.");
+            }
+        }
+    }
+
+    /// Name of type in package. Renamed type is remembered to fix references to it after parsing.
+    fn rename_type(&mut self, rust_name: &str, tg_attrs: &TangaraAttrs) -> String {
+        if let Some(new_name) = &tg_attrs.rename {
+            let namespace = self.package_builder.borrow().get_namespace();
+            self.renames.insert(format!("{}.{}", namespace, rust_name), new_name.clone());
+        }
+        tg_attrs.get_name(rust_name)
+    }
+
     fn get_or_create_struct(&mut self, name: String) -> &mut ClassBuilder {
         self.structs.entry(name.clone()).or_insert(create_class(self.package_builder.clone(), &name))
     }
@@ -439,15 +761,28 @@ impl PackageGenerator {
     fn parse_item(&mut self, item: &Item) {
         match item {
             Item::Enum(enum_item) => {
-                let enum_name = enum_item.ident.to_string();
+                let tg_attrs = TangaraAttrs::parse(&enum_item.attrs);
+                if tg_attrs.skip {
+                    return;
+                }
+                let rust_name = enum_item.ident.to_string();
+                let enum_name = self.rename_type(&rust_name, &tg_attrs);
                 let enum_vis = get_visibility(&enum_item.vis);
                 let is_enum_class = enum_item.variants.iter().any(|v| v.fields != Fields::Unit);
                 if is_enum_class {
                     let mut builder = create_enum_class(self.package_builder.clone(), &enum_name);
                     builder.set_visibility(enum_vis);
+                    for attr in tg_attrs.get_attrs(&rust_name) {
+                        TypeBuilder::add_attribute(&mut builder, attr);
+                    }
                     parse_generics(&mut builder, &enum_item.generics);
                     for variant in &enum_item.variants {
+                        let variant_attrs = TangaraAttrs::parse(&variant.attrs);
+                        warn_variant_attrs(&variant_attrs, &variant.ident);
                         let mut variant_builder = builder.variant(&variant.ident.to_string());
+                        for attr in variant_attrs.attrs {
+                            variant_builder.add_attribute(attr);
+                        }
                         // Count of fields
                         let mut count = 0;
                         if let Fields::Unnamed(_) = &variant.fields {
@@ -481,7 +816,16 @@ impl PackageGenerator {
                 else {
                     let mut builder = create_enum(self.package_builder.clone(), &enum_name);
                     builder.set_visibility(enum_vis);
+                    for attr in tg_attrs.get_attrs(&rust_name) {
+                        TypeBuilder::add_attribute(&mut builder, attr);
+                    }
                     for variant in &enum_item.variants {
+                        let variant_attrs = TangaraAttrs::parse(&variant.attrs);
+                        warn_variant_attrs(&variant_attrs, &variant.ident);
+                        if !variant_attrs.attrs.is_empty() {
+                            println!("[Warning] (tangara-gen::PackageGenerator) Attributes of variant {} \
+                            are ignored because variants of simple enum can't have them.", variant.ident);
+                        }
                         let variant_name = variant.ident.to_string();
                         if let Some((_, lit_value)) = &variant.discriminant {
                             builder.variant_value(
@@ -497,14 +841,22 @@ impl PackageGenerator {
                 }
             }
             Item::Impl(impl_item) => {
+                if TangaraAttrs::parse(&impl_item.attrs).skip {
+                    return;
+                }
                 let mut for_type = None;
                 // Check situation on 'impl Trait for Struct'
                 if let Some((_, type_name, _)) = &impl_item.trait_ {
                     for_type = Some(get_from_path(type_name));
                 }
-                if let (TypeRef::Name(type_name), _) = get_typeref(&impl_item.self_ty)
-                    .expect("Type in 'impl' cannot be None") {
+                // members of 'impl<T> Struct<T>' belong to generic type itself
+                let self_type = match get_typeref(&impl_item.self_ty).expect("Type in 'impl' cannot be None").0 {
+                    TypeRef::Generic(base, _) => *base,
+                    self_type => self_type
+                };
+                if let TypeRef::Name(type_name) = self_type {
                     let ctor_names = self.config.ctor_names.to_vec();
+                    let method_instances = self.config.method_instances.clone();
                     let dont_inherit_traits = self.config.dont_inherit_traits.to_vec();
 
                     let cb = self.get_or_create_struct(type_name.clone());
@@ -537,19 +889,31 @@ impl PackageGenerator {
                             }
                         }
                     }
+                    let mut properties = vec![];
                     for item_impl in &impl_item.items {
                         match item_impl {
                             ImplItem::Fn(fn_item) => {
-                                // TODO check on get_ set_ pair functions to generate properties
+                                let fn_tg_attrs = TangaraAttrs::parse(&fn_item.attrs);
+                                if fn_tg_attrs.skip {
+                                    continue;
+                                }
+                                if fn_tg_attrs.property {
+                                    add_property_accessor(&mut properties, fn_item, fn_tg_attrs);
+                                    continue;
+                                }
                                 let fn_sig = &fn_item.sig;
                                 let name = fn_sig.ident.to_string();
                                 // Check on constructor name
-                                if ctor_names.contains(&name) {
+                                if fn_tg_attrs.ctor || ctor_names.contains(&name) {
                                     // Make constructor
                                     let mut ctor_builder = cb.add_constructor();
                                     ctor_builder.set_visibility(get_visibility(&fn_item.vis));
                                     // Add attribute: name of 'fn' associated to this constructor
                                     ctor_builder.add_attribute(RUST_STD_LIB.constructor_name_attribute(&name));
+                                    // constructors have no names, so only attributes are taken
+                                    for attr in fn_tg_attrs.attrs {
+                                        ctor_builder.add_attribute(attr);
+                                    }
 
                                     // Check for generics emptiness
                                     if fn_sig.generics.params.len() > 0 {
@@ -613,48 +977,61 @@ impl PackageGenerator {
                                     ctor_builder.build();
                                 }
                                 else {
-                                    // Make function
-                                    let mut fn_builder = cb.add_method(&name);
-                                    fn_builder.set_visibility(get_visibility(&fn_item.vis));
-                                    parse_generics(&mut fn_builder, &fn_item.sig.generics);
-                                    parse_return_type(&mut fn_builder, &fn_sig.output);
-
-                                    // Parse arguments
-                                    let mut is_self = false;
-                                    let mut is_self_mut = false;
-                                    let mut is_self_ref = false;
-                                    for arg in &fn_sig.inputs {
-                                        match arg {
-                                            FnArg::Receiver(self_arg) => {
-                                                // TODO add handling of lifetime
-                                                is_self = true;
-                                                is_self_mut = self_arg.mutability.is_some();
-                                                is_self_ref = self_arg.reference.is_some();
-                                            }
-                                            FnArg::Typed(fn_arg) => {
-                                                // TODO add checks on Self type
-                                                parse_arg(&mut fn_builder, fn_arg);
-                                            }
-                                        }
+                                    let method_name = fn_tg_attrs.get_name(&name);
+                                    parse_method(cb, &method_name, &fn_item.vis, fn_sig, fn_tg_attrs.get_attrs(&name));
+                                    // generic method is called only through its declared instantiations
+                                    let method_path = format!("{}::{}", type_name, name);
+                                    for generics in method_instances.get(&method_path).into_iter().flatten() {
+                                        let instance_sig = instantiate_signature(fn_sig, generics);
+                                        let instance_name = format!("{}_{}", method_name, get_instance_suffix(generics));
+                                        let mut attrs = fn_tg_attrs.attrs.clone();
+                                        attrs.push(RUST_STD_LIB.method_instance_attribute(&name, generics));
+                                        parse_method(cb, &instance_name, &fn_item.vis, &instance_sig, attrs);
                                     }
-                                    if is_self {
-                                        if is_self_mut {
-                                            fn_builder.add_attribute(RUST_STD_LIB.mutable_attribute());
-                                        }
-                                        if is_self_ref {
-                                            fn_builder.add_attribute(RUST_STD_LIB.reference_attribute());
-                                        }
-                                    } else {
-                                        fn_builder.set_kind(MethodKind::Static);
-                                    }
-
-                                    fn_builder.build();
                                 }
                             }
+                            ImplItem::Const(const_item) => {
+                                let const_tg_attrs = TangaraAttrs::parse(&const_item.attrs);
+                                if const_tg_attrs.skip {
+                                    continue;
+                                }
+                                let const_name = const_item.ident.to_string();
+                                // associated constant is the closest thing to static field in Rust
+                                let (field_type, field_attrs) = get_typeref(&const_item.ty)
+                                    .expect("Constant cannot have type None");
+                                let mut field_builder = cb.add_static_field(field_type, &const_tg_attrs.get_name(&const_name));
+                                for attr in field_attrs.into_iter().chain(const_tg_attrs.get_attrs(&const_name)) {
+                                    field_builder.add_attribute(attr);
+                                }
+                                field_builder.add_attribute(RUST_STD_LIB.const_attribute());
+                                field_builder.set_visibility(get_visibility(&const_item.vis));
+                                field_builder.build();
+                            }
                             ImplItem::Type(_) => {} // TODO add checks in typeref making in function (return or args) on this type
                             _ => {}
                         }
                     }
+                    for (rust_name, accessors) in properties {
+                        let Some((prop_type, prop_attrs)) = accessors.prop_type else {
+                            println!("[Warning] (tangara-gen::PackageGenerator) Property {} has no getter. \
+                            Ignoring it.", rust_name);
+                            continue;
+                        };
+                        let prop_name = accessors.tg_attrs.get_name(&rust_name);
+                        let mut prop_builder = if accessors.is_static {
+                            cb.add_static_property(prop_type, &prop_name)
+                        } else {
+                            cb.add_property(prop_type, &prop_name)
+                        };
+                        prop_builder.getter_visibility(accessors.getter_visibility);
+                        if let Some(setter_visibility) = accessors.setter_visibility {
+                            prop_builder.setter_visibility(setter_visibility);
+                        }
+                        for attr in prop_attrs.into_iter().chain(accessors.tg_attrs.get_attrs(&rust_name)) {
+                            prop_builder.add_attribute(attr);
+                        }
+                        prop_builder.build();
+                    }
                 } // if let TypeRef::Name(type_name) = get_typeref(&impl_item.self_ty)
                 else {
                     println!("[Warning] (tangara-gen::PackageGenerator) TypeRef from 'impl' root must be Name");
@@ -681,25 +1058,42 @@ impl PackageGenerator {
                 builder.type_visibility = old_vis;
             }
             Item::Struct(struct_item) => {
-                let class_builder = self.get_or_create_struct(struct_item.ident.to_string());
+                let tg_attrs = TangaraAttrs::parse(&struct_item.attrs);
+                let rust_name = struct_item.ident.to_string();
+                if tg_attrs.skip {
+                    // members from 'impl' blocks are skipped with it
+                    self.skipped.push(rust_name);
+                    return;
+                }
+                // struct gets new name after parsing because 'impl' blocks refer to it by Rust name
+                self.rename_type(&rust_name, &tg_attrs);
+                let class_builder = self.get_or_create_struct(rust_name.clone());
                 class_builder.set_visibility(get_visibility(&struct_item.vis));
+                for attr in tg_attrs.get_attrs(&rust_name) {
+                    TypeBuilder::add_attribute(class_builder, attr);
+                }
                 parse_generics(class_builder, &struct_item.generics);
 
                 let mut count = 0;
                 for field in &struct_item.fields {
-                    let field_name = if let Some(field_ident) = &field.ident {
+                    let field_tg_attrs = TangaraAttrs::parse(&field.attrs);
+                    let field_rust_name = if let Some(field_ident) = &field.ident {
                         field_ident.to_string()
                     }
                     else {
                         format!("field{}", count)
                     };
+                    if field_tg_attrs.skip {
+                        count += 1;
+                        continue;
+                    }
                     let (field_type, field_attrs) = get_typeref(&field.ty)
                         .expect("Field cannot have type None");
-                    let mut field_builder = class_builder.add_field(field_type, &field_name);
+                    let mut field_builder = class_builder.add_field(field_type, &field_tg_attrs.get_name(&field_rust_name));
                     if field.ident.is_none() {
                         field_builder.add_attribute(RUST_STD_LIB.tuple_field_attribute(count));
                     }
-                    for attr in field_attrs {
+                    for attr in field_attrs.into_iter().chain(field_tg_attrs.get_attrs(&field_rust_name)) {
                         field_builder.add_attribute(attr);
                     }
                     field_builder.add_attribute(RUST_STD_LIB.struct_field_attribute());
@@ -709,19 +1103,39 @@ impl PackageGenerator {
                 }
             }
             Item::Trait(trait_item) => {
+                let tg_attrs = TangaraAttrs::parse(&trait_item.attrs);
+                if tg_attrs.skip {
+                    return;
+                }
+                let rust_name = trait_item.ident.to_string();
                 let mut interface_builder = create_interface(
                     self.package_builder.clone(),
-                    &trait_item.ident.to_string() // name
+                    &self.rename_type(&rust_name, &tg_attrs) // name
                 );
                 interface_builder.set_visibility(get_visibility(&trait_item.vis));
+                for attr in tg_attrs.get_attrs(&rust_name) {
+                    TypeBuilder::add_attribute(&mut interface_builder, attr);
+                }
                 parse_generics(&mut interface_builder, &trait_item.generics);
 
                 for it in &trait_item.items {
                     match it {
                         TraitItem::Fn(fn_item) => {
                             // TODO check on get_ set_ pair functions to generate properties
-                            let mut fn_builder = interface_builder.add_method(&fn_item.sig.ident.to_string());
+                            let fn_tg_attrs = TangaraAttrs::parse(&fn_item.attrs);
+                            let fn_name = fn_item.sig.ident.to_string();
+                            if fn_tg_attrs.skip {
+                                if fn_item.default.is_none() {
+                                    println!("[Warning] (tangara-gen::PackageGenerator) Skipped method {} of \
+                                    trait {} has no default body, so host can't implement it.", fn_name, rust_name);
+                                }
+                                continue;
+                            }
+                            let mut fn_builder = interface_builder.add_method(&fn_tg_attrs.get_name(&fn_name));
                             fn_builder.set_visibility(TgVis::Public);
+                            for attr in fn_tg_attrs.get_attrs(&fn_name) {
+                                fn_builder.add_attribute(attr);
+                            }
                             parse_generics(&mut fn_builder, &fn_item.sig.generics);
                             parse_return_type(&mut fn_builder, &fn_item.sig.output);
 
@@ -763,13 +1177,22 @@ impl PackageGenerator {
 
                 interface_builder.build();
             }
+            Item::Use(use_item) => self.parse_use_tree(&use_item.tree, String::new()),
             Item::Type(type_item) => {
+                let tg_attrs = TangaraAttrs::parse(&type_item.attrs);
+                if tg_attrs.skip {
+                    return;
+                }
+                let rust_name = type_item.ident.to_string();
                 let mut alias_builder = create_alias(
                     self.package_builder.clone(),
-                    &type_item.ident.to_string(),
+                    &self.rename_type(&rust_name, &tg_attrs),
                     get_typeref(&type_item.ty).expect("Type in alias cannot be None").0
                 );
                 alias_builder.set_visibility(get_visibility(&type_item.vis));
+                for attr in tg_attrs.get_attrs(&rust_name) {
+                    TypeBuilder::add_attribute(&mut alias_builder, attr);
+                }
                 parse_generics(&mut alias_builder, &type_item.generics);
                 alias_builder.build();
             }
@@ -784,11 +1207,29 @@ impl PackageGenerator {
         self
     }
 
-    pub fn parse_code(mut self, code: &str) -> Self {
+    /// Add package which types are referenced by parsed code
+    pub fn add_dependency(self, dependency: &Package) -> Self {
+        self.package_builder.borrow_mut().add_dependency(Dependency::from(dependency));
+        self
+    }
+
+    /// Set semantic version of package, for example `env!("CARGO_PKG_VERSION")` of parsed crate.
+    /// Panics if version is not in `major.minor.patch` format.
+    pub fn set_version(self, version: &str) -> Self {
+        let version = Version::parse(version).expect("Package version must be in 'major.minor.patch' format");
+        self.package_builder.borrow_mut().set_version(version);
+        self
+    }
+
+    pub fn parse_code(self, code: &str) -> Self {
         let syntax_tree = parse_file(code).expect("Failed to parse Rust code");
+        self.parse_items(&syntax_tree.items)
+    }
 
-        for item in syntax_tree.items {
-            self.parse_item(&item);
+    /// Parse items which are already parsed by `syn`, for example by procedural macro
+    pub fn parse_items(mut self, items: &[Item]) -> Self {
+        for item in items {
+            self.parse_item(item);
         }
 
         self
@@ -799,9 +1240,29 @@ impl PackageGenerator {
         self.parse_code(&rust_code)
     }
 
+    /// Generate package and resolve type references in it using parsed `use` items
+    /// and types of dependencies. Returns diagnostics for unresolved references.
+    pub fn generate_resolved(self, deps: &[Package], mode: ResolveMode) -> (Package, Vec<Diagnostic>) {
+        let mut resolver = Resolver::new(deps);
+        resolver.set_mode(mode);
+        for import in &self.imports {
+            resolver.add_import(import);
+        }
+        let mut package = self.generate();
+        let diagnostics = resolver.resolve(&mut package);
+        (package, diagnostics)
+    }
+
     pub fn generate(self) -> Package {
-        for (_, cb) in self.structs {
-            let result = cb.get_type();
+        for (rust_name, cb) in self.structs {
+            if self.skipped.contains(&rust_name) {
+                continue;
+            }
+            let mut result = cb.get_type();
+            if let Some(new_name) = self.renames.get(&format!("{}.{}", result.namespace, result.name)) {
+                result.id = generate_type_id(&format!("{}.{}", result.namespace, new_name));
+                result.name = new_name.clone();
+            }
             if let TypeKind::Class {
                 is_sealed: _is_sealed,
                 constructors,
@@ -834,7 +1295,20 @@ impl PackageGenerator {
             }
         }
         let mut builder = self.package_builder.borrow_mut();
-        builder.add_attribute(Attribute(TypeRef::from("Tangara.Metadata.Lang"), vec![Value::from("Rust")]));
-        builder.build()
+        builder.add_attribute(TANGARA_STD.lang_attribute("Rust"));
+        let mut package = builder.build();
+        if !self.renames.is_empty() {
+            // members were parsed when only Rust names of types were known
+            for t in &mut package.types {
+                let namespace = t.namespace.clone();
+                let mut type_refs = get_member_types(&mut t.kind);
+                type_refs.extend(t.generics.1.iter_mut().map(|(_, bound)| bound));
+                for type_ref in type_refs {
+                    *type_ref = map_names(type_ref, &|name| get_renamed(&self.renames, name, &namespace));
+                }
+            }
+            package.abi = generate_abi_fingerprint(&package.types);
+        }
+        package
     }
 }
\ No newline at end of file
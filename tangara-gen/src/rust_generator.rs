@@ -1,4 +1,4 @@
-use tangara_highlevel::Package;
+use tangara_highlevel::{Package, TypeRef};
 use crate::entrypoint_generator::EntrypointGenerator;
 use crate::source_generator::SourceGenerator;
 
@@ -16,7 +16,16 @@ pub struct Config {
     pub generate_default: bool,
     /// Name of dynamic library's function which loads Tangara data.
     /// Default value: `"tgLoad"`
-    pub load_name: String
+    pub load_name: String,
+    /// Name of dynamic library's function which returns embedded package metadata.
+    /// Default value: `"tgMetadata"`
+    pub metadata_name: String,
+    /// Instantiations of generic types which are generated as concrete types, because generic types
+    /// themselves can't be used through Tangara. For example `GenericsTest<MyStruct>` is declared as
+    /// `TypeRef::Generic(Box::new(TypeRef::from("mylib.GenericsTest")), vec![TypeRef::from("mylib.MyStruct")])`
+    /// and generated as `GenericsTestMyStruct`.
+    /// Default value: `vec![]`
+    pub instances: Vec<TypeRef>
 }
 
 impl Default for Config {
@@ -25,7 +34,9 @@ impl Default for Config {
             enable_internal: false,
             ctor_name: "new".to_string(),
             generate_default: true,
-            load_name: "tgLoad".to_string()
+            load_name: "tgLoad".to_string(),
+            metadata_name: "tgMetadata".to_string(),
+            instances: vec![]
         }
     }
 }
@@ -1,32 +1,23 @@
+use std::collections::HashMap;
 use std::path::Path;
 use std::string::ToString;
 use once_cell::sync::Lazy;
 use tangara_highlevel::*;
+use tangara_highlevel::primitives::Primitive;
+use tangara_highlevel::tangara_std::TANGARA_STD;
 use crate::rust_generator::Config;
-use crate::RUST_STD_LIB;
+use crate::{find_type, get_instances, get_member_types, map_ids, RUST_STD_LIB};
 
 static RUST_NAMING: Lazy<NamingConventions> = Lazy::new(|| NamingConventions::rust());
-// We need this list for excluding these types from naming checks (it's not using Pascal Case, so it causes errors)
-static PRIMITIVE_TYPES: [&str; 12] = [
-    "bool",
-    "str",
-    "char",
-    "i8",
-    "u8",
-    "i16",
-    "u16",
-    "i32",
-    "u32",
-    "i64",
-    "u64",
-    "usize",
-];
 
 pub struct SourceGenerator {
     config: Config,
     package: Package,
     naming: NamingConventions,
     statics_block: String,
+    unload_body: String,
+    /// Assignments of static slots which are done after all lookups of load body succeeded
+    assign_body: String,
     bindings_block: String,
     load_body: String,
     package_name: String
@@ -64,7 +55,7 @@ fn get_generics(generics: &Generics, attrs: &[Attribute], naming: &NamingConvent
                         name.push(':');
                     }
                     for (_, dep) in wheres {
-                        name.push_str(&get_typeref(dep, naming));
+                        name.push_str(&get_typeref(dep, &[], naming));
                         name.push('+');
                     }
                     // remove extra '+' in the end
@@ -92,13 +83,44 @@ fn get_type_name(t: &Type, naming: &NamingConventions, with_where: bool) -> Stri
     name
 }
 
-fn get_typeref(typeref: &TypeRef, naming: &NamingConventions) -> String {
+/// Reference types by names instead of ids, because bindings use names of types.
+/// Types of package are referenced by their names as other members do, types of `Tangara.Std` by full names.
+/// Panics if type with id isn't declared by them, so bindings aren't generated with missing types.
+fn resolve_ids(package: &mut Package) {
+    let names = package.types.iter()
+        .map(|t| (t.id, t.name.clone()))
+        .chain(TANGARA_STD.get_package().types.iter().map(|t| (t.id, format!("{}.{}", t.namespace, t.name))))
+        .collect::<HashMap<u64, String>>();
+    let package_name = package.name.clone();
+    let resolve = |id: u64| match names.get(&id) {
+        Some(name) => TypeRef::Name(name.clone()),
+        None => panic!("Type with id {id} referenced by package {package_name} isn't found, \
+                        types of dependencies must be referenced by names")
+    };
+    for t in &mut package.types {
+        let mut type_refs = get_member_types(&mut t.kind);
+        type_refs.extend(t.generics.1.iter_mut().map(|(_, dependency)| dependency));
+        for type_ref in type_refs {
+            *type_ref = map_ids(type_ref, &resolve);
+        }
+    }
+}
+
+/// Rust type for type reference. Attributes of member are used for getting sizes of arrays
+/// and mutability of pointers.
+fn get_typeref(typeref: &TypeRef, attrs: &[Attribute], naming: &NamingConventions) -> String {
+    let mut array_sizes = TANGARA_STD.get_array_sizes(attrs).into_iter();
+    get_sized_typeref(typeref, attrs, &mut array_sizes, naming)
+}
+
+fn get_sized_typeref(typeref: &TypeRef, attrs: &[Attribute], array_sizes: &mut impl Iterator<Item = Value>,
+                     naming: &NamingConventions) -> String {
     let mut name = String::new();
     match typeref {
         TypeRef::Name(type_name) => {
-            // if it's a primitive type (so it doesn't follow a pascal case), we don't check naming
-            if PRIMITIVE_TYPES.contains(&type_name.as_str()) {
-                name.push_str(type_name);
+            // primitive types are mapped to Rust types and don't follow naming of package
+            if let Some(primitive) = Primitive::from_name(type_name) {
+                name.push_str(primitive.rust_type());
             }
             else {
                 let converted = RUST_NAMING.convert_type(type_name, naming).unwrap();
@@ -106,13 +128,36 @@ fn get_typeref(typeref: &TypeRef, naming: &NamingConventions) -> String {
             }
         }
         TypeRef::Id(id) => {
-            // TODO resolve type
+            // other types are referenced by names after resolve_ids
+            let primitive = Primitive::from_typeref(typeref)
+                .unwrap_or_else(|| panic!("Type with id {id} isn't resolved"));
+            name.push_str(primitive.rust_type());
+        }
+        TypeRef::Generic(parent, generics) if TANGARA_STD.is_array(parent) && generics.len() == 1 => {
+            let size = array_sizes.next();
+            name.push('[');
+            name.push_str(&get_sized_typeref(&generics[0], attrs, array_sizes, naming));
+            if let Some(size) = size {
+                name.push_str("; ");
+                name.push_str(&get_value(&size));
+            }
+            name.push(']');
+        }
+        TypeRef::Generic(parent, generics) if TANGARA_STD.is_ptr(parent) && generics.len() == 1 => {
+            name.push_str(if RUST_STD_LIB.is_mutable(attrs) { "*mut " } else { "*const " });
+            name.push_str(&get_sized_typeref(&generics[0], attrs, array_sizes, naming));
         }
         TypeRef::Generic(parent, generics) => {
-            name.push_str(&get_typeref(parent, naming));
+            if TANGARA_STD.is_option(parent) {
+                name.push_str("Option");
+            } else if TANGARA_STD.is_result(parent) {
+                name.push_str("Result");
+            } else {
+                name.push_str(&get_sized_typeref(parent, attrs, array_sizes, naming));
+            }
             name.push('<');
             for generic in generics {
-                name.push_str(&get_typeref(generic, naming));
+                name.push_str(&get_sized_typeref(generic, attrs, array_sizes, naming));
                 name.push(',');
             }
             if generics.len() > 0 {
@@ -124,7 +169,7 @@ fn get_typeref(typeref: &TypeRef, naming: &NamingConventions) -> String {
         TypeRef::Tuple(types) => {
             name.push('(');
             for t in types {
-                name.push_str(&get_typeref(t, naming));
+                name.push_str(&get_sized_typeref(t, attrs, array_sizes, naming));
                 name.push(',');
             }
             if types.len() > 0 {
@@ -136,7 +181,7 @@ fn get_typeref(typeref: &TypeRef, naming: &NamingConventions) -> String {
         TypeRef::Fn(ret_type, args) => {
             name.push_str("fn(");
             for arg in args {
-                name.push_str(&get_typeref(arg, naming));
+                name.push_str(&get_sized_typeref(arg, attrs, array_sizes, naming));
                 name.push(',');
             }
             if args.len() > 0 {
@@ -146,13 +191,42 @@ fn get_typeref(typeref: &TypeRef, naming: &NamingConventions) -> String {
             name.push(')');
             if let Some(return_type) = ret_type {
                 name.push_str(" -> ");
-                name.push_str(&get_typeref(return_type, naming));
+                name.push_str(&get_sized_typeref(return_type, attrs, array_sizes, naming));
             }
         }
     }
     name
 }
 
+/// Variant of enum with its fields: their types when `values` is `None`,
+/// names for matching when `values` are empty and the given expressions otherwise
+fn get_variant_pattern(variant: &Variant, values: Option<&[String]>, naming: &NamingConventions) -> String {
+    if variant.fields.is_empty() {
+        return variant.name.clone();
+    }
+    let is_tuple = RUST_STD_LIB.is_tuple_variant(&variant.attrs);
+    let fields = variant.fields.iter().enumerate()
+        .map(|(i, field)| {
+            let value = match values {
+                None => get_typeref(&field.field_type, &field.attrs, naming),
+                Some([]) => field.name.clone(),
+                Some(values) => values[i].clone()
+            };
+            if is_tuple || matches!(values, Some([])) {
+                value
+            } else {
+                format!("{}: {}", field.name, value)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    if is_tuple {
+        format!("{}({})", variant.name, fields)
+    } else {
+        format!("{} {{ {} }}", variant.name, fields)
+    }
+}
+
 fn get_value(value: &Value) -> String {
     match value {
         Value::Null => "None".to_string(),
@@ -200,6 +274,16 @@ fn get_value(value: &Value) -> String {
     }
 }
 
+/// Reference part of member's type like `&'a mut ` or empty string if it isn't reference
+fn get_reference_prefix(attrs: &[Attribute]) -> String {
+    if !RUST_STD_LIB.is_reference(attrs) {
+        return String::new();
+    }
+    let lifetime = RUST_STD_LIB.get_lifetime(attrs).map(|lt| format!("'{lt} ")).unwrap_or_default();
+    let mutability = if RUST_STD_LIB.is_mutable(attrs) { "mut " } else { "" };
+    format!("&{lifetime}{mutability}")
+}
+
 fn get_args(args: &[Argument], naming: &NamingConventions) -> String {
     let mut result = String::new();
     for arg in args {
@@ -218,7 +302,7 @@ fn get_args(args: &[Argument], naming: &NamingConventions) -> String {
         result.push_str(&RUST_NAMING.parameter.from(&arg.2, &naming.parameter).unwrap()); // name
         result.push(':');
         result.push_str(&type_prefix);
-        result.push_str(&get_typeref(&arg.1, naming)); // type
+        result.push_str(&get_typeref(&arg.1, &arg.0, naming)); // type
         result.push_str(", ");
     }
     if args.len() > 0 {
@@ -231,7 +315,8 @@ fn get_args(args: &[Argument], naming: &NamingConventions) -> String {
 }
 
 impl SourceGenerator {
-    pub(crate) fn new(package: Package, config: Config) -> Self {
+    pub(crate) fn new(mut package: Package, config: Config) -> Self {
+        resolve_ids(&mut package);
         let mut package_naming = NamingConventions::rust();
         package_naming.package_divider = "_".to_string();
         let package_name = format!("{}_package", package_naming.convert_package(&package.name, &package.naming).unwrap());
@@ -241,12 +326,27 @@ impl SourceGenerator {
             package,
             naming,
             statics_block: String::new(),
+            unload_body: String::new(),
+            assign_body: String::new(),
             bindings_block: String::new(),
             load_body: String::new(),
             package_name
         }
     }
 
+    /// Declare static slot for function pointer loaded from library and reset it on unloading
+    fn add_static(&mut self, name: &str, fn_type: &str) {
+        self.statics_block.push_str(&format!("static mut {name}: Option<{fn_type}> = None;\n"));
+        self.unload_body.push_str(&format!("{name} = None;\n"));
+    }
+
+    /// Look up value of static slot into local variable of load body and assign slot after all lookups,
+    /// so failed lookup leaves all slots of package as they were
+    fn load_static(&mut self, name: &str, value: &str) {
+        self.load_body.push_str(&format!("let {name}_loaded = {value};\n"));
+        self.assign_body.push_str(&format!("{name} = {name}_loaded;\n"));
+    }
+
     /// Checks visibility for pass generating
     fn pass_vis(&self, vis: &Visibility) -> bool {
         let vis = vis.clone();
@@ -279,7 +379,7 @@ impl SourceGenerator {
                     ArgumentKind::Ref => "&mut ",
                     ArgumentKind::In => "&"
                 }.to_string();
-                let arg_type = [type_prefix, get_typeref(&arg.1, &self.naming)].concat();
+                let arg_type = [type_prefix, get_typeref(&arg.1, &arg.0, &self.naming)].concat();
                 if args_size.len() == 0 {
                     args_assign.push(format!("*(args_ptr as *mut {}) = {};", arg_type, arg.2));
                 } else {
@@ -311,7 +411,7 @@ impl SourceGenerator {
         } else {
             RUST_NAMING.private_field.from(&property.name, &self.naming.private_field)
         }.unwrap();
-        let prop_type_name = &get_typeref(&property.prop_type, &self.naming);
+        let prop_type_name = &get_typeref(&property.prop_type, &property.attrs, &self.naming);
         let mut prop_load_name = None;
 
         // generate getter
@@ -325,9 +425,7 @@ impl SourceGenerator {
             if let Some(parent_type_name) = type_name {
                 // add static variable
                 let getter_name = format!("{}_{}_getter", parent_type_name, prop_name);
-                self.statics_block.push_str(
-                    &format!("static mut {}: Option<extern \"C\" fn(Ptr) -> Ptr> = None;\n", getter_name)
-                );
+                self.add_static(&getter_name, "extern \"C\" fn(Ptr) -> Ptr");
 
                 // create property variable in the load body
                 prop_load_name = Some(format!("{}_{}_prop", parent_type_name, prop_name));
@@ -335,13 +433,11 @@ impl SourceGenerator {
                 self.load_body.push_str(&prop_load_name.clone().unwrap());
                 self.load_body.push_str(" = ");
                 self.load_body.push_str(parent_type_name);
-                self.load_body.push_str("_type.get_property(");
+                self.load_body.push_str("_type.try_get_property(");
                 self.load_body.push_str(&property.id.to_string());
-                self.load_body.push_str(");\n");
+                self.load_body.push_str(")?;\n");
                 // assign getter then
-                self.load_body.push_str(
-                    &format!("{} = Some({}.getter);\n", getter_name, prop_load_name.clone().unwrap())
-                );
+                self.load_static(&getter_name, &format!("Some({}.getter)", prop_load_name.clone().unwrap()));
 
                 // implement body
                 self.bindings_block.push_str(" {\n\t\tunsafe {\n\t\t\tlet raw_ptr: *mut ");
@@ -351,7 +447,7 @@ impl SourceGenerator {
                 self.bindings_block.push_str(".unwrap()(self.ptr) as *mut ");
                 self.bindings_block.push_str(prop_type_name);
                 self.bindings_block.push_str(";\n\t\t\tif !raw_ptr.is_null() {\n\t\t\t\t\
-                *Box::from_raw(raw_ptr)\n\t\t\t} else {\n\t\t\t\t\
+                TANGARA_ALLOCATOR.take_object(raw_ptr as Ptr)\n\t\t\t} else {\n\t\t\t\t\
                 panic!(\"Pointer of gotten property is null\")\n\t\t\t}\n\t\t}\n\t}\n");
             }
             else {
@@ -372,9 +468,7 @@ impl SourceGenerator {
                 if let Some(parent_type_name) = type_name {
                     // add static variable
                     let setter_name = format!("{}_{}_setter", parent_type_name, prop_name);
-                    self.statics_block.push_str(
-                        &format!("static mut {}: Option<extern \"C\" fn(Ptr, Ptr)> = None;\n", setter_name)
-                    );
+                    self.add_static(&setter_name, "extern \"C\" fn(Ptr, Ptr)");
 
                     // create property variable in the load body if it doesn't exists yet
                     let prop_name_from_load = prop_load_name.unwrap_or_else(|| {
@@ -383,15 +477,13 @@ impl SourceGenerator {
                         self.load_body.push_str(&prop_load_name);
                         self.load_body.push_str(" = ");
                         self.load_body.push_str(parent_type_name);
-                        self.load_body.push_str("_type.get_property(");
+                        self.load_body.push_str("_type.try_get_property(");
                         self.load_body.push_str(&property.id.to_string());
-                        self.load_body.push_str(");\n");
+                        self.load_body.push_str(")?;\n");
                         prop_load_name
                     });
                     // assign setter then
-                    self.load_body.push_str(
-                        &format!("{} = Some({}.setter.unwrap());\n", setter_name, prop_name_from_load)
-                    );
+                    self.load_static(&setter_name, &format!("Some({}.setter.unwrap())", prop_name_from_load));
 
                     // implement body
                     self.bindings_block.push_str(" {\n\t\tunsafe { ");
@@ -407,9 +499,106 @@ impl SourceGenerator {
         }
     }
 
+    /// Fields are registered in the library as properties, so we generate them the same way
     fn gen_field(&mut self, field: &Field, type_name: &str) {
         if self.pass_vis(&field.vis) {
-            // TODO
+            let field_prop = Property {
+                attrs: field.attrs.to_vec(),
+                getter_visibility: field.vis,
+                setter_visibility: Some(field.vis),
+                prop_type: field.field_type.clone(),
+                name: field.name.clone(),
+                id: field.id
+            };
+            self.gen_property(&field_prop, Some(type_name));
+        }
+    }
+
+    /// Static properties are generated as associated functions which call static property of type.
+    /// Note: set `type_name` to None if you want to generate functions without a body.
+    fn gen_static_property(&mut self, property: &Property, type_name: Option<&str>) {
+        let prop_name = &if property.getter_visibility == Visibility::Public {
+            RUST_NAMING.property.from(&property.name, &self.naming.property)
+        } else {
+            RUST_NAMING.private_field.from(&property.name, &self.naming.private_field)
+        }.unwrap();
+        let prop_type_name = &[
+            get_reference_prefix(&property.attrs),
+            get_typeref(&property.prop_type, &property.attrs, &self.naming)
+        ].concat();
+        // library registers static property only if its getter is visible
+        let static_load_name = type_name.filter(|_| self.pass_vis(&property.getter_visibility)).map(|parent_type_name| {
+            let static_load_name = format!("{}_{}_static", parent_type_name, prop_name);
+            self.load_body.push_str(&format!(
+                "let {} = {}_type.try_get_static({})?;\n", static_load_name, parent_type_name, property.id
+            ));
+            static_load_name
+        });
+
+        // generate getter
+        if self.pass_vis(&property.getter_visibility) {
+            self.bindings_block.push('\t');
+            self.gen_vis(&property.getter_visibility);
+            self.bindings_block.push_str(&format!("fn get_{}() -> {}", prop_name, prop_type_name));
+            if let (Some(parent_type_name), Some(static_load_name)) = (type_name, &static_load_name) {
+                let getter_name = format!("{}_{}_static_getter", parent_type_name, prop_name);
+                self.add_static(&getter_name, "extern \"C\" fn() -> Ptr");
+                self.load_static(&getter_name, &format!("Some({}.getter)", static_load_name));
+                self.bindings_block.push_str(&format!(r#" {{
+		unsafe {{
+			let raw_ptr: *mut {1} = {0}.expect("Static property wasn't loaded")() as *mut {1};
+			if !raw_ptr.is_null() {{
+				TANGARA_ALLOCATOR.take_object(raw_ptr as Ptr)
+			}} else {{
+				panic!("Pointer of gotten static property is null")
+			}}
+		}}
+	}}
+"#, getter_name, prop_type_name));
+            } else {
+                self.bindings_block.push_str(";\n");
+            }
+        }
+
+        // generate setter
+        if let Some(setter_vis) = property.setter_visibility {
+            if self.pass_vis(&setter_vis) {
+                self.bindings_block.push('\t');
+                self.gen_vis(&setter_vis);
+                self.bindings_block.push_str(&format!("fn set_{}(value: {})", prop_name, prop_type_name));
+                if let (Some(parent_type_name), Some(static_load_name)) = (type_name, &static_load_name) {
+                    let setter_name = format!("{}_{}_static_setter", parent_type_name, prop_name);
+                    self.add_static(&setter_name, "extern \"C\" fn(Ptr)");
+                    self.load_static(&setter_name, &format!("{}.setter", static_load_name));
+                    // library reads value from pointer and owns it then, so it mustn't be dropped here
+                    self.bindings_block.push_str(&format!(r#" {{
+		let value = std::mem::ManuallyDrop::new(value);
+		unsafe {{ {}.expect("Static property has no setter")(&*value as *const {} as Ptr); }}
+	}}
+"#, setter_name, prop_type_name));
+                } else {
+                    self.bindings_block.push_str(";\n");
+                }
+            }
+        }
+    }
+
+    /// Static fields are registered in the library as read-only static properties,
+    /// because associated items of Rust types can't be assigned
+    fn gen_static_field(&mut self, field: &Field, type_name: &str) {
+        if self.pass_vis(&field.vis) {
+            // static fields can be named not as properties, like constants in Rust
+            let name = self.naming.property.from(&field.name, &self.naming.private_static)
+                .unwrap_or_else(|_| field.name.clone());
+            let field_prop = Property {
+                attrs: field.attrs.to_vec(),
+                getter_visibility: field.vis,
+                setter_visibility: None,
+                prop_type: field.field_type.clone(),
+                name,
+                id: field.id
+            };
+            self.gen_static_property(&field_prop, Some(type_name));
         }
     }
 
@@ -417,12 +606,8 @@ impl SourceGenerator {
     fn gen_ctor(&mut self, ctor: &Constructor, index: u32, type_name: &str) -> String {
         if self.pass_vis(&ctor.vis) {
             let ctor_load_name = format!("{}_ctor{}", type_name, index);
-            self.statics_block.push_str(
-                &format!("static mut {}: Option<Fn> = None;\n", ctor_load_name)
-            );
-            self.load_body.push_str(
-                &format!("{} = Some({}_type.get_ctor({}).clone());\n", ctor_load_name, type_name, index)
-            );
+            self.add_static(&ctor_load_name, "Fn");
+            self.load_static(&ctor_load_name, &format!("Some({}_type.try_get_ctor({})?.clone())", type_name, index));
 
             let ctor_name = if let Some(ctor_fn_name) = RUST_STD_LIB.get_fn_name(&ctor.attrs) {
                 // get name from ConstructorFnName attribute if it exists
@@ -469,17 +654,79 @@ impl SourceGenerator {
     }
 
     fn gen_method(&mut self, method: &Method, type_name: &str) {
+        // aware that if method is abstract - it's for traits, so it can't have visibility modifier
+        // and implementation
+        if method.kind == MethodKind::Abstract {
+            self.gen_method_with(method, false, None);
+        } else if self.pass_vis(&method.vis) && method.generics.0.is_empty() {
+            // generic method isn't exported by library, its instantiations are loaded instead
+            let method_load_name = self.load_method(method, type_name, &format!("{}_type", type_name));
+            self.gen_method_with(method, true, Some(method_load_name));
+        }
+    }
+
+    /// Add static function of method which is loaded from table with name `table_name`, returns name of static
+    fn load_method(&mut self, method: &Method, prefix: &str, table_name: &str) -> String {
+        let method_name = RUST_NAMING.method.from(&method.name, &self.naming.method).unwrap();
+        let method_load_name = format!("{}_{}", prefix, method_name);
+        self.add_static(&method_load_name, "Fn");
+        self.load_static(&method_load_name, &format!("Some({}.try_get_method({})?.clone())", table_name, method.id));
+        method_load_name
+    }
+
+    /// Methods of class which can be overridden are dispatched by runtime type of object
+    /// to the most derived implementation from `descendants` of class
+    fn gen_class_method(&mut self, method: &Method, t: &Type, descendants: &[Type]) {
+        let is_virtual = matches!(method.kind, MethodKind::Virtual | MethodKind::Abstract);
+        if !is_virtual {
+            self.gen_method(method, &t.name);
+            return;
+        }
+        if !self.pass_vis(&method.vis) || !method.generics.0.is_empty() {
+            return;
+        }
+        // abstract method can have no implementation in class itself, so it's checked only when it's called
+        let get_method = |lookup: String| if method.kind == MethodKind::Abstract {
+            format!("{}.ok().cloned()", lookup)
+        } else {
+            format!("Some({}?.clone())", lookup)
+        };
+        let method_name = RUST_NAMING.method.from(&method.name, &self.naming.method).unwrap();
+        let method_load_name = format!("{}_{}", t.name, method_name);
+        self.add_static(&method_load_name, "Fn");
+        self.load_static(&method_load_name, &get_method(format!("{}_type.try_get_method({})", t.name, method.id)));
+        if descendants.is_empty() {
+            self.gen_method_with(method, true, Some(method_load_name));
+            return;
+        }
+        let mut arms = String::new();
+        for descendant in descendants {
+            let override_name = format!("{}_{}", method_load_name, descendant.name);
+            self.add_static(&override_name, "Fn");
+            let resolve = get_method(format!("{}.try_resolve_method({}, {})", self.package_name, descendant.id, method.id));
+            self.load_static(&override_name, &resolve);
+            arms.push_str(&format!("\n\t\t\t\t{} => {},", descendant.id, override_name));
+        }
+        let method_func = format!(
+            "match ({}_type_of.expect(\"Runtime type getter wasn't loaded\"))(self.ptr) {{{}\n\t\t\t\t_ => {}\n\t\t\t}}",
+            t.name, arms, method_load_name
+        );
+        self.gen_method_with(method, true, Some(method_func));
+    }
+
+    /// Generate method with optional visibility and implementation.
+    /// `method_func` is expression of static function which implements method.
+    fn gen_method_with(&mut self, method: &Method, with_vis: bool, method_func: Option<String>) {
         if self.pass_vis(&method.vis) {
             let method_name = &RUST_NAMING.method.from(&method.name, &self.naming.method).unwrap();
 
             self.bindings_block.push('\t');
-            // aware that if method is abstract - it's for traits, so it can't have visibility modifier
-            if method.kind != MethodKind::Abstract {
+            if with_vis {
                 self.gen_vis(&method.vis);
             }
             let (return_type, return_type_block) = if let Some(ret_type) = &method.return_type {
                 let prefix = RUST_STD_LIB.get_return_prefix(&method.attrs).unwrap_or_default();
-                let core = [prefix, get_typeref(ret_type, &self.naming)].concat();
+                let core = [prefix, get_typeref(ret_type, &[], &self.naming)].concat();
                 (core.clone(), [" -> ", &core].concat())
             } else {
                 (String::new(), String::new())
@@ -509,18 +756,10 @@ impl SourceGenerator {
             );
             // TODO don't forget about generics
 
-            if method.kind != MethodKind::Abstract {
+            if let Some(method_func) = method_func {
                 // generate implementation
-                let method_load_name = format!("{}_{}", type_name, method_name);
-                self.statics_block.push_str(
-                    &format!("static mut {}: Option<Fn> = None;\n", method_load_name)
-                );
-                self.load_body.push_str(
-                    &format!("{} = Some({}_type.get_method({}).clone());\n", method_load_name, type_name, method.id)
-                );
-
                 self.bindings_block.push_str(
-                    &format!(" {{\n\t\tunsafe {{\n\t\t\tif let Some(method_func) = {} {{", method_load_name)
+                    &format!(" {{\n\t\tunsafe {{\n\t\t\tif let Some(method_func) = {} {{", method_func)
                 );
                 // we don't join these two bindings' push_str calls into one because self.gen_args()
                 // called below in format generating code to bindings block between these two
@@ -532,7 +771,7 @@ impl SourceGenerator {
                         &format!(r#"
                 let raw_ptr = method_func({});
                 if !raw_ptr.is_null() {{
-                    *Box::from_raw(raw_ptr as *mut {})
+                    TANGARA_ALLOCATOR.take_object::<{}>(raw_ptr)
                 }} else {{
                     panic!("Pointer of method result is null")
                 }}"#, args, return_type)
@@ -553,13 +792,136 @@ impl SourceGenerator {
         }
     }
 
-    fn gen_drop(&mut self, t: &Type, type_load_name: &str) {
+    /// Implement interface of this package for type through methods from vtable of the interface
+    fn gen_interface_impl(&mut self, t: &Type, parent: &TypeRef, type_load_name: &str) {
+        let interface = match find_type(&self.package, parent, &t.namespace) {
+            Some(interface) if self.pass_vis(&interface.vis) => interface.clone(),
+            _ => return
+        };
+        if let TypeKind::Interface { methods, properties, .. } = &interface.kind {
+            let vtable_name = format!("{}_{}_vtable", t.name, interface.name);
+            self.load_body.push_str(
+                &format!("let {} = {}.try_get_interface({})?;\n", vtable_name, type_load_name, interface.id)
+            );
+            self.bindings_block.push_str("\n\nimpl");
+            self.bindings_block.push_str(&get_generics(&t.generics, &t.attrs, &self.naming, true));
+            self.bindings_block.push(' ');
+            self.bindings_block.push_str(&get_type_name(&interface, &self.naming, false));
+            self.bindings_block.push_str(" for ");
+            self.bindings_block.push_str(&get_type_name(t, &self.naming, false));
+            self.bindings_block.push_str(" {\n");
+            for method in methods {
+                if self.pass_vis(&method.vis) {
+                    let method_load_name = self.load_method(
+                        method,
+                        &format!("{}_{}", t.name, interface.name),
+                        &vtable_name
+                    );
+                    self.gen_method_with(method, false, Some(method_load_name));
+                }
+            }
+            if !properties.is_empty() {
+                println!("[Warning] (tangara-gen::SourceGenerator) Properties of interface {} \
+                are not supported yet, skipping them", interface.name);
+            }
+            self.bindings_block.push('}');
+        }
+    }
+
+    /// Generate function registering host's implementation of interface in context,
+    /// so libraries loaded after it can call methods of this implementation
+    fn gen_host_implementation(&mut self, t: &Type, methods: &[Method]) {
+        let interface_name = get_type_name(t, &self.naming, false);
+        let dtor_name = format!("{}_host_dtor", t.name);
+        let mut add_methods = String::new();
+        for method in methods {
+            let method_name = &RUST_NAMING.method.from(&method.name, &self.naming.method).unwrap();
+            let fn_name = format!("{}_{}_host", t.name, method_name);
+            let this_type = if RUST_STD_LIB.is_mutable(&method.attrs) { "*mut T" } else { "*const T" };
+            let mut args_read = String::new();
+            let mut arg_names = vec![];
+            for arg in &method.args {
+                let arg_type = [
+                    match &arg.3 {
+                        ArgumentKind::Default | ArgumentKind::DefaultValue(_) => "",
+                        ArgumentKind::Out | ArgumentKind::Ref => "&mut ",
+                        ArgumentKind::In => "&"
+                    },
+                    &get_typeref(&arg.1, &arg.0, &self.naming)
+                ].concat();
+                let arg_name = RUST_NAMING.parameter.from(&arg.2, &self.naming.parameter).unwrap();
+                args_read.push_str(&format!(r#"
+        let {0}: {1} = std::ptr::read(args_ptr as *const {1});
+        args_ptr = args_ptr.add(std::mem::size_of::<{1}>());"#, arg_name, arg_type));
+                arg_names.push(arg_name);
+            }
+            let call = if RUST_STD_LIB.is_reference(&method.attrs) {
+                format!("(*this).{}({})", method_name, arg_names.join(", "))
+            } else {
+                // method takes `self` by value, so object is moved out of implementation
+                format!("std::ptr::read(this).{}({})", method_name, arg_names.join(", "))
+            };
+            let final_code = if method.return_type.is_some() {
+                format!("TANGARA_ALLOCATOR.new_object({})", call)
+            } else {
+                format!("{};\n        std::ptr::null_mut()", call)
+            };
+            self.bindings_block.push_str(&format!(r#"
+
+extern "C" fn {0}<T: {1}>(args_size: usize, args: *mut u8) -> Ptr {{
+    unsafe {{
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let mut args_ptr = args_slice.as_mut_ptr();
+        let this: {2} = *(args_ptr as *mut Ptr) as {2};
+        args_ptr = args_ptr.add(std::mem::size_of::<Ptr>());{3}
+        {4}
+    }}
+}}"#, fn_name, interface_name, this_type, args_read, final_code));
+            add_methods.push_str(&format!("\n\timplementation.add_method({}, {}::<T>);", method.id, fn_name));
+        }
+        let register_name = RUST_NAMING.method.from(&t.name, &self.naming.interface).unwrap();
+        self.bindings_block.push_str(&format!(r#"
+
+extern "C" fn {0}<T>(value: Ptr) {{
+    unsafe {{
+        drop(Box::from_raw(value as *mut T));
+    }}
+}}
+
+/// Register `value` as implementation of {1} for libraries. It must be done before loading them.
+/// Libraries can call methods of `value` from any thread, so it must be thread safe.
+pub fn register_{2}<T: {1} + Send + Sync + 'static>(ctx: &mut Context, value: T) {{
+	// `T` is `Send + Sync`, so `value` can be used and dropped on any thread
+	let mut implementation = unsafe {{ Implementation::new({3}, Box::into_raw(Box::new(value)) as Ptr, Some({0}::<T>)) }};{4}
+	ctx.add_implementation(implementation);
+}}"#, dtor_name, interface_name, register_name, t.id, add_methods));
+    }
+
+    /// Destructor is chosen by runtime type of object if class has `descendants`,
+    /// because objects of derived classes can be upcasted to it
+    fn gen_drop(&mut self, t: &Type, type_load_name: &str, descendants: &[Type]) {
         // add static destructor variable
         let dtor_name = format!("{}_dtor", t.name);
-        self.statics_block.push_str(&format!("static mut {dtor_name}: Option<FnDtor> = None;\n"));
+        self.add_static(&dtor_name, "FnDtor");
 
         // assign it in the load body
-        self.load_body.push_str(&format!("{dtor_name} = Some({type_load_name}.get_dtor());\n"));
+        self.load_static(&dtor_name, &format!("Some({type_load_name}.try_get_dtor()?)"));
+
+        let mut dtor_func = dtor_name.clone();
+        if !descendants.is_empty() {
+            let mut arms = String::new();
+            for descendant in descendants {
+                let descendant_dtor_name = format!("{}_{}", dtor_name, descendant.name);
+                self.add_static(&descendant_dtor_name, "FnDtor");
+                let dtor = format!("Some({}.try_get_type({})?.try_get_dtor()?)", self.package_name, descendant.id);
+                self.load_static(&descendant_dtor_name, &dtor);
+                arms.push_str(&format!("\n\t\t\t\t{} => {},", descendant.id, descendant_dtor_name));
+            }
+            dtor_func = format!(
+                "(match ({}_type_of.expect(\"Runtime type getter wasn't loaded\"))(self.ptr) {{{}\n\t\t\t\t_ => {}\n\t\t\t}})",
+                t.name, arms, dtor_name
+            );
+        }
 
         // implement Drop trait
         self.bindings_block.push_str("\n\nimpl");
@@ -567,10 +929,97 @@ impl SourceGenerator {
         self.bindings_block.push_str(" Drop for ");
         self.bindings_block.push_str(&get_type_name(&t, &self.naming, false));
         self.bindings_block.push_str(" {\n\tfn drop(&mut self) {\n\t\tunsafe {\n\t\t\t");
-        self.bindings_block.push_str(&dtor_name);
+        self.bindings_block.push_str(&dtor_func);
         self.bindings_block.push_str(".expect(\"Destructor wasn't loaded from library\")(self.ptr);\n\t\t}\n\t}\n}");
     }
 
+    /// Base class of class from this package, other parents are interfaces
+    fn get_parent_class(&self, t: &Type) -> Option<Type> {
+        if let TypeKind::Class { parents, .. } = &t.kind {
+            for parent in parents {
+                match find_type(&self.package, parent, &t.namespace) {
+                    Some(parent) if matches!(parent.kind, TypeKind::Class { .. }) => return Some(parent.clone()),
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    /// Classes of this package which inherit class directly or through other classes
+    fn get_descendants(&self, t: &Type) -> Vec<Type> {
+        self.package.types.iter()
+            .filter(|descendant| descendant.generics.0.is_empty() && self.pass_vis(&descendant.vis))
+            .filter(|descendant| {
+                let mut current = self.get_parent_class(descendant);
+                // every class is visited once at most even if parents are cyclic
+                for _ in 0..self.package.types.len() {
+                    match current {
+                        Some(parent) if parent.id == t.id => return true,
+                        Some(parent) => current = self.get_parent_class(&parent),
+                        None => return false
+                    }
+                }
+                false
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Expose members of base class by `Deref` and generate casts between class and its base class.
+    /// Downcast checks runtime type of object, so base class must have descendants.
+    fn gen_parent_class(&mut self, t: &Type, parent: &Type, descendants: &[Type]) {
+        let type_name = get_type_name(t, &self.naming, false);
+        let parent_name = get_type_name(parent, &self.naming, false);
+        self.bindings_block.push_str(&format!(r#"
+
+impl std::ops::Deref for {0} {{
+    type Target = {1};
+
+    fn deref(&self) -> &{1} {{
+        // both classes are transparent wrappers of pointer
+        unsafe {{ &*(self as *const {0} as *const {1}) }}
+    }}
+}}
+
+impl std::ops::DerefMut for {0} {{
+    fn deref_mut(&mut self) -> &mut {1} {{
+        unsafe {{ &mut *(self as *mut {0} as *mut {1}) }}
+    }}
+}}"#, type_name, parent_name));
+        if let TypeKind::Class { is_sealed: true, .. } = parent.kind {
+            println!("[Warning] (tangara-gen::SourceGenerator) Class {} inherits sealed class {}, \
+            so casts between them are not generated", t.name, parent.name);
+            return;
+        }
+        let type_ids = std::iter::once(t.id)
+            .chain(descendants.iter().map(|descendant| descendant.id))
+            .map(|id| id.to_string())
+            .collect::<Vec<String>>();
+        self.bindings_block.push_str(&format!(r#"
+
+impl From<{0}> for {1} {{
+    fn from(value: {0}) -> Self {{
+        Self {{
+            ptr: std::mem::ManuallyDrop::new(value).ptr
+        }}
+    }}
+}}
+
+impl TryFrom<{1}> for {0} {{
+    type Error = {1};
+
+    fn try_from(value: {1}) -> Result<Self, {1}> {{
+        match unsafe {{ ({2}_type_of.expect("Runtime type getter wasn't loaded"))(value.ptr) }} {{
+            {3} => Ok(Self {{
+                ptr: std::mem::ManuallyDrop::new(value).ptr
+            }}),
+            _ => Err(value)
+        }}
+    }}
+}}"#, type_name, parent_name, parent.name, type_ids.join(" | ")));
+    }
+
     fn gen_default(&mut self, t: &Type, ctor_name: &str) {
         if self.config.generate_default {
             self.bindings_block.push_str("\n\nimpl");
@@ -585,25 +1034,139 @@ impl SourceGenerator {
         }
     }
 
+    /// Host enum is a copy of enum object from library, so implement conversions between them.
+    /// Variants are created by their constructors and read by variants protocol of type.
+    fn gen_enum_impl(&mut self, t: &Type, variants: &[Variant], type_load_name: &str) {
+        let variants_name = format!("{}_variants", t.name);
+        self.add_static(&variants_name, "Variants");
+        self.load_static(&variants_name, &format!("Some(*{type_load_name}.try_get_variants()?)"));
+        let dtor_name = format!("{}_dtor", t.name);
+        self.add_static(&dtor_name, "FnDtor");
+        self.load_static(&dtor_name, &format!("Some({type_load_name}.try_get_dtor()?)"));
+
+        self.bindings_block.push_str("\n\nimpl ");
+        self.bindings_block.push_str(&get_type_name(t, &self.naming, false));
+        self.bindings_block.push_str(r#" {
+    /// Create object of this enum in library, it must be deleted by `delete_ptr`
+    pub fn into_ptr(self) -> Ptr {
+        unsafe {
+            match self {"#);
+        for variant in variants {
+            let ctor_name = format!("{}_{}", t.name, variant.name);
+            self.add_static(&ctor_name, "Fn");
+            self.load_static(&ctor_name, &format!("Some({}.try_get_method({})?.clone())", type_load_name, variant.id));
+            self.bindings_block.push_str(&format!(
+                "\n\t\t\t\t{}::{} => {{", t.name, get_variant_pattern(variant, Some(&[]), &self.naming)
+            ));
+            let args = variant.fields.iter().map(|field| Argument::from(field.clone())).collect::<Vec<Argument>>();
+            let args = self.gen_args(&args, false);
+            self.bindings_block.push_str(&format!(
+                "\n\t\t\t\t\t{}.expect(\"Constructor wasn't loaded\")({})\n\t\t\t\t}}", ctor_name, args
+            ));
+        }
+        self.bindings_block.push_str(r#"
+            }
+        }
+    }
+
+    /// Read value of enum object from library, object isn't deleted
+    pub unsafe fn from_ptr(ptr: Ptr) -> Self {"#);
+        let match_code = self.gen_variants_match(t, variants, &t.name, |field| format!("{field}.clone()"));
+        self.bindings_block.push_str(&match_code);
+        self.bindings_block.push_str(&format!(r#"
+    }}
+
+    /// Delete enum object created in library
+    pub unsafe fn delete_ptr(ptr: Ptr) {{
+        {}.expect("Destructor wasn't loaded from library")(ptr);
+    }}
+}}"#, dtor_name));
+    }
+
+    /// Generate view of enum class object which borrows fields of its variant from library,
+    /// so object can be matched without copying it
+    fn gen_enum_view(&mut self, t: &Type, variants: &[Variant]) {
+        let view_name = format!("{}Ref", get_type_name(t, &self.naming, false));
+        self.bindings_block.push_str("\n\n/// Borrowed view of ");
+        self.bindings_block.push_str(&t.name);
+        self.bindings_block.push_str(" object from library, it's valid while object is alive\n");
+        self.gen_vis(&t.vis);
+        self.bindings_block.push_str(&format!("enum {view_name}<'a> {{\n"));
+        for variant in variants {
+            let field_types = variant.fields.iter()
+                .map(|field| format!("&'a {}", get_typeref(&field.field_type, &field.attrs, &self.naming)))
+                .collect::<Vec<String>>();
+            self.bindings_block.push('\t');
+            self.bindings_block.push_str(&get_variant_pattern(variant, Some(&field_types), &self.naming));
+            self.bindings_block.push_str(",\n");
+        }
+        self.bindings_block.push_str(&format!(r#"}}
+
+impl<'a> {view_name}<'a> {{
+    /// View enum object from library, object must outlive the view
+    pub unsafe fn from_ptr(ptr: Ptr) -> Self {{"#));
+        let match_code = self.gen_variants_match(t, variants, &view_name, |field| field);
+        self.bindings_block.push_str(&match_code);
+        self.bindings_block.push_str("\n    }\n}");
+    }
+
+    /// Match tag of enum object at `ptr` to variants of `enum_name`, fields are borrowed from object
+    /// and passed to `field_value` for getting value of variant's field
+    fn gen_variants_match<F: Fn(String) -> String>(&self, t: &Type, variants: &[Variant], enum_name: &str,
+                                                   field_value: F) -> String {
+        let mut code = format!(r#"
+        let variants = {}_variants.expect("Variants weren't loaded");
+        match (variants.tag)(ptr) {{"#, t.name);
+        for variant in variants {
+            let field_values = variant.fields.iter()
+                .map(|field| field_value(format!(
+                    "((variants.field)(ptr, {}) as *const {}).as_ref().expect(\"Field of variant wasn't found\")",
+                    generate_variant_field_id(&variant.name, &field.name),
+                    get_typeref(&field.field_type, &field.attrs, &self.naming)
+                )))
+                .collect::<Vec<String>>();
+            code.push_str(&format!(
+                "\n\t\t\t{} => {}::{},",
+                variant.id, enum_name, get_variant_pattern(variant, Some(&field_values), &self.naming)
+            ));
+        }
+        code.push_str(&format!(r#"
+            _ => panic!("Unknown variant of {}")
+        }}"#, t.name));
+        code
+    }
+
     fn add_load_type(&mut self, t: &Type) -> String {
         let type_name = format!("{}_type", t.name);
         self.load_body.push_str(
-            &format!("let {} = {}.get_type({});\n", type_name, self.package_name, t.id.to_string())
+            &format!("let {} = {}.try_get_type({})?;\n", type_name, self.package_name, t.id.to_string())
         );
         type_name
     }
 
     pub fn generate(&mut self) {
         self.load_body.push_str(
-            &format!("let {} = ctx.get_package({});\n", self.package_name, self.package.id)
+            &format!("let {} = ctx.try_get_package({})?;\n", self.package_name, self.package.id)
         );
-        let types = self.package.types.to_vec();
+        // allocator is checked after all lookups, so bindings stay unchanged if any of them fails
+        self.assign_body.push_str("TANGARA_ALLOCATOR.set(ctx.get_allocator())?;\n");
+        let version = &self.package.version;
+        self.load_body.push_str(&format!(
+            "{}.check_compatibility(Version::new({}, {}, {}), {})?;\n",
+            self.package_name, version.major, version.minor, version.patch, self.package.abi
+        ));
+        // generic types are generated only as their declared instantiations
+        let mut types = Vec::with_capacity(self.package.types.len());
+        for t in &self.package.types {
+            if t.generics.0.is_empty() {
+                types.push(t.clone());
+            } else {
+                let instances = get_instances(&self.package, &self.config.instances, t);
+                types.extend(instances.into_iter().map(|(instance, _)| instance));
+            }
+        }
         for t in types {
             if self.pass_vis(&t.vis) {
-                // TODO: resolve generics later
-                if t.generics.0.len() > 0 {
-                    continue; // we can't resolve generics for now
-                }
                 self.gen_vis(&t.vis);
 
                 match &t.kind {
@@ -618,8 +1181,26 @@ impl SourceGenerator {
                         parents
                     } => {
                         let class_load_name = self.add_load_type(&t);
-                        // TODO implement parents
-                        // TODO do something with 'is_sealed'
+                        let parent_class = self.get_parent_class(&t);
+                        let descendants = if *is_sealed {
+                            vec![]
+                        } else {
+                            self.get_descendants(&t)
+                        };
+                        if parent_class.is_some() || !descendants.is_empty() {
+                            // classes of hierarchy are casted to each other by pointers
+                            let index_before_vis = self.bindings_block.len() - if t.vis == Visibility::Public {
+                                4
+                            } else {
+                                11
+                            }; // index to insert attributes before 'pub ' or 'pub(crate) '
+                            self.bindings_block.insert_str(index_before_vis, "#[repr(transparent)]\n");
+                        }
+                        if !descendants.is_empty() {
+                            let type_of_name = format!("{}_type_of", t.name);
+                            self.add_static(&type_of_name, "FnTypeOf");
+                            self.load_static(&type_of_name, &format!("Some({}.try_get_type_of()?)", class_load_name));
+                        }
                         self.bindings_block.push_str(&format!(r#"struct {} {{
     ptr: Ptr
 }}
@@ -655,16 +1236,23 @@ impl{} {} {{
                             self.gen_static_field(&field, &t.name);
                         }
                         for method in methods {
-                            self.gen_method(&method, &t.name);
+                            self.gen_class_method(method, &t, &descendants);
                         }
                         self.bindings_block.push('}');
-                        self.gen_drop(&t, &class_load_name);
+                        if let Some(parent_class) = &parent_class {
+                            self.gen_parent_class(&t, parent_class, &descendants);
+                        }
+                        for parent in parents {
+                            self.gen_interface_impl(&t, parent, &class_load_name);
+                        }
+                        self.gen_drop(&t, &class_load_name, &descendants);
                         // implement Default trait for empty constructor
                         if let Some(ctor_name) = default_ctor_name {
                             self.gen_default(&t, &ctor_name);
                         }
                     }
                     TypeKind::Enum { variants } => {
+                        let enum_load_name = self.add_load_type(&t);
                         let index_before_vis = self.bindings_block.len() - if t.vis == Visibility::Public {
                             4
                         } else {
@@ -678,17 +1266,35 @@ impl{} {} {{
                             self.bindings_block.push_str(&format!("\t{} = {},\n", &v.0, &get_value(&v.1)));
                         }
                         self.bindings_block.push('}');
+                        // variants of enum are handled as variants of enum class without fields
+                        let variants = variants.iter()
+                            .map(|(name, _)| Variant {
+                                attrs: vec![],
+                                vis: t.vis,
+                                name: name.clone(),
+                                id: generate_member_id(name),
+                                fields: vec![]
+                            })
+                            .collect::<Vec<Variant>>();
+                        self.gen_enum_impl(&t, &variants, &enum_load_name);
                     }
                     TypeKind::EnumClass { variants, methods } => {
                         let enum_load_name = self.add_load_type(&t);
                         self.bindings_block.push_str("enum ");
                         self.bindings_block.push_str(&get_type_name(&t, &self.naming, true));
                         self.bindings_block.push_str(" {\n");
-                        // TODO implement variants
-                        for method in methods {
-                            self.gen_method(&method, &t.name);
+                        for variant in variants {
+                            self.bindings_block.push('\t');
+                            self.bindings_block.push_str(&get_variant_pattern(variant, None, &self.naming));
+                            self.bindings_block.push_str(",\n");
                         }
                         self.bindings_block.push('}');
+                        self.gen_enum_impl(&t, variants, &enum_load_name);
+                        self.gen_enum_view(&t, variants);
+                        if !methods.is_empty() {
+                            println!("[Warning] (tangara-gen::SourceGenerator) Methods of enum class {} \
+                            are not supported yet, skipping them", t.name);
+                        }
                     }
                     TypeKind::Interface { properties, methods, parents } => {
                         // TODO implement parents
@@ -702,6 +1308,7 @@ impl{} {} {{
                             self.gen_property(&prop, None);
                         }
                         self.bindings_block.push('}');
+                        self.gen_host_implementation(&t, methods);
                     }
                     TypeKind::Struct { constructors, fields, static_fields } => {
                         let struct_load_name = self.add_load_type(&t);
@@ -735,7 +1342,7 @@ impl{} {} {{
                             self.gen_static_field(&field, &t.name);
                         }
                         self.bindings_block.push('}');
-                        self.gen_drop(&t, &struct_load_name);
+                        self.gen_drop(&t, &struct_load_name, &[]);
 
                         // implement Default trait for empty constructor
                         if let Some(ctor_name) = default_ctor_name {
@@ -747,7 +1354,7 @@ impl{} {} {{
                             &format!(
                                 "type {} = {};",
                                 get_type_name(&t, &self.naming, true),
-                                get_typeref(&alias, &self.naming)
+                                get_typeref(&alias, &t.attrs, &self.naming)
                             )
                         );
                     }
@@ -762,14 +1369,34 @@ impl{} {} {{
         self.generate();
         let disclaimer = r#"// This file was generated by tangara-gen
 // All changes in this file will discard after rebuilding project
-use tangara::context::{FnDtor, Context, Ptr, Fn};
+use tangara::allocator::AllocatorCell;
+use tangara::context::{FnDtor, FnTypeOf, Context, Implementation, Ptr, Fn, Variants, Version};
+use tangara::error::TangaraError;
+
+/// Allocator of context from which bindings were loaded
+static TANGARA_ALLOCATOR: AllocatorCell = AllocatorCell::new();
 
 "#.to_string();
         self.statics_block.push('\n');
+        self.load_body.push_str(&self.assign_body);
         let mut load_body = self.load_body.replace("\n", "\n\t\t");
         load_body.remove(load_body.len() - 1); // remove last extra '\t'
+        let mut unload_body = self.unload_body.replace("\n", "\n\t\t");
+        unload_body.truncate(unload_body.len().saturating_sub(1)); // remove last extra '\t'
         let pkg_name = self.package.name;
-        let load_fn = format!("\npub fn load_{pkg_name}(ctx: &Context) {{\n\tunsafe {{\n\t\t{load_body}}}\n}}\n");
-        std::fs::write(path, String::from_iter([disclaimer, self.statics_block, self.bindings_block, load_fn]))
+        let load_fn = format!(
+            "\npub fn load_{pkg_name}(ctx: &Context) -> Result<(), TangaraError> {{\n\tunsafe {{\n\t\t{load_body}}}\n\tOk(())\n}}\n"
+        );
+        // functions of unloaded library mustn't be called, so every slot is reset to None
+        let unload_fn = format!(
+            "\n/// Forget all functions loaded by `load_{pkg_name}`. Call it before library is unloaded.\n\
+            pub fn unload_{pkg_name}() {{\n\tunsafe {{\n\t\t{unload_body}}}\n}}\n"
+        );
+        // reload is just unload and load again from context with refreshed package
+        let reload_fn = format!(
+            "\n/// Refresh all functions from context after library was reloaded\n\
+            pub fn reload_{pkg_name}(ctx: &Context) -> Result<(), TangaraError> {{\n\tunload_{pkg_name}();\n\tload_{pkg_name}(ctx)\n}}\n"
+        );
+        std::fs::write(path, String::from_iter([disclaimer, self.statics_block, self.bindings_block, load_fn, unload_fn, reload_fn]))
     }
 }
\ No newline at end of file
@@ -0,0 +1,799 @@
+//! Compact binary encoding of [Package], alternative to JSON made by serde.
+//!
+//! Format: magic `TGPK`, format version (u16 LE), table of interned strings and then package itself.
+//! Strings (names, namespaces, typerefs and so on) are written once into the table
+//! and referenced by index, numbers are written as LEB128 varints except ids which are fixed u64.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+use crate::*;
+
+/// First bytes of every binary package
+pub const MAGIC: [u8; 4] = *b"TGPK";
+/// Version of binary format written by this library.
+/// Version 2 added dependencies of package, packages of version 1 are read without them.
+pub const FORMAT_VERSION: u16 = 2;
+
+#[derive(Debug)]
+pub enum BinaryError {
+    Io(std::io::Error),
+    /// Data doesn't start with [MAGIC]
+    InvalidMagic,
+    /// Data was written by newer version of format
+    UnsupportedVersion(u16),
+    /// Data is corrupted
+    InvalidData(String)
+}
+
+impl Display for BinaryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BinaryError::Io(err) => write!(f, "IO error: {err}"),
+            BinaryError::InvalidMagic => write!(f, "Data is not a binary Tangara package"),
+            BinaryError::UnsupportedVersion(version) => {
+                write!(f, "Binary package format version {version} is not supported")
+            }
+            BinaryError::InvalidData(message) => write!(f, "Invalid binary package: {message}")
+        }
+    }
+}
+
+impl std::error::Error for BinaryError {}
+
+impl From<std::io::Error> for BinaryError {
+    fn from(value: std::io::Error) -> Self {
+        BinaryError::Io(value)
+    }
+}
+
+/// Check that bytes begin with header of binary package
+pub fn is_binary_package(bytes: &[u8]) -> bool {
+    bytes.starts_with(&MAGIC)
+}
+
+/// Write package in binary format
+pub fn write_package<W: Write>(package: &Package, writer: &mut W) -> std::io::Result<()> {
+    writer.write_all(&package_to_bytes(package))
+}
+
+/// Read package in binary format
+pub fn read_package<R: Read>(reader: &mut R) -> Result<Package, BinaryError> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    package_from_bytes(&bytes)
+}
+
+pub fn package_to_bytes(package: &Package) -> Vec<u8> {
+    let mut encoder = Encoder {
+        strings: HashMap::new(),
+        string_table: vec![],
+        body: vec![]
+    };
+    encoder.package(package);
+
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(FORMAT_VERSION.to_le_bytes());
+    write_varint(&mut bytes, encoder.string_table.len() as u64);
+    for string in &encoder.string_table {
+        write_varint(&mut bytes, string.len() as u64);
+        bytes.extend(string.as_bytes());
+    }
+    bytes.extend(encoder.body);
+    bytes
+}
+
+pub fn package_from_bytes(bytes: &[u8]) -> Result<Package, BinaryError> {
+    if !is_binary_package(bytes) {
+        return Err(BinaryError::InvalidMagic);
+    }
+    let mut decoder = Decoder {
+        bytes,
+        pos: MAGIC.len(),
+        strings: vec![],
+        format_version: FORMAT_VERSION
+    };
+    decoder.format_version = u16::from_le_bytes([decoder.u8()?, decoder.u8()?]);
+    if decoder.format_version > FORMAT_VERSION {
+        return Err(BinaryError::UnsupportedVersion(decoder.format_version));
+    }
+    let strings_count = decoder.len()?;
+    for _ in 0..strings_count {
+        let len = decoder.len()?;
+        let string = std::str::from_utf8(decoder.take(len)?)
+            .map_err(|err| BinaryError::InvalidData(err.to_string()))?;
+        decoder.strings.push(string.to_string());
+    }
+    let package = decoder.package()?;
+    if decoder.pos != bytes.len() {
+        return Err(BinaryError::InvalidData("Extra bytes after package".to_string()));
+    }
+    Ok(package)
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            break;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+struct Encoder {
+    strings: HashMap<String, u64>,
+    string_table: Vec<String>,
+    body: Vec<u8>
+}
+
+impl Encoder {
+    fn u8(&mut self, value: u8) {
+        self.body.push(value);
+    }
+
+    fn varint(&mut self, value: u64) {
+        write_varint(&mut self.body, value);
+    }
+
+    fn id(&mut self, value: u64) {
+        self.body.extend(value.to_le_bytes());
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+
+    fn string(&mut self, value: &str) {
+        let index = match self.strings.get(value) {
+            Some(index) => *index,
+            None => {
+                let index = self.string_table.len() as u64;
+                self.strings.insert(value.to_string(), index);
+                self.string_table.push(value.to_string());
+                index
+            }
+        };
+        self.varint(index);
+    }
+
+    fn vec<T>(&mut self, values: &[T], mut encode: impl FnMut(&mut Self, &T)) {
+        self.varint(values.len() as u64);
+        for value in values {
+            encode(self, value);
+        }
+    }
+
+    fn option<T>(&mut self, value: &Option<T>, encode: impl FnOnce(&mut Self, &T)) {
+        match value {
+            Some(value) => {
+                self.u8(1);
+                encode(self, value);
+            }
+            None => self.u8(0)
+        }
+    }
+
+    fn visibility(&mut self, vis: &Visibility) {
+        self.u8(match vis {
+            Visibility::Private => 0,
+            Visibility::Protected => 1,
+            Visibility::Internal => 2,
+            Visibility::Public => 3
+        });
+    }
+
+    fn typeref(&mut self, typeref: &TypeRef) {
+        match typeref {
+            TypeRef::Name(name) => {
+                self.u8(0);
+                self.string(name);
+            }
+            TypeRef::Id(id) => {
+                self.u8(1);
+                self.id(*id);
+            }
+            TypeRef::Generic(base, generics) => {
+                self.u8(2);
+                self.typeref(base);
+                self.vec(generics, Self::typeref);
+            }
+            TypeRef::Tuple(types) => {
+                self.u8(3);
+                self.vec(types, Self::typeref);
+            }
+            TypeRef::Fn(return_type, args) => {
+                self.u8(4);
+                self.option(return_type, |encoder, return_type| encoder.typeref(return_type));
+                self.vec(args, Self::typeref);
+            }
+        }
+    }
+
+    fn value(&mut self, value: &Value) {
+        match value {
+            Value::Null => self.u8(0),
+            Value::Bool(v) => {
+                self.u8(1);
+                self.bool(*v);
+            }
+            Value::Byte(v) => {
+                self.u8(2);
+                self.u8(*v);
+            }
+            Value::Short(v) => {
+                self.u8(3);
+                self.body.extend(v.to_le_bytes());
+            }
+            Value::Int(v) => {
+                self.u8(4);
+                self.body.extend(v.to_le_bytes());
+            }
+            Value::Long(v) => {
+                self.u8(5);
+                self.body.extend(v.to_le_bytes());
+            }
+            Value::SByte(v) => {
+                self.u8(6);
+                self.body.extend(v.to_le_bytes());
+            }
+            Value::UShort(v) => {
+                self.u8(7);
+                self.body.extend(v.to_le_bytes());
+            }
+            Value::UInt(v) => {
+                self.u8(8);
+                self.body.extend(v.to_le_bytes());
+            }
+            Value::ULong(v) => {
+                self.u8(9);
+                self.body.extend(v.to_le_bytes());
+            }
+            Value::Float(v) => {
+                self.u8(10);
+                self.body.extend(v.to_le_bytes());
+            }
+            Value::Double(v) => {
+                self.u8(11);
+                self.body.extend(v.to_le_bytes());
+            }
+            Value::String(v) => {
+                self.u8(12);
+                self.string(v);
+            }
+            Value::Array(values) => {
+                self.u8(13);
+                self.vec(values, Self::value);
+            }
+            Value::Tuple(values) => {
+                self.u8(14);
+                self.vec(values, Self::value);
+            }
+            Value::Object(fields) => {
+                self.u8(15);
+                // sort fields so the same object is always written the same way
+                let mut fields = fields.iter().collect::<Vec<_>>();
+                fields.sort_by(|a, b| a.0.cmp(b.0));
+                self.vec(&fields, |encoder, (name, value)| {
+                    encoder.string(name);
+                    encoder.value(value);
+                });
+            }
+        }
+    }
+
+    fn attrs(&mut self, attrs: &[Attribute]) {
+        self.vec(attrs, |encoder, attr| {
+            encoder.typeref(&attr.0);
+            encoder.vec(&attr.1, Self::value);
+        });
+    }
+
+    fn generics(&mut self, generics: &Generics) {
+        self.vec(&generics.0, |encoder, name| encoder.string(name));
+        self.vec(&generics.1, |encoder, (name, typeref)| {
+            encoder.string(name);
+            encoder.typeref(typeref);
+        });
+    }
+
+    fn args(&mut self, args: &[Argument]) {
+        self.vec(args, |encoder, arg| {
+            encoder.attrs(&arg.0);
+            encoder.typeref(&arg.1);
+            encoder.string(&arg.2);
+            match &arg.3 {
+                ArgumentKind::Default => encoder.u8(0),
+                ArgumentKind::DefaultValue(value) => {
+                    encoder.u8(1);
+                    encoder.value(value);
+                }
+                ArgumentKind::Out => encoder.u8(2),
+                ArgumentKind::Ref => encoder.u8(3),
+                ArgumentKind::In => encoder.u8(4)
+            }
+        });
+    }
+
+    fn constructors(&mut self, constructors: &[Constructor]) {
+        self.vec(constructors, |encoder, ctor| {
+            encoder.attrs(&ctor.attrs);
+            encoder.visibility(&ctor.vis);
+            encoder.args(&ctor.args);
+        });
+    }
+
+    fn fields(&mut self, fields: &[Field]) {
+        self.vec(fields, |encoder, field| {
+            encoder.attrs(&field.attrs);
+            encoder.visibility(&field.vis);
+            encoder.typeref(&field.field_type);
+            encoder.string(&field.name);
+            encoder.option(&field.default_value, Self::value);
+            encoder.id(field.id);
+        });
+    }
+
+    fn properties(&mut self, properties: &[Property]) {
+        self.vec(properties, |encoder, prop| {
+            encoder.attrs(&prop.attrs);
+            encoder.visibility(&prop.getter_visibility);
+            encoder.option(&prop.setter_visibility, Self::visibility);
+            encoder.typeref(&prop.prop_type);
+            encoder.string(&prop.name);
+            encoder.id(prop.id);
+        });
+    }
+
+    fn methods(&mut self, methods: &[Method]) {
+        self.vec(methods, |encoder, method| {
+            encoder.attrs(&method.attrs);
+            encoder.visibility(&method.vis);
+            encoder.string(&method.name);
+            encoder.id(method.id);
+            encoder.generics(&method.generics);
+            encoder.args(&method.args);
+            encoder.option(&method.return_type, Self::typeref);
+            encoder.u8(match method.kind {
+                MethodKind::Default => 0,
+                MethodKind::Abstract => 1,
+                MethodKind::Virtual => 2,
+                MethodKind::Static => 3
+            });
+        });
+    }
+
+    fn type_kind(&mut self, kind: &TypeKind) {
+        match kind {
+            TypeKind::Class {
+                is_sealed, constructors, properties, fields, static_properties, static_fields, methods, parents
+            } => {
+                self.u8(0);
+                self.bool(*is_sealed);
+                self.constructors(constructors);
+                self.properties(properties);
+                self.fields(fields);
+                self.properties(static_properties);
+                self.fields(static_fields);
+                self.methods(methods);
+                self.vec(parents, Self::typeref);
+            }
+            TypeKind::Enum { variants } => {
+                self.u8(1);
+                self.vec(variants, |encoder, (name, value)| {
+                    encoder.string(name);
+                    encoder.value(value);
+                });
+            }
+            TypeKind::EnumClass { variants, methods } => {
+                self.u8(2);
+                self.vec(variants, |encoder, variant| {
+                    encoder.attrs(&variant.attrs);
+                    encoder.visibility(&variant.vis);
+                    encoder.string(&variant.name);
+                    encoder.id(variant.id);
+                    encoder.fields(&variant.fields);
+                });
+                self.methods(methods);
+            }
+            TypeKind::Interface { properties, methods, parents } => {
+                self.u8(3);
+                self.properties(properties);
+                self.methods(methods);
+                self.vec(parents, Self::typeref);
+            }
+            TypeKind::Struct { constructors, fields, static_fields } => {
+                self.u8(4);
+                self.constructors(constructors);
+                self.fields(fields);
+                self.fields(static_fields);
+            }
+            TypeKind::TypeAlias(alias) => {
+                self.u8(5);
+                self.typeref(alias);
+            }
+        }
+    }
+
+    fn naming(&mut self, naming: &Naming) {
+        self.string(naming.get_prefix());
+        self.string(naming.get_suffix());
+        self.string(naming.get_sep());
+        self.u8(match naming.get_case() {
+            NamingCase::Lower => 0,
+            NamingCase::Upper => 1,
+            NamingCase::Pascal => 2,
+            NamingCase::Camel => 3
+        });
+    }
+
+    fn naming_conventions(&mut self, naming: &NamingConventions) {
+        self.naming(&naming.package);
+        self.string(&naming.package_divider);
+        self.naming(&naming.namespace);
+        self.string(&naming.namespace_divider);
+        self.naming(&naming.base_type);
+        self.naming(&naming.interface);
+        self.naming(&naming.private_field);
+        self.naming(&naming.private_static);
+        self.naming(&naming.method);
+        self.naming(&naming.property);
+        self.naming(&naming.variant);
+        self.naming(&naming.parameter);
+    }
+
+    fn version(&mut self, version: &Version) {
+        self.varint(version.major as u64);
+        self.varint(version.minor as u64);
+        self.varint(version.patch as u64);
+    }
+
+    fn package(&mut self, package: &Package) {
+        self.attrs(&package.attrs);
+        self.string(&package.name);
+        self.id(package.id);
+        self.version(&package.version);
+        self.id(package.abi);
+        self.vec(&package.dependencies, |encoder, dependency| {
+            encoder.string(&dependency.name);
+            encoder.id(dependency.id);
+            encoder.version(&dependency.version.min);
+            encoder.option(&dependency.version.max, Self::version);
+        });
+        self.vec(&package.types, |encoder, t| {
+            encoder.attrs(&t.attrs);
+            encoder.visibility(&t.vis);
+            encoder.string(&t.namespace);
+            encoder.string(&t.name);
+            encoder.id(t.id);
+            encoder.generics(&t.generics);
+            encoder.type_kind(&t.kind);
+        });
+        self.naming_conventions(&package.naming);
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    strings: Vec<String>,
+    /// Version of format data was written with
+    format_version: u16
+}
+
+fn invalid_tag<T>(name: &str, tag: u8) -> Result<T, BinaryError> {
+    Err(BinaryError::InvalidData(format!("Unknown {name} tag {tag}")))
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], BinaryError> {
+        let end = self.pos.checked_add(count)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| BinaryError::InvalidData("Unexpected end of data".to_string()))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], BinaryError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, BinaryError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, BinaryError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(BinaryError::InvalidData("Varint is too long".to_string()))
+    }
+
+    fn len(&mut self) -> Result<usize, BinaryError> {
+        let len = self.varint()?;
+        // every element takes at least one byte, so it protects from allocating huge vectors
+        if len > (self.bytes.len() - self.pos) as u64 {
+            return Err(BinaryError::InvalidData(format!("Length {len} is out of data")));
+        }
+        Ok(len as usize)
+    }
+
+    fn u32(&mut self) -> Result<u32, BinaryError> {
+        u32::try_from(self.varint()?).map_err(|err| BinaryError::InvalidData(err.to_string()))
+    }
+
+    fn id(&mut self) -> Result<u64, BinaryError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn bool(&mut self) -> Result<bool, BinaryError> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            tag => invalid_tag("bool", tag)
+        }
+    }
+
+    fn string(&mut self) -> Result<String, BinaryError> {
+        let index = self.varint()?;
+        self.strings.get(index as usize)
+            .cloned()
+            .ok_or_else(|| BinaryError::InvalidData(format!("String index {index} is out of table")))
+    }
+
+    fn vec<T>(&mut self, mut decode: impl FnMut(&mut Self) -> Result<T, BinaryError>) -> Result<Vec<T>, BinaryError> {
+        let len = self.len()?;
+        let mut values = Vec::with_capacity(len);
+        for _ in 0..len {
+            values.push(decode(self)?);
+        }
+        Ok(values)
+    }
+
+    fn option<T>(&mut self, decode: impl FnOnce(&mut Self) -> Result<T, BinaryError>) -> Result<Option<T>, BinaryError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(decode(self)?)),
+            tag => invalid_tag("option", tag)
+        }
+    }
+
+    fn visibility(&mut self) -> Result<Visibility, BinaryError> {
+        match self.u8()? {
+            0 => Ok(Visibility::Private),
+            1 => Ok(Visibility::Protected),
+            2 => Ok(Visibility::Internal),
+            3 => Ok(Visibility::Public),
+            tag => invalid_tag("visibility", tag)
+        }
+    }
+
+    fn typeref(&mut self) -> Result<TypeRef, BinaryError> {
+        match self.u8()? {
+            0 => Ok(TypeRef::Name(self.string()?)),
+            1 => Ok(TypeRef::Id(self.id()?)),
+            2 => Ok(TypeRef::Generic(Box::new(self.typeref()?), self.vec(Self::typeref)?)),
+            3 => Ok(TypeRef::Tuple(self.vec(Self::typeref)?)),
+            4 => {
+                let return_type = self.option(Self::typeref)?.map(Box::new);
+                Ok(TypeRef::Fn(return_type, self.vec(Self::typeref)?))
+            }
+            tag => invalid_tag("typeref", tag)
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, BinaryError> {
+        Ok(match self.u8()? {
+            0 => Value::Null,
+            1 => Value::Bool(self.bool()?),
+            2 => Value::Byte(self.u8()?),
+            3 => Value::Short(i16::from_le_bytes(self.array()?)),
+            4 => Value::Int(i32::from_le_bytes(self.array()?)),
+            5 => Value::Long(i64::from_le_bytes(self.array()?)),
+            6 => Value::SByte(i8::from_le_bytes(self.array()?)),
+            7 => Value::UShort(u16::from_le_bytes(self.array()?)),
+            8 => Value::UInt(u32::from_le_bytes(self.array()?)),
+            9 => Value::ULong(u64::from_le_bytes(self.array()?)),
+            10 => Value::Float(f32::from_le_bytes(self.array()?)),
+            11 => Value::Double(f64::from_le_bytes(self.array()?)),
+            12 => Value::String(self.string()?),
+            13 => Value::Array(self.vec(Self::value)?),
+            14 => Value::Tuple(self.vec(Self::value)?),
+            15 => Value::Object(
+                self.vec(|decoder| Ok((decoder.string()?, Box::new(decoder.value()?))))?
+                    .into_iter()
+                    .collect()
+            ),
+            tag => return invalid_tag("value", tag)
+        })
+    }
+
+    fn attrs(&mut self) -> Result<Vec<Attribute>, BinaryError> {
+        self.vec(|decoder| Ok(Attribute(decoder.typeref()?, decoder.vec(Self::value)?)))
+    }
+
+    fn generics(&mut self) -> Result<Generics, BinaryError> {
+        Ok(Generics(
+            self.vec(Self::string)?,
+            self.vec(|decoder| Ok((decoder.string()?, decoder.typeref()?)))?
+        ))
+    }
+
+    fn args(&mut self) -> Result<Vec<Argument>, BinaryError> {
+        self.vec(|decoder| {
+            let attrs = decoder.attrs()?;
+            let typeref = decoder.typeref()?;
+            let name = decoder.string()?;
+            let kind = match decoder.u8()? {
+                0 => ArgumentKind::Default,
+                1 => ArgumentKind::DefaultValue(decoder.value()?),
+                2 => ArgumentKind::Out,
+                3 => ArgumentKind::Ref,
+                4 => ArgumentKind::In,
+                tag => return invalid_tag("argument kind", tag)
+            };
+            Ok(Argument(attrs, typeref, name, kind))
+        })
+    }
+
+    fn constructors(&mut self) -> Result<Vec<Constructor>, BinaryError> {
+        self.vec(|decoder| Ok(Constructor {
+            attrs: decoder.attrs()?,
+            vis: decoder.visibility()?,
+            args: decoder.args()?
+        }))
+    }
+
+    fn fields(&mut self) -> Result<Vec<Field>, BinaryError> {
+        self.vec(|decoder| Ok(Field {
+            attrs: decoder.attrs()?,
+            vis: decoder.visibility()?,
+            field_type: decoder.typeref()?,
+            name: decoder.string()?,
+            default_value: decoder.option(Self::value)?,
+            id: decoder.id()?
+        }))
+    }
+
+    fn properties(&mut self) -> Result<Vec<Property>, BinaryError> {
+        self.vec(|decoder| Ok(Property {
+            attrs: decoder.attrs()?,
+            getter_visibility: decoder.visibility()?,
+            setter_visibility: decoder.option(Self::visibility)?,
+            prop_type: decoder.typeref()?,
+            name: decoder.string()?,
+            id: decoder.id()?
+        }))
+    }
+
+    fn methods(&mut self) -> Result<Vec<Method>, BinaryError> {
+        self.vec(|decoder| Ok(Method {
+            attrs: decoder.attrs()?,
+            vis: decoder.visibility()?,
+            name: decoder.string()?,
+            id: decoder.id()?,
+            generics: decoder.generics()?,
+            args: decoder.args()?,
+            return_type: decoder.option(Self::typeref)?,
+            kind: match decoder.u8()? {
+                0 => MethodKind::Default,
+                1 => MethodKind::Abstract,
+                2 => MethodKind::Virtual,
+                3 => MethodKind::Static,
+                tag => return invalid_tag("method kind", tag)
+            }
+        }))
+    }
+
+    fn type_kind(&mut self) -> Result<TypeKind, BinaryError> {
+        Ok(match self.u8()? {
+            0 => TypeKind::Class {
+                is_sealed: self.bool()?,
+                constructors: self.constructors()?,
+                properties: self.properties()?,
+                fields: self.fields()?,
+                static_properties: self.properties()?,
+                static_fields: self.fields()?,
+                methods: self.methods()?,
+                parents: self.vec(Self::typeref)?
+            },
+            1 => TypeKind::Enum {
+                variants: self.vec(|decoder| Ok((decoder.string()?, decoder.value()?)))?
+            },
+            2 => TypeKind::EnumClass {
+                variants: self.vec(|decoder| Ok(Variant {
+                    attrs: decoder.attrs()?,
+                    vis: decoder.visibility()?,
+                    name: decoder.string()?,
+                    id: decoder.id()?,
+                    fields: decoder.fields()?
+                }))?,
+                methods: self.methods()?
+            },
+            3 => TypeKind::Interface {
+                properties: self.properties()?,
+                methods: self.methods()?,
+                parents: self.vec(Self::typeref)?
+            },
+            4 => TypeKind::Struct {
+                constructors: self.constructors()?,
+                fields: self.fields()?,
+                static_fields: self.fields()?
+            },
+            5 => TypeKind::TypeAlias(Box::new(self.typeref()?)),
+            tag => return invalid_tag("type kind", tag)
+        })
+    }
+
+    fn naming(&mut self) -> Result<Naming, BinaryError> {
+        let prefix = self.string()?;
+        let suffix = self.string()?;
+        let sep = self.string()?;
+        let case = match self.u8()? {
+            0 => NamingCase::Lower,
+            1 => NamingCase::Upper,
+            2 => NamingCase::Pascal,
+            3 => NamingCase::Camel,
+            tag => return invalid_tag("naming case", tag)
+        };
+        Ok(Naming::new(prefix, suffix, sep, case))
+    }
+
+    fn naming_conventions(&mut self) -> Result<NamingConventions, BinaryError> {
+        Ok(NamingConventions {
+            package: self.naming()?,
+            package_divider: self.string()?,
+            namespace: self.naming()?,
+            namespace_divider: self.string()?,
+            base_type: self.naming()?,
+            interface: self.naming()?,
+            private_field: self.naming()?,
+            private_static: self.naming()?,
+            method: self.naming()?,
+            property: self.naming()?,
+            variant: self.naming()?,
+            parameter: self.naming()?
+        })
+    }
+
+    fn version(&mut self) -> Result<Version, BinaryError> {
+        Ok(Version::new(self.u32()?, self.u32()?, self.u32()?))
+    }
+
+    fn package(&mut self) -> Result<Package, BinaryError> {
+        Ok(Package {
+            attrs: self.attrs()?,
+            name: self.string()?,
+            id: self.id()?,
+            version: self.version()?,
+            abi: self.id()?,
+            dependencies: if self.format_version >= 2 {
+                self.vec(|decoder| Ok(Dependency {
+                    name: decoder.string()?,
+                    id: decoder.id()?,
+                    version: VersionRange::new(decoder.version()?, decoder.option(Self::version)?)
+                }))?
+            } else {
+                vec![]
+            },
+            types: self.vec(|decoder| Ok(Type {
+                attrs: decoder.attrs()?,
+                vis: decoder.visibility()?,
+                namespace: decoder.string()?,
+                name: decoder.string()?,
+                id: decoder.id()?,
+                generics: decoder.generics()?,
+                kind: decoder.type_kind()?
+            }))?,
+            naming: self.naming_conventions()?
+        })
+    }
+}
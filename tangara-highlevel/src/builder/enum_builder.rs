@@ -3,6 +3,7 @@ use std::rc::Rc;
 use crate::builder::{AttributeCollector, GenericsCollector, PackageBuilder, FieldBuilder, FieldCollector, TypeBuilder};
 use crate::{Attribute, Field, generate_member_id, generate_type_id, Generics, Method, Property, Type, TypeRef, Value, Variant, Visibility};
 use crate::TypeKind::{Enum, EnumClass};
+use crate::tangara_std::TANGARA_STD;
 
 pub struct EnumBuilder {
     builder: Rc<RefCell<PackageBuilder>>,
@@ -113,7 +114,7 @@ impl TypeBuilder for BitflagsBuilder {
     fn get_type(&self) -> Type {
         let enum_builder = &self.builder;
         let mut attrs = self.builder.attrs.to_vec();
-        attrs.push(Attribute(TypeRef::Name("Tangara.Flags".to_string()), vec![]));
+        attrs.push(TANGARA_STD.flags_attribute());
         let namespace = enum_builder.builder.borrow().namespace.clone();
         let name = enum_builder.name.clone();
         let mut full_name = String::with_capacity(namespace.len() + name.len() + 1);
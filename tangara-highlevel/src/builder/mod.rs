@@ -1,6 +1,6 @@
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::{Attribute, generate_package_id, NamingConventions, Package, Type, TypeRef, Visibility};
+use crate::{Attribute, Dependency, generate_abi_fingerprint, generate_package_id, NamingConventions, Package, Type, TypeRef, Version, Visibility};
 
 mod enum_builder;
 mod class_builder;
@@ -69,6 +69,8 @@ pub struct PackageBuilder {
     /// Default visibility for methods
     pub method_visibility: Visibility,
     attrs: Vec<Attribute>,
+    version: Version,
+    dependencies: Vec<Dependency>,
     types: Vec<Type>,
     naming: NamingConventions
 }
@@ -84,6 +86,8 @@ impl PackageBuilder {
                 member_visibility: Visibility::Public,
                 method_visibility: Visibility::Public,
                 attrs: vec![],
+                version: Version::default(),
+                dependencies: vec![],
                 types: vec![],
                 naming
             }
@@ -119,6 +123,20 @@ impl PackageBuilder {
         generate_package_id(&self.name)
     }
 
+    pub fn set_version(&mut self, version: Version) -> &mut Self {
+        self.version = version;
+        self
+    }
+
+    pub fn get_version(&self) -> Version {
+        self.version
+    }
+
+    pub fn add_dependency(&mut self, dependency: Dependency) -> &mut Self {
+        self.dependencies.push(dependency);
+        self
+    }
+
     pub fn add_type(&mut self, t: Type) -> &mut Self {
         self.types.push(t);
         self
@@ -129,6 +147,9 @@ impl PackageBuilder {
             attrs: self.attrs.to_vec(),
             name: self.name.clone(),
             id: self.get_id(),
+            version: self.version,
+            abi: generate_abi_fingerprint(&self.types),
+            dependencies: self.dependencies.to_vec(),
             types: self.types.to_vec(),
             naming: self.naming.clone()
         }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 #[cfg(feature = "serde")]
 use serde::{Serialize, Deserialize};
 use xxhash_rust::const_xxh3::const_custom_default_secret;
@@ -7,8 +8,16 @@ use xxhash_rust::xxh3::xxh3_64_with_secret;
 mod naming;
 pub use naming::*;
 
+pub mod binary;
+pub mod primitives;
+pub mod resolver;
+pub mod universe;
+pub mod validation;
+
 #[cfg(feature = "builder")]
 pub mod builder;
+#[cfg(feature = "builder")]
+pub mod tangara_std;
 
 // Enums block
 #[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
@@ -99,6 +108,7 @@ pub enum TypeRef {
 const PACKAGE_SECRET: [u8; 192] = const_custom_default_secret(772);
 const TYPE_SECRET: [u8; 192] = const_custom_default_secret(4900);
 const MEMBER_SECRET: [u8; 192] = const_custom_default_secret(18257);
+const ABI_SECRET: [u8; 192] = const_custom_default_secret(31337);
 
 /// Generate XXHash id for package with given name
 pub fn generate_package_id(name: &str) -> u64 {
@@ -115,11 +125,17 @@ pub fn generate_member_id(name: &str) -> u64 {
     xxh3_64_with_secret(name.as_bytes(), &MEMBER_SECRET)
 }
 
+/// Generate id of enum class variant's field,
+/// fields of different variants can have the same name so variant name is included
+pub fn generate_variant_field_id(variant: &str, field: &str) -> u64 {
+    generate_member_id(&format!("{variant}.{field}"))
+}
+
 /// Generate vec of bytes made from type's id or collection of ids
 pub fn get_typeref_bytes(type_ref: &TypeRef) -> Vec<u8> {
     match type_ref {
         TypeRef::Name(name) => {
-            // NOTE: if name doesn't contains namespace (it would in most cases) it can get wrong id
+            // NOTE: if name doesn't contains namespace it gets wrong id, resolve package before (see resolver module)
             generate_type_id(&name).to_be_bytes().to_vec()
         }
         TypeRef::Id(id) => {
@@ -160,6 +176,11 @@ pub fn get_typeref_bytes(type_ref: &TypeRef) -> Vec<u8> {
     }
 }
 
+/// Generate XXHash id for concrete instantiation of generic type, for example `GenericsTest<Foo>`
+pub fn generate_instance_id(type_ref: &TypeRef) -> u64 {
+    xxh3_64_with_secret(&get_typeref_bytes(type_ref), &TYPE_SECRET)
+}
+
 /// Generate XXHash id for method with given name
 pub fn generate_method_id(name: &str, args: &Vec<Argument>) -> u64 {
     // What makes method unique? His name and types of his arguments
@@ -171,6 +192,126 @@ pub fn generate_method_id(name: &str, args: &Vec<Argument>) -> u64 {
     xxh3_64_with_secret([name.as_bytes(), args_bytes.as_slice()].concat().as_slice(), &MEMBER_SECRET)
 }
 
+fn get_args_bytes(args: &[Argument]) -> Vec<u8> {
+    let mut bytes = (args.len() as u64).to_be_bytes().to_vec();
+    for arg in args {
+        bytes.extend(get_typeref_bytes(&arg.1));
+        bytes.push(match arg.3 {
+            ArgumentKind::Default | ArgumentKind::DefaultValue(_) => 0,
+            ArgumentKind::Out => 1,
+            ArgumentKind::Ref => 2,
+            ArgumentKind::In => 3
+        });
+    }
+    bytes
+}
+
+fn get_methods_bytes(methods: &[Method]) -> Vec<u8> {
+    let mut bytes = vec![];
+    for method in methods {
+        bytes.extend(method.id.to_be_bytes());
+        bytes.extend(get_args_bytes(&method.args));
+        match &method.return_type {
+            Some(return_type) => bytes.extend(get_typeref_bytes(return_type)),
+            None => bytes.extend([0u8; 8])
+        }
+        bytes.push(method.kind as u8);
+    }
+    bytes
+}
+
+fn get_fields_bytes(fields: &[Field]) -> Vec<u8> {
+    // order of fields matters because it's a layout of type
+    let mut bytes = (fields.len() as u64).to_be_bytes().to_vec();
+    for field in fields {
+        bytes.extend(field.id.to_be_bytes());
+        bytes.extend(get_typeref_bytes(&field.field_type));
+    }
+    bytes
+}
+
+fn get_properties_bytes(properties: &[Property]) -> Vec<u8> {
+    let mut bytes = vec![];
+    for prop in properties {
+        bytes.extend(prop.id.to_be_bytes());
+        bytes.extend(get_typeref_bytes(&prop.prop_type));
+        bytes.push(prop.setter_visibility.is_some() as u8);
+    }
+    bytes
+}
+
+fn get_constructors_bytes(constructors: &[Constructor]) -> Vec<u8> {
+    // constructors are registered by index so their order matters too
+    let mut bytes = (constructors.len() as u64).to_be_bytes().to_vec();
+    for ctor in constructors {
+        bytes.extend(get_args_bytes(&ctor.args));
+    }
+    bytes
+}
+
+/// Generate XXHash fingerprint of binary interface of given types.
+/// It depends on ids of all members, types of arguments and layout of fields,
+/// so any change which breaks calls through the runtime changes the fingerprint.
+pub fn generate_abi_fingerprint(types: &[Type]) -> u64 {
+    let mut types = types.iter().collect::<Vec<&Type>>();
+    // order of types in package doesn't matter
+    types.sort_by_key(|t| t.id);
+    let mut bytes = vec![];
+    for t in types {
+        bytes.extend(t.id.to_be_bytes());
+        match &t.kind {
+            TypeKind::Class {
+                constructors, properties, fields, static_properties, static_fields, methods, parents, ..
+            } => {
+                bytes.push(0);
+                bytes.extend(get_constructors_bytes(constructors));
+                bytes.extend(get_properties_bytes(properties));
+                bytes.extend(get_fields_bytes(fields));
+                bytes.extend(get_properties_bytes(static_properties));
+                bytes.extend(get_fields_bytes(static_fields));
+                bytes.extend(get_methods_bytes(methods));
+                for parent in parents {
+                    bytes.extend(get_typeref_bytes(parent));
+                }
+            }
+            TypeKind::Enum { variants } => {
+                bytes.push(1);
+                for (name, value) in variants {
+                    bytes.extend(name.as_bytes());
+                    bytes.extend(format!("{:?}", value).as_bytes());
+                }
+            }
+            TypeKind::EnumClass { variants, methods } => {
+                bytes.push(2);
+                for variant in variants {
+                    bytes.extend(variant.id.to_be_bytes());
+                    bytes.extend(get_fields_bytes(&variant.fields));
+                }
+                bytes.extend(get_methods_bytes(methods));
+            }
+            TypeKind::Interface { properties, methods, parents } => {
+                bytes.push(3);
+                bytes.extend(get_properties_bytes(properties));
+                bytes.extend(get_methods_bytes(methods));
+                for parent in parents {
+                    bytes.extend(get_typeref_bytes(parent));
+                }
+            }
+            TypeKind::Struct { constructors, fields, static_fields } => {
+                bytes.push(4);
+                bytes.extend(get_constructors_bytes(constructors));
+                bytes.extend(get_fields_bytes(fields));
+                bytes.extend(get_fields_bytes(static_fields));
+            }
+            TypeKind::TypeAlias(alias) => {
+                bytes.push(5);
+                bytes.extend(get_typeref_bytes(alias));
+            }
+        }
+    }
+    xxh3_64_with_secret(&bytes, &ABI_SECRET)
+}
+
 impl PartialEq for TypeRef {
     fn eq(&self, other: &Self) -> bool {
         get_typeref_bytes(self) == get_typeref_bytes(other)
@@ -351,12 +492,150 @@ pub struct Attribute(pub TypeRef, pub Vec<Value>);
 #[derive(Debug, Clone)]
 pub struct Generics(pub Vec<String>, pub Vec<(String, TypeRef)>);
 
+/// Semantic version of package
+#[repr(C)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
+#[derive(Ord, PartialOrd, Hash, Eq, PartialEq, Debug, Copy, Clone, Default)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self { major, minor, patch }
+    }
+
+    /// Parse version in `major.minor.patch` format. Pre-release and build suffixes are ignored.
+    pub fn parse(version: &str) -> Option<Self> {
+        let version = version.split(['-', '+']).next()?;
+        let mut parts = version.split('.').map(|part| part.parse::<u32>());
+        let major = parts.next()?.ok()?;
+        let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+        let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self::new(major, minor, patch))
+    }
+
+    /// Check that package with this version can be used instead of `required` one by semver rules
+    pub fn is_compatible_with(&self, required: &Version) -> bool {
+        if self.major != required.major {
+            return false;
+        }
+        if self.major == 0 && self.minor != required.minor {
+            return false;
+        }
+        self >= required
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Range of versions: from `min` (inclusive) to `max` (exclusive)
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
+#[derive(Hash, Eq, PartialEq, Debug, Copy, Clone, Default)]
+pub struct VersionRange {
+    pub min: Version,
+    pub max: Option<Version>
+}
+
+impl VersionRange {
+    pub const fn new(min: Version, max: Option<Version>) -> Self {
+        Self { min, max }
+    }
+
+    /// Range of versions compatible with given one by semver rules, like `^1.2.0` in Cargo
+    pub const fn caret(version: Version) -> Self {
+        let max = if version.major > 0 {
+            Version::new(version.major + 1, 0, 0)
+        } else {
+            Version::new(0, version.minor + 1, 0)
+        };
+        Self::new(version, Some(max))
+    }
+
+    /// Parse range in one of formats: `*`, `1.2.0` or `^1.2.0` (caret range), `>=1.2.0` and `>=1.2.0, <2.0.0`
+    pub fn parse(range: &str) -> Option<Self> {
+        let range = range.trim();
+        if range == "*" {
+            return Some(Self::default());
+        }
+        if let Some(min) = range.strip_prefix(">=") {
+            return match min.split_once(',') {
+                Some((min, max)) => Some(Self::new(
+                    Version::parse(min.trim())?,
+                    Some(Version::parse(max.trim().strip_prefix('<')?.trim())?)
+                )),
+                None => Some(Self::new(Version::parse(min.trim())?, None))
+            };
+        }
+        Version::parse(range.strip_prefix('^').unwrap_or(range)).map(Self::caret)
+    }
+
+    pub fn contains(&self, version: &Version) -> bool {
+        version >= &self.min && self.max.is_none_or(|max| version < &max)
+    }
+}
+
+impl Display for VersionRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.max {
+            Some(max) => write!(f, ">={}, <{}", self.min, max),
+            None => write!(f, ">={}", self.min)
+        }
+    }
+}
+
+/// Package which types are referenced by another package
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    pub name: String,
+    pub id: u64,
+    pub version: VersionRange
+}
+
+impl Dependency {
+    pub fn new(name: &str, version: VersionRange) -> Self {
+        Self {
+            name: name.to_string(),
+            id: generate_package_id(name),
+            version
+        }
+    }
+}
+
+impl From<&Package> for Dependency {
+    /// Dependency on versions compatible with version of given package
+    fn from(value: &Package) -> Self {
+        Self {
+            name: value.name.clone(),
+            id: value.id,
+            version: VersionRange::caret(value.version)
+        }
+    }
+}
+
 #[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone)]
 pub struct Package {
     pub attrs: Vec<Attribute>,
     pub name: String,
     pub id: u64,
+    #[cfg_attr(feature="serde", serde(default))]
+    pub version: Version,
+    /// Fingerprint of binary interface of package made by [generate_abi_fingerprint]
+    #[cfg_attr(feature="serde", serde(default))]
+    pub abi: u64,
+    #[cfg_attr(feature="serde", serde(default))]
+    pub dependencies: Vec<Dependency>,
     pub types: Vec<Type>,
     pub naming: NamingConventions
 }
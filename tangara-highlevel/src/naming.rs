@@ -43,6 +43,22 @@ impl Naming {
         }
     }
 
+    pub fn get_prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn get_suffix(&self) -> &str {
+        &self.suffix
+    }
+
+    pub fn get_sep(&self) -> &str {
+        &self.sep
+    }
+
+    pub fn get_case(&self) -> NamingCase {
+        self.case
+    }
+
     /// PascalCase
     pub fn pascal_case() -> Self {
         Self {
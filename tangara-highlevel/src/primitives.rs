@@ -0,0 +1,160 @@
+//! Canonical set of primitive types and their native representation in every supported language.
+//!
+//! Packages reference primitives by different names: `int` (C# style), `i32` (Rust style),
+//! `Int` or `Tangara.Std.Int` (types of `Tangara.Std` package). All of them are mapped
+//! to the same [Primitive], so generators produce the same native type for the same metadata.
+
+use crate::*;
+
+#[derive(Ord, PartialOrd, Hash, Eq, PartialEq, Debug, Copy, Clone)]
+pub enum Primitive {
+    Void,
+    Bool,
+    Byte,
+    SByte,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Long,
+    ULong,
+    Float,
+    Double,
+    /// Unicode scalar value
+    Char,
+    /// Unsigned integer of pointer size
+    USize,
+    /// Signed integer of pointer size
+    ISize,
+    /// Owned UTF-8 string
+    String,
+    /// Borrowed UTF-8 string, in Rust it's used behind reference
+    Str,
+    /// Null-terminated string
+    CString,
+    /// Untyped pointer to object, `Ptr` in Tangara runtime.
+    /// Typed pointers are referenced as generic `Tangara.Std.Ptr<T>`.
+    RawPtr
+}
+
+struct Mapping {
+    primitive: Primitive,
+    /// Name in `Tangara.Std` package
+    name: &'static str,
+    /// Other names which can be used in [TypeRef::Name], the first one is builtin name
+    /// which type in `Tangara.Std` is alias of
+    aliases: &'static [&'static str],
+    rust: &'static str,
+    /// Type in C, strings of Rust are declared by `tangara.h`
+    c: &'static str,
+    csharp: &'static str
+}
+
+const MAPPINGS: &[Mapping] = &[
+    Mapping { primitive: Primitive::Void, name: "Void", aliases: &["void", "()"], rust: "()", c: "void", csharp: "void" },
+    Mapping { primitive: Primitive::Bool, name: "Bool", aliases: &["bool"], rust: "bool", c: "bool", csharp: "bool" },
+    Mapping { primitive: Primitive::Byte, name: "Byte", aliases: &["byte", "u8"], rust: "u8", c: "uint8_t", csharp: "byte" },
+    Mapping { primitive: Primitive::SByte, name: "SByte", aliases: &["sbyte", "i8"], rust: "i8", c: "int8_t", csharp: "sbyte" },
+    Mapping { primitive: Primitive::Short, name: "Short", aliases: &["short", "i16"], rust: "i16", c: "int16_t", csharp: "short" },
+    Mapping { primitive: Primitive::UShort, name: "UShort", aliases: &["ushort", "u16"], rust: "u16", c: "uint16_t", csharp: "ushort" },
+    Mapping { primitive: Primitive::Int, name: "Int", aliases: &["int", "i32"], rust: "i32", c: "int32_t", csharp: "int" },
+    Mapping { primitive: Primitive::UInt, name: "UInt", aliases: &["uint", "u32"], rust: "u32", c: "uint32_t", csharp: "uint" },
+    Mapping { primitive: Primitive::Long, name: "Long", aliases: &["long", "i64"], rust: "i64", c: "int64_t", csharp: "long" },
+    Mapping { primitive: Primitive::ULong, name: "ULong", aliases: &["ulong", "u64"], rust: "u64", c: "uint64_t", csharp: "ulong" },
+    Mapping { primitive: Primitive::Float, name: "Float", aliases: &["float", "f32"], rust: "f32", c: "float", csharp: "float" },
+    Mapping { primitive: Primitive::Double, name: "Double", aliases: &["double", "f64"], rust: "f64", c: "double", csharp: "double" },
+    Mapping { primitive: Primitive::Char, name: "Char", aliases: &["char"], rust: "char", c: "uint32_t", csharp: "uint" },
+    Mapping { primitive: Primitive::USize, name: "USize", aliases: &["usize", "nuint"], rust: "usize", c: "size_t", csharp: "nuint" },
+    Mapping { primitive: Primitive::ISize, name: "ISize", aliases: &["isize", "nint"], rust: "isize", c: "intptr_t", csharp: "nint" },
+    Mapping { primitive: Primitive::String, name: "String", aliases: &["string"], rust: "String", c: "TgRustString", csharp: "string" },
+    Mapping { primitive: Primitive::Str, name: "Str", aliases: &["str"], rust: "str", c: "TgStr", csharp: "string" },
+    Mapping { primitive: Primitive::CString, name: "CString", aliases: &["cstring"], rust: "std::ffi::CString", c: "char*", csharp: "string" },
+    Mapping { primitive: Primitive::RawPtr, name: "RawPtr", aliases: &["rawptr"], rust: "*mut u8", c: "void*", csharp: "IntPtr" },
+];
+
+impl Primitive {
+    pub const ALL: [Primitive; 19] = [
+        Primitive::Void, Primitive::Bool, Primitive::Byte, Primitive::SByte, Primitive::Short, Primitive::UShort,
+        Primitive::Int, Primitive::UInt, Primitive::Long, Primitive::ULong, Primitive::Float, Primitive::Double,
+        Primitive::Char, Primitive::USize, Primitive::ISize, Primitive::String, Primitive::Str, Primitive::CString,
+        Primitive::RawPtr
+    ];
+
+    fn mapping(self) -> &'static Mapping {
+        MAPPINGS.iter().find(|mapping| mapping.primitive == self).unwrap()
+    }
+
+    /// Find primitive by any of its names, full name in `Tangara.Std` is accepted too
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("Tangara.Std.").unwrap_or(name);
+        MAPPINGS.iter()
+            .find(|mapping| mapping.name == name || mapping.aliases.contains(&name))
+            .map(|mapping| mapping.primitive)
+    }
+
+    /// Find primitive which type reference points to: by name or by id of type from `Tangara.Std`
+    pub fn from_typeref(type_ref: &TypeRef) -> Option<Self> {
+        match type_ref {
+            TypeRef::Name(name) => Self::from_name(name),
+            TypeRef::Id(id) => Self::ALL.into_iter().find(|primitive| primitive.type_id() == *id),
+            _ => None
+        }
+    }
+
+    /// Name of type in `Tangara.Std` package
+    pub fn name(self) -> &'static str {
+        self.mapping().name
+    }
+
+    /// Builtin name which type in `Tangara.Std` is alias of
+    pub fn builtin_name(self) -> &'static str {
+        self.mapping().aliases[0]
+    }
+
+    pub fn full_name(self) -> String {
+        format!("Tangara.Std.{}", self.name())
+    }
+
+    pub fn type_id(self) -> u64 {
+        generate_type_id(&self.full_name())
+    }
+
+    pub fn rust_type(self) -> &'static str {
+        self.mapping().rust
+    }
+
+    pub fn c_type(self) -> &'static str {
+        self.mapping().c
+    }
+
+    pub fn csharp_type(self) -> &'static str {
+        self.mapping().csharp
+    }
+
+    /// Check that value can be stored in variable of this type.
+    /// Returns `None` if primitive has no corresponding [Value] variant.
+    pub fn matches_value(self, value: &Value) -> Option<bool> {
+        let matches = match self {
+            Primitive::Bool => matches!(value, Value::Bool(_)),
+            Primitive::Byte => matches!(value, Value::Byte(_)),
+            Primitive::SByte => matches!(value, Value::SByte(_)),
+            Primitive::Short => matches!(value, Value::Short(_)),
+            Primitive::UShort => matches!(value, Value::UShort(_)),
+            Primitive::Int => matches!(value, Value::Int(_)),
+            Primitive::UInt => matches!(value, Value::UInt(_)),
+            Primitive::Long => matches!(value, Value::Long(_)),
+            Primitive::ULong => matches!(value, Value::ULong(_)),
+            Primitive::Float => matches!(value, Value::Float(_)),
+            Primitive::Double => matches!(value, Value::Double(_)),
+            Primitive::String | Primitive::Str | Primitive::CString => matches!(value, Value::String(_)),
+            Primitive::Void | Primitive::Char | Primitive::USize | Primitive::ISize | Primitive::RawPtr => return None
+        };
+        Some(matches)
+    }
+}
+
+impl Display for Primitive {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
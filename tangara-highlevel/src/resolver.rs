@@ -0,0 +1,319 @@
+//! Resolution of [TypeRef::Name] references against package and its dependencies.
+//!
+//! Generators often emit bare names like `MyStruct` which give wrong ids when hashed
+//! by [get_typeref_bytes]. Resolver rewrites them into full names (`namespace.Name`, the same
+//! string which id of type is generated from) or into ids, and recomputes ids of methods after it.
+
+use crate::validation::{is_builtin_type, Diagnostic, Severity};
+use crate::*;
+
+#[derive(Hash, Eq, PartialEq, Debug, Copy, Clone)]
+pub enum ResolveMode {
+    /// Rewrite names into `namespace.Name`
+    FullName,
+    /// Rewrite names into [TypeRef::Id]
+    Id
+}
+
+#[derive(Debug, Clone)]
+struct ScopeType {
+    /// Full name with `.` as namespace divider, used for matching
+    path: String,
+    /// Full name used for generating id of type
+    full_name: String,
+    name: String,
+    id: u64
+}
+
+impl ScopeType {
+    fn new(t: &Type) -> Self {
+        Self {
+            path: normalize_path(&format!("{}.{}", t.namespace, t.name)),
+            full_name: format!("{}.{}", t.namespace, t.name),
+            name: t.name.clone(),
+            id: t.id
+        }
+    }
+}
+
+fn normalize_path(path: &str) -> String {
+    path.replace("::", ".").trim_start_matches('.').to_string()
+}
+
+/// Resolves names of types in package. Name is searched in this order:
+/// generic parameters, `Self`, namespace of type which uses it, imports, builtin types
+/// and at last in all types of package and dependencies by short name.
+pub struct Resolver {
+    deps: Vec<ScopeType>,
+    imports: Vec<String>,
+    mode: ResolveMode
+}
+
+enum Lookup<'a> {
+    Found(&'a ScopeType),
+    /// Name must stay as is: builtin type or generic parameter
+    Keep,
+    NotFound,
+    Ambiguous(usize)
+}
+
+struct Scope<'a> {
+    types: &'a [ScopeType],
+    imports: &'a [String],
+    namespace: String,
+    self_type: Option<&'a ScopeType>,
+    generics: Vec<String>
+}
+
+impl<'a> Scope<'a> {
+    fn find_path(&self, path: &str) -> Option<&'a ScopeType> {
+        self.types.iter().find(|t| t.path == path)
+    }
+
+    fn lookup(&self, name: &str) -> Lookup<'a> {
+        if self.generics.iter().any(|g| g == name) {
+            return Lookup::Keep;
+        }
+        if name == "Self" {
+            return self.self_type.map_or(Lookup::Keep, Lookup::Found);
+        }
+        let path = normalize_path(name);
+        let (first, rest) = match path.split_once('.') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (path.as_str(), None)
+        };
+        // absolute path and path relative to current namespace
+        if rest.is_some() {
+            if let Some(t) = self.find_path(&path) {
+                return Lookup::Found(t);
+            }
+        }
+        let relative = if self.namespace.is_empty() {
+            path.clone()
+        } else {
+            format!("{}.{}", self.namespace, path)
+        };
+        if let Some(t) = self.find_path(&relative) {
+            return Lookup::Found(t);
+        }
+        for import in self.imports {
+            let found = if let Some(glob) = import.strip_suffix(".*") {
+                self.find_path(&format!("{glob}.{path}"))
+            } else if import.rsplit('.').next() == Some(first) {
+                match rest {
+                    Some(rest) => self.find_path(&format!("{import}.{rest}")),
+                    None => self.find_path(import)
+                }
+            } else {
+                None
+            };
+            if let Some(t) = found {
+                return Lookup::Found(t);
+            }
+        }
+        let short_name = path.rsplit('.').next().unwrap_or(&path);
+        if is_builtin_type(short_name) {
+            return Lookup::Keep;
+        }
+        if rest.is_some() {
+            return Lookup::NotFound;
+        }
+        let found = self.types.iter().filter(|t| t.name == path).collect::<Vec<&ScopeType>>();
+        match found.len() {
+            0 => Lookup::NotFound,
+            1 => Lookup::Found(found[0]),
+            count => Lookup::Ambiguous(count)
+        }
+    }
+}
+
+impl Resolver {
+    pub fn new(deps: &[Package]) -> Self {
+        Self {
+            deps: deps.iter().flat_map(|dep| dep.types.iter().map(ScopeType::new)).collect(),
+            imports: vec![],
+            mode: ResolveMode::FullName
+        }
+    }
+
+    /// Add `use` path visible for every type: full name of type or namespace ending with `*`.
+    /// Both `::` and `.` can be used as divider.
+    pub fn add_import(&mut self, path: &str) -> &mut Self {
+        self.imports.push(normalize_path(path));
+        self
+    }
+
+    pub fn set_mode(&mut self, mode: ResolveMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Rewrite type references of package, recompute ids of methods and ABI fingerprint.
+    /// Returns diagnostics for references which can't be resolved, they are left unchanged.
+    pub fn resolve(&self, package: &mut Package) -> Vec<Diagnostic> {
+        let types = package.types.iter().map(ScopeType::new)
+            .chain(self.deps.iter().cloned())
+            .collect::<Vec<ScopeType>>();
+        let mut diagnostics = vec![];
+        for (t, scope_type) in package.types.iter_mut().zip(&types) {
+            let scope = Scope {
+                types: &types,
+                imports: &self.imports,
+                namespace: normalize_path(&t.namespace),
+                self_type: Some(scope_type),
+                generics: t.generics.0.clone()
+            };
+            let mut resolver = TypeResolver {
+                mode: self.mode,
+                scope,
+                location: format!("{}.{}", t.namespace, t.name),
+                diagnostics: &mut diagnostics
+            };
+            resolver.resolve_type(t);
+        }
+        package.abi = generate_abi_fingerprint(&package.types);
+        diagnostics
+    }
+}
+
+struct TypeResolver<'a, 'd> {
+    mode: ResolveMode,
+    scope: Scope<'a>,
+    location: String,
+    diagnostics: &'d mut Vec<Diagnostic>
+}
+
+impl<'a, 'd> TypeResolver<'a, 'd> {
+    fn report(&mut self, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            location: self.location.clone(),
+            message
+        });
+    }
+
+    fn type_ref(&mut self, type_ref: &mut TypeRef) {
+        match type_ref {
+            TypeRef::Name(name) => match self.scope.lookup(name) {
+                Lookup::Found(t) => {
+                    *type_ref = match self.mode {
+                        ResolveMode::FullName => TypeRef::Name(t.full_name.clone()),
+                        ResolveMode::Id => TypeRef::Id(t.id)
+                    };
+                }
+                Lookup::Keep => {}
+                Lookup::NotFound => {
+                    let message = format!("Type {name} is not found");
+                    self.report(message);
+                }
+                Lookup::Ambiguous(count) => {
+                    let message = format!("Type name {name} is ambiguous, it matches {count} types");
+                    self.report(message);
+                }
+            },
+            TypeRef::Id(id) => {
+                let id = *id;
+                if !self.scope.types.iter().any(|t| t.id == id) {
+                    self.report(format!("Type with id {id:#x} is not found"));
+                }
+            }
+            TypeRef::Generic(base, args) => {
+                self.type_ref(base);
+                self.type_refs(args);
+            }
+            TypeRef::Tuple(types) => self.type_refs(types),
+            TypeRef::Fn(return_type, args) => {
+                if let Some(return_type) = return_type {
+                    self.type_ref(return_type);
+                }
+                self.type_refs(args);
+            }
+        }
+    }
+
+    fn type_refs(&mut self, type_refs: &mut [TypeRef]) {
+        for type_ref in type_refs {
+            self.type_ref(type_ref);
+        }
+    }
+
+    fn generics(&mut self, generics: &mut Generics) {
+        for (_, bound) in &mut generics.1 {
+            self.type_ref(bound);
+        }
+    }
+
+    fn args(&mut self, args: &mut [Argument]) {
+        for arg in args {
+            self.type_ref(&mut arg.1);
+        }
+    }
+
+    fn fields(&mut self, fields: &mut [Field]) {
+        for field in fields {
+            self.type_ref(&mut field.field_type);
+        }
+    }
+
+    fn properties(&mut self, properties: &mut [Property]) {
+        for prop in properties {
+            self.type_ref(&mut prop.prop_type);
+        }
+    }
+
+    fn constructors(&mut self, constructors: &mut [Constructor]) {
+        for ctor in constructors {
+            self.args(&mut ctor.args);
+        }
+    }
+
+    fn methods(&mut self, methods: &mut [Method]) {
+        let type_generics = self.scope.generics.len();
+        for method in methods {
+            self.scope.generics.extend(method.generics.0.iter().cloned());
+            self.generics(&mut method.generics);
+            self.args(&mut method.args);
+            if let Some(return_type) = &mut method.return_type {
+                self.type_ref(return_type);
+            }
+            self.scope.generics.truncate(type_generics);
+            // id depends on types of arguments, so it changes after resolving them
+            method.id = generate_method_id(&method.name, &method.args);
+        }
+    }
+
+    fn resolve_type(&mut self, t: &mut Type) {
+        self.generics(&mut t.generics);
+        match &mut t.kind {
+            TypeKind::Class {
+                constructors, properties, fields, static_properties, static_fields, methods, parents, ..
+            } => {
+                self.constructors(constructors);
+                self.properties(properties);
+                self.fields(fields);
+                self.properties(static_properties);
+                self.fields(static_fields);
+                self.methods(methods);
+                self.type_refs(parents);
+            }
+            TypeKind::Enum { .. } => {}
+            TypeKind::EnumClass { variants, methods } => {
+                for variant in variants {
+                    self.fields(&mut variant.fields);
+                }
+                self.methods(methods);
+            }
+            TypeKind::Interface { properties, methods, parents } => {
+                self.properties(properties);
+                self.methods(methods);
+                self.type_refs(parents);
+            }
+            TypeKind::Struct { constructors, fields, static_fields } => {
+                self.constructors(constructors);
+                self.fields(fields);
+                self.fields(static_fields);
+            }
+            TypeKind::TypeAlias(alias) => self.type_ref(alias)
+        }
+    }
+}
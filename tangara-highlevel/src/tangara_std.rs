@@ -0,0 +1,181 @@
+//! Built-in `Tangara.Std` package which every other package implicitly can reference.
+//!
+//! It contains aliases of every [Primitive], generic containers which generators
+//! map language constructs to (`Array`, `Ptr`, `Option`, `Result`), base class for attributes
+//! and attributes used by generators (namespace `Tangara.Metadata`).
+
+use once_cell::sync::Lazy;
+use crate::builder::*;
+use crate::primitives::Primitive;
+use crate::*;
+
+pub static TANGARA_STD: Lazy<TangaraStd> = Lazy::new(TangaraStd::new);
+
+pub struct TangaraStd {
+    tangara_std: Package,
+    attribute: Type,
+    array: Type,
+    ptr: Type,
+    option: Type,
+    result: Type,
+    array_size_attribute: Type,
+    lang_attribute: Type,
+    flags_attribute: Type,
+}
+
+/// Reference by full name, so generators which can't resolve ids still can use it,
+/// while its bytes are the same as of reference by id
+fn full_name(t: &Type) -> TypeRef {
+    TypeRef::Name(format!("{}.{}", t.namespace, t.name))
+}
+
+impl TangaraStd {
+    fn new() -> Self {
+        let tangara_std = PackageBuilder::new("Tangara.Std", NamingConventions::csharp());
+        tangara_std.borrow_mut().set_namespace("Tangara.Std");
+
+        for primitive in Primitive::ALL {
+            create_alias(tangara_std.clone(), primitive.name(), TypeRef::from(primitive.builtin_name())).build();
+        }
+
+        let mut attribute = create_class(tangara_std.clone(), "Attribute");
+        attribute.open();
+        let attribute = attribute.build();
+
+        let mut array = create_class(tangara_std.clone(), "Array");
+        array.generic("T".to_string())
+            .add_property(TypeRef::from("ULong"), "Length").build();
+        let array = array.build();
+
+        let mut ptr = create_struct(tangara_std.clone(), "Ptr");
+        ptr.generic("T".to_string());
+        let ptr = ptr.build();
+
+        let mut option = create_enum_class(tangara_std.clone(), "Option");
+        option.generic("T".to_string());
+        option.variant("None").build();
+        let mut some = option.variant("Some");
+        some.add_field(TypeRef::from("T"), "Value").build();
+        some.build();
+        let option = option.build();
+
+        let mut result = create_enum_class(tangara_std.clone(), "Result");
+        result.generics(vec!["T".to_string(), "E".to_string()]);
+        let mut ok = result.variant("Ok");
+        ok.add_field(TypeRef::from("T"), "Value").build();
+        ok.build();
+        let mut err = result.variant("Err");
+        err.add_field(TypeRef::from("E"), "Error").build();
+        err.build();
+        let result = result.build();
+
+        tangara_std.borrow_mut().set_namespace("Tangara.Metadata");
+        let mut array_size_attribute = create_class(tangara_std.clone(), "ArraySize");
+        array_size_attribute.inherits(full_name(&attribute))
+            .add_property(TypeRef::from("ULong"), "Size")
+            .setter_visibility(Visibility::Public).build();
+        let array_size_attribute = array_size_attribute.build();
+        let mut lang_attribute = create_class(tangara_std.clone(), "Lang");
+        lang_attribute.inherits(full_name(&attribute))
+            .add_property(TypeRef::from("String"), "Name")
+            .setter_visibility(Visibility::Public).build();
+        let lang_attribute = lang_attribute.build();
+        let mut flags_attribute = create_class(tangara_std.clone(), "Flags");
+        flags_attribute.inherits(full_name(&attribute));
+        let flags_attribute = flags_attribute.build();
+
+        let tangara_std = tangara_std.borrow().build();
+
+        Self {
+            tangara_std,
+            attribute,
+            array,
+            ptr,
+            option,
+            result,
+            array_size_attribute,
+            lang_attribute,
+            flags_attribute,
+        }
+    }
+
+    pub fn get_package(&self) -> Package {
+        self.tangara_std.clone()
+    }
+
+    /// Base class which all attribute classes should inherit
+    pub fn attribute(&self) -> TypeRef {
+        full_name(&self.attribute)
+    }
+
+    pub fn array(&self, element: TypeRef) -> TypeRef {
+        TypeRef::Generic(Box::new(full_name(&self.array)), vec![element])
+    }
+
+    /// Check if type reference is `Array` without generic arguments
+    pub fn is_array(&self, type_ref: &TypeRef) -> bool {
+        get_typeref_bytes(type_ref) == get_typeref_bytes(&full_name(&self.array))
+    }
+
+    pub fn ptr(&self, pointee: TypeRef) -> TypeRef {
+        TypeRef::Generic(Box::new(full_name(&self.ptr)), vec![pointee])
+    }
+
+    /// Check if type reference is `Ptr` without generic arguments
+    pub fn is_ptr(&self, type_ref: &TypeRef) -> bool {
+        get_typeref_bytes(type_ref) == get_typeref_bytes(&full_name(&self.ptr))
+    }
+
+    pub fn option(&self, value: TypeRef) -> TypeRef {
+        TypeRef::Generic(Box::new(full_name(&self.option)), vec![value])
+    }
+
+    /// Check if type reference is `Option` without generic arguments
+    pub fn is_option(&self, type_ref: &TypeRef) -> bool {
+        get_typeref_bytes(type_ref) == get_typeref_bytes(&full_name(&self.option))
+    }
+
+    pub fn result(&self, value: TypeRef, error: TypeRef) -> TypeRef {
+        TypeRef::Generic(Box::new(full_name(&self.result)), vec![value, error])
+    }
+
+    /// Check if type reference is `Result` without generic arguments
+    pub fn is_result(&self, type_ref: &TypeRef) -> bool {
+        get_typeref_bytes(type_ref) == get_typeref_bytes(&full_name(&self.result))
+    }
+
+    /// Reference to primitive type by its name, like `Int` or `String`.
+    /// Returns `None` if there is no such primitive type.
+    pub fn primitive(&self, name: &str) -> Option<TypeRef> {
+        Primitive::ALL.into_iter()
+            .find(|primitive| primitive.name() == name)
+            .map(|primitive| TypeRef::Name(primitive.full_name()))
+    }
+
+    pub fn array_size_attribute(&self, size: Value) -> Attribute {
+        Attribute(TypeRef::from(&self.array_size_attribute), vec![size])
+    }
+
+    /// Collect sizes of all `ArraySize` attributes, from outer array to inner one
+    pub fn get_array_sizes(&self, attrs: &[Attribute]) -> Vec<Value> {
+        let array_size_data = get_typeref_bytes(&TypeRef::from(&self.array_size_attribute));
+        attrs.iter()
+            .filter(|attr| get_typeref_bytes(&attr.0) == array_size_data)
+            .filter_map(|attr| attr.1.first().cloned())
+            .collect()
+    }
+
+    pub fn lang_attribute(&self, lang: &str) -> Attribute {
+        Attribute(TypeRef::from(&self.lang_attribute), vec![Value::from(lang)])
+    }
+
+    pub fn flags_attribute(&self) -> Attribute {
+        Attribute(TypeRef::from(&self.flags_attribute), vec![])
+    }
+
+    /// Check if attributes contain `Flags` attribute which marks enum as bitflags
+    pub fn is_flags(&self, attrs: &[Attribute]) -> bool {
+        let flags_data = get_typeref_bytes(&TypeRef::from(&self.flags_attribute));
+        attrs.iter().any(|attr| get_typeref_bytes(&attr.0) == flags_data)
+    }
+}
@@ -0,0 +1,188 @@
+//! Set of packages with all their dependencies, so types can be referenced across packages.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use crate::resolver::{ResolveMode, Resolver};
+use crate::validation::{validate, Diagnostic};
+use crate::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DependencyError {
+    /// Package with the same id was added twice
+    DuplicatePackage(String),
+    /// Dependency of package isn't in universe
+    MissingDependency { package: String, dependency: String },
+    /// Dependency is in universe but its version isn't in required range
+    IncompatibleVersion { package: String, dependency: String, required: VersionRange, found: Version },
+    /// Packages depend on each other, names are listed in order of dependency with the first package repeated at the end
+    Cycle(Vec<String>)
+}
+
+impl Display for DependencyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DependencyError::DuplicatePackage(name) => write!(f, "Package {name} is added twice"),
+            DependencyError::MissingDependency { package, dependency } => {
+                write!(f, "Package {package} depends on {dependency} which is not found")
+            }
+            DependencyError::IncompatibleVersion { package, dependency, required, found } => {
+                write!(f, "Package {package} requires {dependency} {required} but found version {found}")
+            }
+            DependencyError::Cycle(names) => write!(f, "Cyclic dependency: {}", names.join(" -> "))
+        }
+    }
+}
+
+impl std::error::Error for DependencyError {}
+
+/// Consistent set of packages: every dependency is present, has required version and there are no cycles
+pub struct Universe {
+    /// Packages sorted so dependencies go before packages which depend on them
+    packages: Vec<Package>
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Mark {
+    Visiting,
+    Visited
+}
+
+struct Sorter<'a> {
+    packages: &'a HashMap<u64, &'a Package>,
+    marks: HashMap<u64, Mark>,
+    stack: Vec<u64>,
+    order: Vec<u64>,
+    errors: Vec<DependencyError>
+}
+
+impl<'a> Sorter<'a> {
+    fn visit(&mut self, id: u64) {
+        match self.marks.get(&id) {
+            Some(Mark::Visited) => return,
+            Some(Mark::Visiting) => {
+                let start = self.stack.iter().position(|stack_id| *stack_id == id).unwrap_or(0);
+                let mut names = self.stack[start..].iter()
+                    .map(|id| self.packages[id].name.clone())
+                    .collect::<Vec<String>>();
+                names.push(self.packages[&id].name.clone());
+                self.errors.push(DependencyError::Cycle(names));
+                return;
+            }
+            None => {}
+        }
+        self.marks.insert(id, Mark::Visiting);
+        self.stack.push(id);
+        let package = self.packages[&id];
+        for dependency in &package.dependencies {
+            match self.packages.get(&dependency.id) {
+                Some(found) => {
+                    if !dependency.version.contains(&found.version) {
+                        self.errors.push(DependencyError::IncompatibleVersion {
+                            package: package.name.clone(),
+                            dependency: dependency.name.clone(),
+                            required: dependency.version,
+                            found: found.version
+                        });
+                    }
+                    self.visit(dependency.id);
+                }
+                None => self.errors.push(DependencyError::MissingDependency {
+                    package: package.name.clone(),
+                    dependency: dependency.name.clone()
+                })
+            }
+        }
+        self.stack.pop();
+        self.marks.insert(id, Mark::Visited);
+        self.order.push(id);
+    }
+}
+
+impl Universe {
+    /// Assemble packages into universe or return all problems with their dependencies
+    pub fn load(packages: Vec<Package>) -> Result<Self, Vec<DependencyError>> {
+        let mut errors = vec![];
+        let mut by_id = HashMap::with_capacity(packages.len());
+        for package in &packages {
+            if by_id.insert(package.id, package).is_some() {
+                errors.push(DependencyError::DuplicatePackage(package.name.clone()));
+            }
+        }
+        let mut sorter = Sorter {
+            packages: &by_id,
+            marks: HashMap::new(),
+            stack: vec![],
+            order: vec![],
+            errors
+        };
+        for package in &packages {
+            sorter.visit(package.id);
+        }
+        if !sorter.errors.is_empty() {
+            return Err(sorter.errors);
+        }
+        let order = sorter.order;
+        let mut packages = packages.into_iter().map(|package| (package.id, package)).collect::<HashMap<u64, Package>>();
+        Ok(Self {
+            packages: order.into_iter().filter_map(|id| packages.remove(&id)).collect()
+        })
+    }
+
+    /// Get packages sorted so dependencies go before packages which depend on them
+    pub fn get_packages(&self) -> &[Package] {
+        &self.packages
+    }
+
+    pub fn get_package(&self, id: u64) -> Option<&Package> {
+        self.packages.iter().find(|package| package.id == id)
+    }
+
+    pub fn find_package(&self, name: &str) -> Option<&Package> {
+        self.packages.iter().find(|package| package.name == name)
+    }
+
+    /// Get direct and transitive dependencies of package in the same order as [get_packages](Universe::get_packages)
+    pub fn get_dependencies(&self, id: u64) -> Vec<&Package> {
+        let mut required = vec![id];
+        let mut i = 0;
+        while i < required.len() {
+            if let Some(package) = self.get_package(required[i]) {
+                for dependency in &package.dependencies {
+                    if !required.contains(&dependency.id) {
+                        required.push(dependency.id);
+                    }
+                }
+            }
+            i += 1;
+        }
+        self.packages.iter()
+            .filter(|package| package.id != id && required.contains(&package.id))
+            .collect()
+    }
+
+    fn get_owned_dependencies(&self, id: u64) -> Vec<Package> {
+        self.get_dependencies(id).into_iter().cloned().collect()
+    }
+
+    /// Validate every package against its dependencies
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        self.packages.iter()
+            .flat_map(|package| validate(package, &self.get_owned_dependencies(package.id)))
+            .collect()
+    }
+
+    /// Resolve type references of every package against its dependencies
+    pub fn resolve(&mut self, mode: ResolveMode) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        for i in 0..self.packages.len() {
+            let mut resolver = Resolver::new(&self.get_owned_dependencies(self.packages[i].id));
+            resolver.set_mode(mode);
+            diagnostics.extend(resolver.resolve(&mut self.packages[i]));
+        }
+        diagnostics
+    }
+
+    pub fn into_packages(self) -> Vec<Package> {
+        self.packages
+    }
+}
@@ -0,0 +1,381 @@
+//! Consistency checks of [Package] which can be run by build scripts before generation of bindings.
+
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use crate::primitives::Primitive;
+use crate::*;
+
+/// Names of primitive and standard types which are always known
+pub const BUILTIN_TYPES: &[&str] = &[
+    "()", "bool", "char", "str", "String", "Self",
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32", "f64",
+    "Box", "Option", "Result", "Vec", "HashMap", "HashSet", "Rc", "Arc", "RefCell", "Cell",
+    "void", "byte", "sbyte", "short", "ushort", "int", "uint", "long", "ulong", "float", "double",
+    "string", "object"
+];
+
+/// Check that type name is always known: it's one of [BUILTIN_TYPES] or builtin name of [Primitive]
+pub fn is_builtin_type(name: &str) -> bool {
+    BUILTIN_TYPES.contains(&name) || Primitive::ALL.into_iter().any(|primitive| primitive.builtin_name() == name)
+}
+
+#[derive(Ord, PartialOrd, Hash, Eq, PartialEq, Debug, Copy, Clone)]
+pub enum Severity {
+    /// Package can be used but something in it looks like a mistake
+    Warning,
+    /// Package is inconsistent and bindings generated from it will be broken
+    Error
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Path to checked item, like `Namespace.Type.member`
+    pub location: String,
+    pub message: String
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error"
+        };
+        write!(f, "{severity}: {}: {}", self.location, self.message)
+    }
+}
+
+/// Check package for consistency. Types of `deps` can be referenced by package but aren't checked.
+pub fn validate(package: &Package, deps: &[Package]) -> Vec<Diagnostic> {
+    let mut validator = Validator {
+        package,
+        types: package.types.iter().chain(deps.iter().flat_map(|dep| dep.types.iter())).collect(),
+        diagnostics: vec![]
+    };
+    validator.check_ids();
+    for t in &package.types {
+        validator.check_type(t);
+    }
+    validator.diagnostics
+}
+
+struct Validator<'a> {
+    package: &'a Package,
+    /// Types of package and its dependencies
+    types: Vec<&'a Type>,
+    diagnostics: Vec<Diagnostic>
+}
+
+fn get_location(t: &Type, member: Option<&str>) -> String {
+    let mut location = if t.namespace.is_empty() {
+        t.name.clone()
+    } else {
+        format!("{}.{}", t.namespace, t.name)
+    };
+    if let Some(member) = member {
+        location.push('.');
+        location.push_str(member);
+    }
+    location
+}
+
+impl<'a> Validator<'a> {
+    fn report(&mut self, severity: Severity, location: String, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            location,
+            message
+        });
+    }
+
+    fn find_by_name(&self, name: &str) -> Vec<&'a Type> {
+        let divider = &self.package.naming.namespace_divider;
+        self.types.iter().copied().filter(|t| {
+            t.name == name || name.strip_prefix(t.namespace.as_str())
+                .and_then(|name| name.strip_prefix(divider.as_str()).or(name.strip_prefix('.')))
+                .is_some_and(|name| name == t.name)
+        }).collect()
+    }
+
+    fn find_by_id(&self, id: u64) -> Option<&'a Type> {
+        self.types.iter().copied().find(|t| t.id == id)
+    }
+
+    /// Find type referenced by name or id, returns `None` for builtin, generic and missing types
+    fn find_type(&self, type_ref: &TypeRef) -> Option<&'a Type> {
+        match type_ref {
+            TypeRef::Name(name) => {
+                let found = self.find_by_name(name);
+                if found.len() == 1 { Some(found[0]) } else { None }
+            }
+            TypeRef::Id(id) => self.find_by_id(*id),
+            _ => None
+        }
+    }
+
+    fn check_ids(&mut self) {
+        let mut ids = HashSet::new();
+        for t in &self.package.types {
+            if !ids.insert(t.id) {
+                self.report(Severity::Error, get_location(t, None), format!("Type id {:#x} is used twice", t.id));
+            }
+        }
+    }
+
+    fn check_type_ref(&mut self, type_ref: &TypeRef, generics: &[&str], location: &str) {
+        match type_ref {
+            TypeRef::Name(name) => {
+                if generics.contains(&name.as_str()) || is_builtin_type(name) {
+                    return;
+                }
+                match self.find_by_name(name).len() {
+                    0 => self.report(Severity::Error, location.to_string(), format!("Type {name} is not found")),
+                    1 => {}
+                    count => self.report(
+                        Severity::Warning,
+                        location.to_string(),
+                        format!("Type name {name} is ambiguous, it matches {count} types")
+                    )
+                }
+            }
+            TypeRef::Id(id) => {
+                if self.find_by_id(*id).is_none() {
+                    self.report(Severity::Error, location.to_string(), format!("Type with id {id:#x} is not found"));
+                }
+            }
+            TypeRef::Generic(base, args) => {
+                self.check_type_ref(base, generics, location);
+                for arg in args {
+                    self.check_type_ref(arg, generics, location);
+                }
+            }
+            TypeRef::Tuple(types) => {
+                for t in types {
+                    self.check_type_ref(t, generics, location);
+                }
+            }
+            TypeRef::Fn(return_type, args) => {
+                if let Some(return_type) = return_type {
+                    self.check_type_ref(return_type, generics, location);
+                }
+                for arg in args {
+                    self.check_type_ref(arg, generics, location);
+                }
+            }
+        }
+    }
+
+    fn check_generics(&mut self, generics: &Generics, scope: &[&str], location: &str) {
+        for (_, bound) in &generics.1 {
+            self.check_type_ref(bound, scope, location);
+        }
+    }
+
+    /// Check that value can be stored in variable of given type.
+    /// Types which can't be checked (like classes) accept any value.
+    fn value_matches(&self, type_ref: &TypeRef, value: &Value) -> bool {
+        match type_ref {
+            TypeRef::Name(name) => match Primitive::from_name(name).and_then(|primitive| primitive.matches_value(value)) {
+                Some(matches) => matches,
+                None => self.alias_matches(type_ref, value)
+            },
+            TypeRef::Generic(base, args) => match (base.as_ref(), value) {
+                (TypeRef::Name(name), Value::Null) if name == "Option" => true,
+                (TypeRef::Name(name), _) if name == "Option" && args.len() == 1 => self.value_matches(&args[0], value),
+                (TypeRef::Name(name), Value::Array(values)) if name == "Vec" && args.len() == 1 => {
+                    values.iter().all(|value| self.value_matches(&args[0], value))
+                }
+                _ => true
+            },
+            TypeRef::Tuple(types) => match value {
+                Value::Tuple(values) => {
+                    types.len() == values.len() &&
+                        types.iter().zip(values).all(|(t, value)| self.value_matches(t, value))
+                }
+                _ => false
+            },
+            _ => self.alias_matches(type_ref, value)
+        }
+    }
+
+    fn alias_matches(&self, type_ref: &TypeRef, value: &Value) -> bool {
+        match self.find_type(type_ref).map(|t| &t.kind) {
+            // cyclic aliases are reported separately, so don't follow them
+            Some(TypeKind::TypeAlias(alias)) if !self.is_cyclic_alias(type_ref) => self.value_matches(alias, value),
+            _ => true
+        }
+    }
+
+    fn check_value(&mut self, type_ref: &TypeRef, value: &Value, location: &str) {
+        if !self.value_matches(type_ref, value) {
+            self.report(
+                Severity::Error,
+                location.to_string(),
+                format!("Default value {value:?} doesn't match type {type_ref:?}")
+            );
+        }
+    }
+
+    fn check_args(&mut self, args: &[Argument], generics: &[&str], location: &str) {
+        for arg in args {
+            let location = format!("{location}({})", arg.2);
+            self.check_type_ref(&arg.1, generics, &location);
+            if let ArgumentKind::DefaultValue(value) = &arg.3 {
+                self.check_value(&arg.1, value, &location);
+            }
+        }
+    }
+
+    fn check_fields(&mut self, t: &Type, fields: &[Field], generics: &[&str], ids: &mut HashSet<u64>) {
+        for field in fields {
+            let location = get_location(t, Some(&field.name));
+            if !ids.insert(field.id) {
+                self.report(Severity::Error, location.clone(), format!("Member id {:#x} is used twice", field.id));
+            }
+            self.check_type_ref(&field.field_type, generics, &location);
+            if let Some(value) = &field.default_value {
+                self.check_value(&field.field_type, value, &location);
+            }
+        }
+    }
+
+    fn check_properties(&mut self, t: &Type, properties: &[Property], generics: &[&str], ids: &mut HashSet<u64>) {
+        for prop in properties {
+            let location = get_location(t, Some(&prop.name));
+            if !ids.insert(prop.id) {
+                self.report(Severity::Error, location.clone(), format!("Member id {:#x} is used twice", prop.id));
+            }
+            self.check_type_ref(&prop.prop_type, generics, &location);
+        }
+    }
+
+    fn check_constructors(&mut self, t: &Type, constructors: &[Constructor], generics: &[&str]) {
+        for (i, ctor) in constructors.iter().enumerate() {
+            self.check_args(&ctor.args, generics, &get_location(t, Some(&format!("ctor{i}"))));
+        }
+    }
+
+    fn check_methods(&mut self, t: &Type, methods: &[Method], generics: &[&str]) {
+        let mut ids = HashSet::new();
+        for method in methods {
+            let location = get_location(t, Some(&method.name));
+            if !ids.insert(method.id) {
+                self.report(
+                    Severity::Error,
+                    location.clone(),
+                    format!("Method id {:#x} is used twice, overloads must have different arguments", method.id)
+                );
+            }
+            let mut scope = generics.to_vec();
+            scope.extend(method.generics.0.iter().map(String::as_str));
+            self.check_generics(&method.generics, &scope, &location);
+            self.check_args(&method.args, &scope, &location);
+            if let Some(return_type) = &method.return_type {
+                self.check_type_ref(return_type, &scope, &location);
+            }
+        }
+    }
+
+    fn check_parents(&mut self, t: &Type, parents: &[TypeRef], generics: &[&str], only_interfaces: bool) {
+        let location = get_location(t, None);
+        for parent in parents {
+            self.check_type_ref(parent, generics, &location);
+            if only_interfaces {
+                let base = match parent {
+                    TypeRef::Generic(base, _) => base.as_ref(),
+                    _ => parent
+                };
+                if let Some(parent_type) = self.find_type(base) {
+                    if !matches!(parent_type.kind, TypeKind::Interface { .. }) {
+                        self.report(
+                            Severity::Error,
+                            location.clone(),
+                            format!("Interface can inherit only interfaces but {} isn't interface", parent_type.name)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check that following alias by alias leads back to the alias
+    fn is_cyclic_alias(&self, type_ref: &TypeRef) -> bool {
+        let Some(start) = self.find_type(type_ref) else { return false };
+        let mut visited = HashSet::new();
+        let mut current = start;
+        while let TypeKind::TypeAlias(alias) = &current.kind {
+            if !visited.insert(current.id) {
+                return false;
+            }
+            match self.find_type(alias) {
+                Some(next) if next.id == start.id => return true,
+                Some(next) => current = next,
+                None => return false
+            }
+        }
+        false
+    }
+
+    fn check_type(&mut self, t: &'a Type) {
+        let generics = t.generics.0.iter().map(String::as_str).collect::<Vec<&str>>();
+        self.check_generics(&t.generics, &generics, &get_location(t, None));
+        let mut member_ids = HashSet::new();
+        match &t.kind {
+            TypeKind::Class {
+                constructors, properties, fields, static_properties, static_fields, methods, parents, ..
+            } => {
+                self.check_constructors(t, constructors, &generics);
+                self.check_properties(t, properties, &generics, &mut member_ids);
+                self.check_fields(t, fields, &generics, &mut member_ids);
+                self.check_properties(t, static_properties, &generics, &mut member_ids);
+                self.check_fields(t, static_fields, &generics, &mut member_ids);
+                self.check_methods(t, methods, &generics);
+                self.check_parents(t, parents, &generics, false);
+            }
+            TypeKind::Enum { variants } => {
+                let mut names = HashSet::new();
+                for (name, _) in variants {
+                    if !names.insert(name) {
+                        self.report(Severity::Error, get_location(t, Some(name)), "Variant is declared twice".to_string());
+                    }
+                }
+            }
+            TypeKind::EnumClass { variants, methods } => {
+                for variant in variants {
+                    if !member_ids.insert(variant.id) {
+                        self.report(
+                            Severity::Error,
+                            get_location(t, Some(&variant.name)),
+                            format!("Member id {:#x} is used twice", variant.id)
+                        );
+                    }
+                    self.check_fields(t, &variant.fields, &generics, &mut HashSet::new());
+                }
+                self.check_methods(t, methods, &generics);
+            }
+            TypeKind::Interface { properties, methods, parents } => {
+                self.check_properties(t, properties, &generics, &mut member_ids);
+                self.check_methods(t, methods, &generics);
+                self.check_parents(t, parents, &generics, true);
+            }
+            TypeKind::Struct { constructors, fields, static_fields } => {
+                self.check_constructors(t, constructors, &generics);
+                self.check_fields(t, fields, &generics, &mut member_ids);
+                self.check_fields(t, static_fields, &generics, &mut member_ids);
+            }
+            TypeKind::TypeAlias(alias) => {
+                let location = get_location(t, None);
+                self.check_type_ref(alias, &generics, &location);
+                if self.is_cyclic_alias(&TypeRef::Id(t.id)) {
+                    self.report(Severity::Error, location, "Type alias refers to itself".to_string());
+                }
+            }
+        }
+    }
+}
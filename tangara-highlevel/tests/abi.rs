@@ -0,0 +1,52 @@
+use tangara_highlevel::{generate_abi_fingerprint, Version};
+#[cfg(feature = "builder")]
+use tangara_highlevel::{NamingConventions, Package, TypeRef};
+#[cfg(feature = "builder")]
+use tangara_highlevel::builder::*;
+
+#[test]
+fn version() {
+    assert_eq!(Version::parse("1.4.2"), Some(Version::new(1, 4, 2)));
+    assert_eq!(Version::parse("0.3.0-beta+build"), Some(Version::new(0, 3, 0)));
+    assert_eq!(Version::parse("1.x"), None);
+    assert_eq!(Version::new(2, 10, 1).to_string(), "2.10.1");
+    assert!(Version::new(1, 4, 2).is_compatible_with(&Version::new(1, 2, 0)));
+    assert!(!Version::new(1, 1, 0).is_compatible_with(&Version::new(1, 2, 0)));
+    assert!(!Version::new(2, 0, 0).is_compatible_with(&Version::new(1, 2, 0)));
+    assert!(!Version::new(0, 3, 0).is_compatible_with(&Version::new(0, 2, 0)));
+    assert_eq!(generate_abi_fingerprint(&[]), generate_abi_fingerprint(&[]));
+}
+
+#[cfg(feature = "builder")]
+fn build_package(reversed: bool, point_arg: &str) -> Package {
+    let builder = PackageBuilder::new("Geometry", NamingConventions::csharp());
+    builder.borrow_mut().set_version(Version::new(1, 0, 0));
+    let mut point = create_struct(builder.clone(), "Point");
+    point.add_field(TypeRef::from(point_arg), "X").build()
+        .add_field(TypeRef::from(point_arg), "Y").build();
+    let mut shape = create_class(builder.clone(), "Shape");
+    shape.add_method("Move")
+        .arg(TypeRef::from("Point"), "offset")
+        .build();
+    if reversed {
+        shape.build();
+        point.build();
+    } else {
+        point.build();
+        shape.build();
+    }
+    let package = builder.borrow().build();
+    package
+}
+
+#[test]
+#[cfg(feature = "builder")]
+fn abi_fingerprint() {
+    let package = build_package(false, "float");
+    assert_eq!(package.version, Version::new(1, 0, 0));
+    assert_eq!(package.abi, generate_abi_fingerprint(&package.types));
+    // order of types doesn't change binary interface
+    assert_eq!(package.abi, build_package(true, "float").abi);
+    // but layout of fields does
+    assert_ne!(package.abi, build_package(false, "double").abi);
+}
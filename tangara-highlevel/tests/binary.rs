@@ -0,0 +1,84 @@
+use tangara_highlevel::binary::*;
+#[cfg(all(feature = "builder", feature = "serde"))]
+use std::collections::HashMap;
+#[cfg(all(feature = "builder", feature = "serde"))]
+use tangara_highlevel::{Attribute, Dependency, MethodKind, NamingConventions, Package, TypeRef, Value, Version, VersionRange};
+#[cfg(all(feature = "builder", feature = "serde"))]
+use tangara_highlevel::builder::*;
+
+#[test]
+fn invalid_data() {
+    assert!(!is_binary_package(b"{\"name\":\"Geometry\"}"));
+    assert!(matches!(package_from_bytes(b"{}"), Err(BinaryError::InvalidMagic)));
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend((FORMAT_VERSION + 1).to_le_bytes());
+    assert!(matches!(package_from_bytes(&bytes), Err(BinaryError::UnsupportedVersion(_))));
+}
+
+#[cfg(all(feature = "builder", feature = "serde"))]
+fn build_package() -> Package {
+    let builder = PackageBuilder::new("Geometry", NamingConventions::csharp());
+    builder.borrow_mut()
+        .set_namespace("Geometry.Shapes")
+        .set_version(Version::new(0, 3, 1))
+        .add_dependency(Dependency::new("Math", VersionRange::parse("^1.2.0").unwrap()))
+        .add_dependency(Dependency::new("Tangara.Std", VersionRange::parse(">=0.1.0").unwrap()));
+    let mut point = create_struct(builder.clone(), "Point");
+    point.add_field(TypeRef::from("float"), "X")
+        .set_default_value(Value::Float(1.5)).build()
+        .add_field(TypeRef::from("float"), "Y").build()
+        .add_constructor()
+            .arg_value(TypeRef::from("float"), "x", Value::Float(0.0))
+            .arg_in(TypeRef::from("float"), "y")
+            .build();
+    point.build();
+    let mut shape = create_interface(builder.clone(), "IShape");
+    shape.add_method("Area")
+        .return_type(TypeRef::from("double"))
+        .set_kind(MethodKind::Abstract)
+        .build();
+    shape.build();
+    let mut polygon = create_class(builder.clone(), "Polygon");
+    TypeBuilder::add_attribute(&mut polygon, Attribute(TypeRef::from("Serializable"), vec![
+        Value::Object(HashMap::from([
+            ("Name".to_string(), Box::new(Value::String("polygon".to_string()))),
+            ("Tags".to_string(), Box::new(Value::Array(vec![Value::Long(-1), Value::ULong(u64::MAX)])))
+        ]))
+    ]));
+    polygon.inherits(TypeRef::from("IShape"))
+        .generic("T".to_string())
+        .add_property(TypeRef::Generic(Box::new(TypeRef::from("List")), vec![TypeRef::from("Point")]), "Points").build()
+        .add_static_field(TypeRef::from("int"), "Count").build()
+        .add_method("Map")
+            .arg(TypeRef::Fn(Some(Box::new(TypeRef::from("Point"))), vec![TypeRef::from("Point")]), "f")
+            .arg_out(TypeRef::Tuple(vec![TypeRef::from("int"), TypeRef::from("T")]), "result")
+            .build();
+    polygon.build();
+    let mut side = create_enum(builder.clone(), "Side");
+    side.variant("Left").variant_value("Right", Value::Byte(7));
+    side.build();
+    create_alias(builder.clone(), "Points", TypeRef::from("Polygon")).build();
+    let package = builder.borrow().build();
+    package
+}
+
+#[test]
+#[cfg(all(feature = "builder", feature = "serde"))]
+fn round_trip() {
+    let package = build_package();
+    let bytes = package_to_bytes(&package);
+    assert!(is_binary_package(&bytes));
+    let decoded = package_from_bytes(&bytes).unwrap();
+    assert_eq!(serde_json::to_value(&package).unwrap(), serde_json::to_value(&decoded).unwrap());
+    // binary package is written the same way every time
+    assert_eq!(bytes, package_to_bytes(&decoded));
+
+    let mut buffer = vec![];
+    write_package(&package, &mut buffer).unwrap();
+    let decoded = read_package(&mut buffer.as_slice()).unwrap();
+    assert_eq!(decoded.abi, package.abi);
+    // interned strings make it smaller than JSON
+    assert!(bytes.len() < serde_json::to_vec(&package).unwrap().len());
+
+    assert!(matches!(package_from_bytes(&bytes[..bytes.len() - 1]), Err(BinaryError::InvalidData(_))));
+}
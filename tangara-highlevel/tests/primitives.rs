@@ -0,0 +1,42 @@
+use tangara_highlevel::primitives::Primitive;
+use tangara_highlevel::{generate_type_id, TypeRef, Value};
+
+#[test]
+fn primitive_names() {
+    for name in ["int", "i32", "Int", "Tangara.Std.Int"] {
+        assert_eq!(Primitive::from_name(name), Some(Primitive::Int), "{name}");
+    }
+    assert_eq!(Primitive::from_name("UShort"), Some(Primitive::UShort));
+    assert_eq!(Primitive::from_name("CString"), Some(Primitive::CString));
+    assert_eq!(Primitive::from_name("RawPtr"), Some(Primitive::RawPtr));
+    // generic `Tangara.Std.Ptr<T>` isn't primitive
+    assert_eq!(Primitive::from_name("Tangara.Std.Ptr"), None);
+    assert_eq!(Primitive::from_name("Window"), None);
+    assert_eq!(
+        Primitive::from_typeref(&TypeRef::from(generate_type_id("Tangara.Std.Bool"))),
+        Some(Primitive::Bool)
+    );
+    for primitive in Primitive::ALL {
+        assert_eq!(Primitive::from_name(primitive.name()), Some(primitive));
+        assert_eq!(Primitive::from_name(primitive.builtin_name()), Some(primitive));
+    }
+}
+
+#[test]
+fn native_types() {
+    assert_eq!(Primitive::ULong.rust_type(), "u64");
+    assert_eq!(Primitive::ULong.c_type(), "uint64_t");
+    assert_eq!(Primitive::ULong.csharp_type(), "ulong");
+    assert_eq!(Primitive::String.rust_type(), "String");
+    assert_eq!(Primitive::String.c_type(), "TgRustString");
+    assert_eq!(Primitive::Str.c_type(), "TgStr");
+    assert_eq!(Primitive::RawPtr.csharp_type(), "IntPtr");
+}
+
+#[test]
+fn primitive_values() {
+    assert_eq!(Primitive::Int.matches_value(&Value::Int(1)), Some(true));
+    assert_eq!(Primitive::Int.matches_value(&Value::Long(1)), Some(false));
+    assert_eq!(Primitive::Str.matches_value(&Value::from("text")), Some(true));
+    assert_eq!(Primitive::RawPtr.matches_value(&Value::Null), None);
+}
@@ -0,0 +1,93 @@
+#![cfg(feature = "builder")]
+
+use tangara_highlevel::builder::*;
+use tangara_highlevel::resolver::*;
+use tangara_highlevel::{
+    generate_method_id, generate_type_id, get_typeref_bytes, Argument, ArgumentKind, NamingConventions, Package, TypeKind, TypeRef
+};
+
+fn build_dependency() -> Package {
+    let builder = PackageBuilder::new("math", NamingConventions::rust());
+    builder.borrow_mut().set_namespace("math::vector");
+    create_struct(builder.clone(), "Vector").build();
+    let package = builder.borrow().build();
+    package
+}
+
+fn build_package() -> Package {
+    let builder = PackageBuilder::new("geometry", NamingConventions::rust());
+    builder.borrow_mut().set_namespace("geometry");
+    create_struct(builder.clone(), "Point").build();
+    let mut shape = create_class(builder.clone(), "Shape");
+    shape.add_method("move_by")
+            .arg(TypeRef::from("Vector"), "offset")
+            .build()
+        .add_method("contains")
+            .arg(TypeRef::from("Point"), "point")
+            .return_type(TypeRef::from("bool"))
+            .build()
+        .add_method("copy")
+            .return_type(TypeRef::from("Self"))
+            .build()
+        .add_field(TypeRef::Generic(Box::new(TypeRef::from("Vec")), vec![TypeRef::from("Missing")]), "items")
+            .build();
+    shape.build();
+    let package = builder.borrow().build();
+    package
+}
+
+fn get_methods(package: &Package) -> Vec<(String, Vec<TypeRef>, u64)> {
+    let shape = package.types.iter().find(|t| t.name == "Shape").unwrap();
+    if let TypeKind::Class { methods, .. } = &shape.kind {
+        methods.iter()
+            .map(|m| (m.name.clone(), m.args.iter().map(|arg| arg.1.clone()).collect(), m.id))
+            .collect()
+    } else {
+        panic!("Shape must be class")
+    }
+}
+
+#[test]
+fn resolve_full_names() {
+    let mut package = build_package();
+    let old_abi = package.abi;
+    let mut resolver = Resolver::new(&[build_dependency()]);
+    resolver.add_import("math::vector::*");
+    let diagnostics = resolver.resolve(&mut package);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].to_string(), "error: geometry.Shape: Type Missing is not found");
+
+    let methods = get_methods(&package);
+    assert!(matches!(&methods[0].1[0], TypeRef::Name(name) if name == "math::vector.Vector"));
+    assert!(matches!(&methods[1].1[0], TypeRef::Name(name) if name == "geometry.Point"));
+    // full name gives the same id as type itself
+    assert_eq!(get_typeref_bytes(&methods[1].1[0]), generate_type_id("geometry.Point").to_be_bytes());
+    let args = vec![Argument(vec![], methods[1].1[0].clone(), "point".to_string(), ArgumentKind::Default)];
+    assert_eq!(methods[1].2, generate_method_id("contains", &args));
+    assert_ne!(package.abi, old_abi);
+}
+
+#[test]
+fn resolve_ids() {
+    let mut package = build_package();
+    let mut resolver = Resolver::new(&[build_dependency()]);
+    resolver.add_import("math::vector::Vector").set_mode(ResolveMode::Id);
+    resolver.resolve(&mut package);
+    let full_names = {
+        let mut package = build_package();
+        let mut resolver = Resolver::new(&[build_dependency()]);
+        resolver.add_import("math.vector.Vector");
+        resolver.resolve(&mut package);
+        package
+    };
+    let methods = get_methods(&package);
+    assert!(matches!(methods[0].1[0], TypeRef::Id(id) if id == generate_type_id("math::vector.Vector")));
+    // ids of methods don't depend on the way types are referenced
+    let get_ids = |methods: &[(String, Vec<TypeRef>, u64)]| methods.iter().map(|m| m.2).collect::<Vec<u64>>();
+    assert_eq!(get_ids(&methods), get_ids(&get_methods(&full_names)));
+
+    // without import vector is still found by its short name
+    let mut package = build_package();
+    Resolver::new(&[build_dependency()]).set_mode(ResolveMode::Id).resolve(&mut package);
+    assert_eq!(get_ids(&get_methods(&package)), get_ids(&methods));
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "builder")]
+
+use tangara_highlevel::builder::*;
+use tangara_highlevel::primitives::Primitive;
+use tangara_highlevel::resolver::*;
+use tangara_highlevel::tangara_std::TANGARA_STD;
+use tangara_highlevel::validation::validate;
+use tangara_highlevel::{generate_type_id, NamingConventions, TypeKind, TypeRef};
+
+#[test]
+fn std_package_is_valid() {
+    let std = TANGARA_STD.get_package();
+    assert_eq!(std.name, "Tangara.Std");
+    let diagnostics = validate(&std, &[]);
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    for name in ["Bool", "Int", "ULong", "String", "Array", "Ptr", "Option", "Result", "Attribute"] {
+        assert!(std.types.iter().any(|t| t.namespace == "Tangara.Std" && t.name == name), "{name} is missing");
+    }
+    let array_size = std.types.iter().find(|t| t.name == "ArraySize").unwrap();
+    assert_eq!(array_size.namespace, "Tangara.Metadata");
+    if let TypeKind::Class { parents, .. } = &array_size.kind {
+        assert_eq!(parents, &vec![TANGARA_STD.attribute()]);
+    } else {
+        panic!("ArraySize must be a class");
+    }
+}
+
+#[test]
+fn std_references() {
+    assert_eq!(TANGARA_STD.primitive("Int"), Some(TypeRef::from(generate_type_id("Tangara.Std.Int"))));
+    assert_eq!(TANGARA_STD.primitive("Array"), None);
+    // every primitive is defined in Tangara.Std, so its id is resolved
+    let std = TANGARA_STD.get_package();
+    for primitive in Primitive::ALL {
+        assert!(TANGARA_STD.primitive(primitive.name()).is_some(), "{primitive}");
+        let t = std.types.iter().find(|t| t.id == primitive.type_id()).unwrap();
+        assert_eq!(Primitive::from_typeref(&TypeRef::from(t.id)), Some(primitive));
+    }
+    assert_eq!(
+        TANGARA_STD.array(TypeRef::from("int")),
+        TypeRef::Generic(Box::new(TypeRef::from(generate_type_id("Tangara.Std.Array"))), vec![TypeRef::from("int")])
+    );
+    if let TypeRef::Generic(option, _) = TANGARA_STD.option(TypeRef::from("int")) {
+        assert!(TANGARA_STD.is_option(&option));
+        assert!(!TANGARA_STD.is_result(&option));
+    }
+    assert_eq!(
+        TANGARA_STD.lang_attribute("Rust").0,
+        TypeRef::from(generate_type_id("Tangara.Metadata.Lang"))
+    );
+}
+
+#[test]
+fn package_depends_on_std() {
+    let builder = PackageBuilder::new("geometry", NamingConventions::rust());
+    builder.borrow_mut().set_namespace("geometry");
+    let mut point = create_struct(builder.clone(), "Point");
+    point.add_field(TypeRef::from("Float"), "x").build()
+        .add_field(TANGARA_STD.array(TypeRef::from("Int")), "tags").build();
+    point.build();
+    let mut side = create_enum(builder.clone(), "Side").bitflags();
+    side.variant("Left").variant("Right");
+    let side = side.build();
+    assert!(TANGARA_STD.is_flags(&side.attrs));
+    let mut package = builder.borrow().build();
+
+    let std = TANGARA_STD.get_package();
+    assert!(validate(&package, &[]).iter().any(|d| d.is_error()));
+    assert!(validate(&package, std::slice::from_ref(&std)).is_empty());
+
+    let mut resolver = Resolver::new(&[std]);
+    resolver.set_mode(ResolveMode::Id);
+    assert!(resolver.resolve(&mut package).is_empty());
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "builder")]
+
+use tangara_highlevel::builder::*;
+use tangara_highlevel::resolver::ResolveMode;
+use tangara_highlevel::universe::*;
+use tangara_highlevel::{Dependency, NamingConventions, Package, TypeKind, TypeRef, Version, VersionRange};
+
+fn build_package(name: &str, version: Version, dependencies: &[Dependency]) -> Package {
+    let builder = PackageBuilder::new(name, NamingConventions::rust());
+    builder.borrow_mut().set_version(version);
+    for dependency in dependencies {
+        builder.borrow_mut().add_dependency(dependency.clone());
+    }
+    let package = builder.borrow().build();
+    package
+}
+
+#[test]
+fn version_range() {
+    assert_eq!(VersionRange::parse("^1.2.0"), Some(VersionRange::caret(Version::new(1, 2, 0))));
+    assert_eq!(VersionRange::parse("0.3.1").unwrap().max, Some(Version::new(0, 4, 0)));
+    assert_eq!(
+        VersionRange::parse(">=1.0.0, <3.0.0"),
+        Some(VersionRange::new(Version::new(1, 0, 0), Some(Version::new(3, 0, 0))))
+    );
+    assert_eq!(VersionRange::parse("*"), Some(VersionRange::default()));
+    assert_eq!(VersionRange::parse(">=1.x"), None);
+    let range = VersionRange::caret(Version::new(1, 2, 0));
+    assert!(range.contains(&Version::new(1, 9, 3)));
+    assert!(!range.contains(&Version::new(1, 1, 0)));
+    assert!(!range.contains(&Version::new(2, 0, 0)));
+    assert_eq!(range.to_string(), ">=1.2.0, <2.0.0");
+}
+
+#[test]
+fn load_universe() {
+    let std = build_package("std", Version::new(1, 4, 0), &[]);
+    let runtime = build_package("runtime", Version::new(0, 2, 1), &[Dependency::from(&std)]);
+    let app = build_package("app", Version::new(1, 0, 0), &[
+        Dependency::new("runtime", VersionRange::parse("0.2").unwrap()),
+        Dependency::new("std", VersionRange::parse("1.1.0").unwrap())
+    ]);
+    let universe = Universe::load(vec![app.clone(), std.clone(), runtime.clone()]).unwrap();
+    let names = universe.get_packages().iter().map(|p| p.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(names, ["std", "runtime", "app"]);
+    let dependencies = universe.get_dependencies(app.id).iter().map(|p| p.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(dependencies, ["std", "runtime"]);
+    assert!(universe.get_dependencies(std.id).is_empty());
+    assert_eq!(universe.find_package("runtime").map(|p| p.id), Some(runtime.id));
+
+    let errors = Universe::load(vec![app.clone(), build_package("std", Version::new(2, 0, 0), &[])]).err().unwrap();
+    assert_eq!(errors, vec![
+        DependencyError::MissingDependency { package: "app".to_string(), dependency: "runtime".to_string() },
+        DependencyError::IncompatibleVersion {
+            package: "app".to_string(),
+            dependency: "std".to_string(),
+            required: VersionRange::caret(Version::new(1, 1, 0)),
+            found: Version::new(2, 0, 0)
+        }
+    ]);
+}
+
+#[test]
+fn cyclic_dependencies() {
+    let first = build_package("first", Version::new(1, 0, 0), &[Dependency::new("second", VersionRange::default())]);
+    let second = build_package("second", Version::new(1, 0, 0), &[Dependency::new("first", VersionRange::default())]);
+    let errors = Universe::load(vec![first, second]).err().unwrap();
+    assert_eq!(errors, vec![DependencyError::Cycle(vec!["first".to_string(), "second".to_string(), "first".to_string()])]);
+    assert_eq!(errors[0].to_string(), "Cyclic dependency: first -> second -> first");
+}
+
+#[test]
+fn cross_package_references() {
+    let runtime = PackageBuilder::new("runtime", NamingConventions::rust());
+    runtime.borrow_mut().set_namespace("runtime::context");
+    create_class(runtime.clone(), "Context").build();
+    let runtime = runtime.borrow().build();
+
+    let app = PackageBuilder::new("app", NamingConventions::rust());
+    app.borrow_mut().add_dependency(Dependency::from(&runtime));
+    let mut plugin = create_class(app.clone(), "Plugin");
+    plugin.add_method("load").arg(TypeRef::from("Context"), "ctx").build();
+    plugin.build();
+    let app = app.borrow().build();
+    let app_id = app.id;
+
+    let mut universe = Universe::load(vec![app, runtime]).unwrap();
+    assert!(universe.validate().is_empty());
+    assert!(universe.resolve(ResolveMode::FullName).is_empty());
+    let app = universe.get_package(app_id).unwrap();
+    if let TypeKind::Class { methods, .. } = &app.types[0].kind {
+        assert!(matches!(&methods[0].args[0].1, TypeRef::Name(name) if name == "runtime::context.Context"));
+    } else {
+        panic!("Plugin must be class");
+    }
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "builder")]
+
+use tangara_highlevel::builder::*;
+use tangara_highlevel::validation::*;
+use tangara_highlevel::{NamingConventions, Package, TypeRef, Value};
+
+fn build_dependency() -> Package {
+    let builder = PackageBuilder::new("math", NamingConventions::rust());
+    builder.borrow_mut().set_namespace("math");
+    let mut vector = create_struct(builder.clone(), "Vector");
+    vector.add_field(TypeRef::from("f32"), "x").build();
+    vector.build();
+    let package = builder.borrow().build();
+    package
+}
+
+#[test]
+fn valid_package() {
+    let builder = PackageBuilder::new("geometry", NamingConventions::rust());
+    builder.borrow_mut().set_namespace("geometry");
+    let mut point = create_struct(builder.clone(), "Point");
+    point.generic("T".to_string())
+        .add_field(TypeRef::from("T"), "value").build()
+        .add_field(TypeRef::from("math::Vector"), "position").build()
+        .add_field(TypeRef::from("f32"), "scale")
+            .set_default_value(Value::Float(1.0)).build()
+        .add_field(TypeRef::Generic(Box::new(TypeRef::from("Option")), vec![TypeRef::from("i32")]), "tag")
+            .set_default_value(Value::Null).build();
+    point.build();
+    let package = builder.borrow().build();
+    assert_eq!(validate(&package, &[build_dependency()]), vec![]);
+    // without dependency vector isn't found
+    let diagnostics = validate(&package, &[]);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].to_string(), "error: geometry.Point.position: Type math::Vector is not found");
+}
+
+#[test]
+fn invalid_package() {
+    let builder = PackageBuilder::new("geometry", NamingConventions::rust());
+    builder.borrow_mut().set_namespace("geometry");
+    let mut point = create_struct(builder.clone(), "Point");
+    point.add_field(TypeRef::from("f32"), "x")
+            .set_default_value(Value::Int(1)).build()
+        .add_field(TypeRef::from("Missing"), "y").build();
+    point.build();
+    let mut shape = create_class(builder.clone(), "Shape");
+    shape.add_method("move_by").arg(TypeRef::from("Point"), "offset").build()
+        .add_method("move_by").arg(TypeRef::from("Point"), "other").build();
+    shape.build();
+    let mut drawable = create_interface(builder.clone(), "Drawable");
+    drawable.inherits(TypeRef::from("Shape"));
+    drawable.build();
+    create_alias(builder.clone(), "First", TypeRef::from("Second")).build();
+    create_alias(builder.clone(), "Second", TypeRef::from("geometry::First")).build();
+    let package = builder.borrow().build();
+
+    let diagnostics = validate(&package, &[]);
+    assert!(diagnostics.iter().all(Diagnostic::is_error));
+    let messages = diagnostics.iter().map(|d| d.to_string()).collect::<Vec<String>>();
+    assert!(messages.contains(&"error: geometry.Point.x: Default value Int(1) doesn't match type Name(\"f32\")".to_string()));
+    assert!(messages.contains(&"error: geometry.Point.y: Type Missing is not found".to_string()));
+    assert!(messages.iter().any(|m| m.starts_with("error: geometry.Shape.move_by: Method id")));
+    assert!(messages.contains(&"error: geometry.Drawable: Interface can inherit only interfaces but Shape isn't interface".to_string()));
+    assert!(messages.contains(&"error: geometry.First: Type alias refers to itself".to_string()));
+    assert!(messages.contains(&"error: geometry.Second: Type alias refers to itself".to_string()));
+    assert_eq!(messages.len(), 6);
+}
+
+#[test]
+fn ambiguous_name() {
+    let builder = PackageBuilder::new("shapes", NamingConventions::rust());
+    builder.borrow_mut().set_namespace("shapes");
+    create_struct(builder.clone(), "Vector").build();
+    let mut line = create_struct(builder.clone(), "Line");
+    line.add_field(TypeRef::from("Vector"), "direction").build();
+    line.build();
+    let package = builder.borrow().build();
+    let diagnostics = validate(&package, &[build_dependency()]);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    // full name isn't ambiguous
+    assert!(validate(&package, &[]).is_empty());
+}
@@ -0,0 +1,215 @@
+//! Attributes which control how `tangara-gen` exports Rust code to Tangara.
+//!
+//! `PackageGenerator` reads them from parsed source, so in compiled code they do nothing:
+//! `#[tangara(...)]` returns item without nested `#[tangara(...)]` attributes.
+//! Put bare `#[tangara]` on struct or enum to use options on its fields or variants,
+//! because attribute macros can't be placed on them.
+//!
+//! Options:
+//! - `skip` - item is not exported
+//! - `rename = "name"` - item is exported with another name
+//! - `ctor` - function is exported as constructor
+//! - `property` - `get_`/`set_` function is exported as accessor of property
+//! - `attr(Path, values...)` - attribute is added to exported item
+//!
+//! ```ignore
+//! use tangara_macros::tangara;
+//!
+//! #[tangara]
+//! pub struct Counter {
+//!     #[tangara(skip)]
+//!     pub cache: Vec<u64>,
+//!     #[tangara(rename = "count")]
+//!     pub value: u64
+//! }
+//!
+//! #[tangara]
+//! impl Counter {
+//!     #[tangara(ctor)]
+//!     pub fn zero() -> Self { ... }
+//!
+//!     #[tangara(property)]
+//!     pub fn get_double(&self) -> u64 { ... }
+//! }
+//! ```
+//!
+//! `#[export]` is alternative to build script with `PackageGenerator` and `EntrypointGenerator`:
+//! it generates package and entrypoint of inline module at compile time
+//! (only whole modules can be exported, not separate items),
+//! so options are written without importing `#[tangara]`. Library can have several exported modules,
+//! `tangara::export_library!` combines them into one package.
+//!
+//! ```ignore
+//! #[tangara::export]
+//! mod api {
+//!     pub struct Counter {
+//!         #[tangara(rename = "count")]
+//!         pub value: u64
+//!     }
+//! }
+//!
+//! tangara::export_library!(api);
+//! ```
+use proc_macro::TokenStream;
+use quote::ToTokens;
+use syn::{parse_quote, Attribute, Field, ImplItemConst, ImplItemFn, ImplItemType, Item, ItemEnum, ItemImpl,
+          ItemStruct, ItemTrait, ItemType, TraitItemConst, TraitItemFn, TraitItemType, Variant};
+use syn::visit_mut::{self, VisitMut};
+use tangara_gen::{PackageGenerator, PkgGenConfig, RustGenConfig, RustGenerator};
+
+/// Remove `#[tangara(...)]` attributes from items inside of annotated one
+struct StripAttributes;
+
+fn strip(attrs: &mut Vec<Attribute>) {
+    attrs.retain(|attr| !attr.path().is_ident("tangara"));
+}
+
+impl VisitMut for StripAttributes {
+    fn visit_item_struct_mut(&mut self, struct_item: &mut ItemStruct) {
+        strip(&mut struct_item.attrs);
+        visit_mut::visit_item_struct_mut(self, struct_item);
+    }
+
+    fn visit_item_enum_mut(&mut self, enum_item: &mut ItemEnum) {
+        strip(&mut enum_item.attrs);
+        visit_mut::visit_item_enum_mut(self, enum_item);
+    }
+
+    fn visit_item_trait_mut(&mut self, trait_item: &mut ItemTrait) {
+        strip(&mut trait_item.attrs);
+        visit_mut::visit_item_trait_mut(self, trait_item);
+    }
+
+    fn visit_item_impl_mut(&mut self, impl_item: &mut ItemImpl) {
+        strip(&mut impl_item.attrs);
+        visit_mut::visit_item_impl_mut(self, impl_item);
+    }
+
+    fn visit_item_type_mut(&mut self, type_item: &mut ItemType) {
+        strip(&mut type_item.attrs);
+        visit_mut::visit_item_type_mut(self, type_item);
+    }
+
+    fn visit_field_mut(&mut self, field: &mut Field) {
+        strip(&mut field.attrs);
+        visit_mut::visit_field_mut(self, field);
+    }
+
+    fn visit_variant_mut(&mut self, variant: &mut Variant) {
+        strip(&mut variant.attrs);
+        visit_mut::visit_variant_mut(self, variant);
+    }
+
+    fn visit_impl_item_fn_mut(&mut self, fn_item: &mut ImplItemFn) {
+        strip(&mut fn_item.attrs);
+        visit_mut::visit_impl_item_fn_mut(self, fn_item);
+    }
+
+    fn visit_impl_item_const_mut(&mut self, const_item: &mut ImplItemConst) {
+        strip(&mut const_item.attrs);
+        visit_mut::visit_impl_item_const_mut(self, const_item);
+    }
+
+    fn visit_impl_item_type_mut(&mut self, type_item: &mut ImplItemType) {
+        strip(&mut type_item.attrs);
+        visit_mut::visit_impl_item_type_mut(self, type_item);
+    }
+
+    fn visit_trait_item_fn_mut(&mut self, fn_item: &mut TraitItemFn) {
+        strip(&mut fn_item.attrs);
+        visit_mut::visit_trait_item_fn_mut(self, fn_item);
+    }
+
+    fn visit_trait_item_const_mut(&mut self, const_item: &mut TraitItemConst) {
+        strip(&mut const_item.attrs);
+        visit_mut::visit_trait_item_const_mut(self, const_item);
+    }
+
+    fn visit_trait_item_type_mut(&mut self, type_item: &mut TraitItemType) {
+        strip(&mut type_item.attrs);
+        visit_mut::visit_trait_item_type_mut(self, type_item);
+    }
+}
+
+/// Mark item for `tangara-gen`. It doesn't change item except removing nested `#[tangara(...)]` attributes.
+#[proc_macro_attribute]
+pub fn tangara(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    match syn::parse::<Item>(item.clone()) {
+        Ok(mut item) => {
+            StripAttributes.visit_item_mut(&mut item);
+            item.into_token_stream().into()
+        }
+        // associated items of impl and trait blocks aren't items, they have nothing to strip
+        Err(_) => item
+    }
+}
+
+/// Export structs, enums, traits and impls of inline module to Tangara.
+///
+/// Package is named and versioned as crate which is compiled, types of module are placed
+/// in namespace `{package}.{module}`, so module must be declared in root of crate.
+/// Module gets `bindings` submodule with its own `tgLoad` and `tgMetadata`, they aren't exported from library:
+/// `tangara::export_library!` exports entrypoint combined from all modules.
+/// Items are controlled by `#[tangara(...)]` attributes.
+///
+/// Only whole inline modules can be exported. Struct, enum, trait or impl can't be annotated by itself,
+/// because macro on it sees neither impls of type nor path of its module, so it can't make type
+/// with its methods. Such items and modules declared in other files are rejected with compile error.
+///
+/// ```compile_fail
+/// #[tangara_macros::export]
+/// pub struct Counter {
+///     pub value: u64
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn export(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut module = match syn::parse::<Item>(item) {
+        Ok(Item::Mod(module)) if module.content.is_some() => module,
+        Ok(item) => return unsupported_item(&item).to_compile_error().into(),
+        Err(err) => return err.to_compile_error().into()
+    };
+    let package_name = std::env::var("CARGO_CRATE_NAME").expect("Package must be compiled by Cargo");
+    let package = PackageGenerator::new(&package_name, PkgGenConfig::default())
+        .set_version(&std::env::var("CARGO_PKG_VERSION").expect("Package must be compiled by Cargo"))
+        .parse_items(&[Item::Mod(module.clone())])
+        .generate();
+    let code = RustGenerator::new(package, RustGenConfig::default())
+        .generate_entrypoint()
+        .embed_metadata()
+        .hide_symbols()
+        .custom_use("super::*")
+        .generate_code();
+    let bindings = syn::parse_file(&code).expect("Generated entrypoint must be valid Rust code").items;
+    // namespace is made of crate and module names, because macro doesn't know where module is
+    let module_path = format!("{}::{}", package_name, module.ident);
+    let error = format!("#[tangara::export] module must be declared in root of crate as `{}`", module_path);
+
+    StripAttributes.visit_item_mod_mut(&mut module);
+    if let Some((_, items)) = &mut module.content {
+        items.push(parse_quote! {
+            const _: () = assert!(::tangara::export::is_module_path(module_path!(), #module_path), #error);
+        });
+        items.push(parse_quote! {
+            /// Entrypoint of module generated by `#[tangara::export]`
+            #[allow(warnings)]
+            pub mod bindings {
+                #(#bindings)*
+            }
+        });
+    }
+    module.into_token_stream().into()
+}
+
+/// Error for item which `#[export]` can't be applied to, it points to item
+fn unsupported_item(item: &Item) -> syn::Error {
+    let message = match item {
+        Item::Mod(_) => "#[tangara::export] can't read module from another file, declare it inline: `mod name { ... }`",
+        Item::Struct(_) | Item::Enum(_) | Item::Trait(_) | Item::Impl(_) | Item::Type(_) => {
+            "#[tangara::export] exports whole modules only: move this item into inline module and annotate module, \
+            items inside of it are controlled by #[tangara(...)]"
+        }
+        _ => "#[tangara::export] must be applied to inline module"
+    };
+    syn::Error::new_spanned(item, message)
+}
@@ -0,0 +1,206 @@
+// This file was generated by `cargo xtask header`
+// All changes in this file will discard after regenerating it
+#ifndef TANGARA_H
+#define TANGARA_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct TgAllocator TgAllocator;
+typedef struct TgAllocatorCell TgAllocatorCell;
+typedef struct TgVersion TgVersion;
+typedef uint8_t* TgPtr;
+typedef void (*TgFnDtor)(TgPtr);
+typedef TgPtr (*TgFn)(size_t, uint8_t*);
+// Get id of runtime type of object, so objects of derived classes can be used as objects of base one
+typedef uint64_t (*TgFnTypeOf)(TgPtr);
+typedef struct TgProperty TgProperty;
+typedef struct TgStaticProperty TgStaticProperty;
+typedef struct TgVariants TgVariants;
+typedef struct TgVTable TgVTable;
+typedef struct TgImplementation TgImplementation;
+typedef struct TgFuncTable TgFuncTable;
+typedef struct TgTypeTable TgTypeTable;
+typedef struct TgContext TgContext;
+typedef struct TgMetadata TgMetadata;
+typedef struct TgRuntime TgRuntime;
+typedef struct TgRustString TgRustString;
+typedef struct TgStr TgStr;
+// Signature of `tgLoad` function exported by libraries with Tangara entrypoint
+typedef void (*TgFnLoad)(TgContext*);
+// Signature of `tgMetadata` function exported by libraries with embedded package metadata
+typedef TgMetadata (*TgFnMetadata)(void);
+
+// Functions used to allocate and free objects which cross boundary between library and host.
+// Both sides take allocator from `Context`, so memory is always freed
+// by the same allocator that allocated it.
+struct TgAllocator {
+    // Allocate memory with given size and alignment
+    TgPtr (*alloc)(size_t, size_t);
+    // Resize memory: pointer, old size, alignment and new size
+    TgPtr (*realloc)(TgPtr, size_t, size_t, size_t);
+    // Free memory: pointer, size and alignment
+    void (*free)(TgPtr, size_t, size_t);
+};
+
+// Semantic version of package
+struct TgVersion {
+    uint32_t major;
+    uint32_t minor;
+    uint32_t patch;
+};
+
+struct TgProperty {
+    TgPtr (*getter)(TgPtr);
+    void (*setter)(TgPtr, TgPtr);
+};
+
+struct TgStaticProperty {
+    TgPtr (*getter)(void);
+    void (*setter)(TgPtr);
+};
+
+// Functions for inspecting objects of enum or enum class without copying them
+struct TgVariants {
+    // Get id of variant which object holds
+    uint64_t (*tag)(TgPtr);
+    // Get field of object's variant by its id or null if this variant has no such field.
+    // Field is borrowed from object, so it mustn't be freed and is valid while object is alive.
+    TgPtr (*field)(TgPtr, uint64_t);
+};
+
+// Bytes of package in binary format returned by `tgMetadata`
+struct TgMetadata {
+    // Pointer to the first byte of package
+    const uint8_t* data;
+    // Count of bytes
+    size_t size;
+};
+
+// Borrowed UTF-8 string which is `Tangara.Std.Str` in packages. It has layout of Rust `&str`
+struct TgStr {
+    // Pointer to the first byte, string isn't null-terminated
+    const uint8_t* ptr;
+    // Count of bytes
+    size_t len;
+};
+
+// Get message of the last error happened in this thread or null if there were no errors.
+// Pointer is valid until the next failed call in this thread.
+const char* tg_last_error(void);
+
+// Create new runtime. Free it with `tg_runtime_free`.
+TgRuntime* tg_runtime_new(void);
+
+// Create new runtime with custom allocator for objects crossing library boundary.
+// Free it with `tg_runtime_free`.
+TgRuntime* tg_runtime_new_with_allocator(TgAllocator allocator);
+
+// Free runtime created by `tg_runtime_new`
+void tg_runtime_free(TgRuntime* runtime);
+
+// Get context of runtime. Pass it to `tgLoad` of library to load its packages.
+TgContext* tg_runtime_use_context(TgRuntime* runtime);
+
+// Load library by path and run its `tgLoad` on context of runtime
+bool tg_runtime_load_library(const TgRuntime* runtime, const char* path);
+
+// Remove packages of library loaded by `tg_runtime_load_library` from context of runtime
+bool tg_runtime_unload_library(const TgRuntime* runtime, const char* path);
+
+// Unload library and load it again. All pointers got from its packages become invalid.
+bool tg_runtime_reload_library(const TgRuntime* runtime, const char* path);
+
+// Make everything loaded into context of runtime visible for other threads and get published context.
+// Context stays alive until it's released by `tg_context_release`.
+const TgContext* tg_runtime_publish(const TgRuntime* runtime);
+
+// Get last published context of runtime, it doesn't change when runtime publishes new one.
+// Context stays alive until it's released by `tg_context_release`.
+const TgContext* tg_runtime_snapshot(const TgRuntime* runtime);
+
+// Release context got by `tg_runtime_publish` or `tg_runtime_snapshot`
+void tg_context_release(const TgContext* ctx);
+
+// Get allocator of context. Objects returned by library functions must be freed by it.
+TgAllocator tg_context_get_allocator(const TgContext* ctx);
+
+// Create implementation of interface with given id for host object `data`.
+// `dtor` (nullable) is called for `data` when implementation isn't used anymore.
+// Add it to context with `tg_context_add_implementation`.
+// `data` and `dtor` must be safe to use from any thread.
+TgImplementation* tg_implementation_new(uint64_t id, TgPtr data, TgFnDtor dtor);
+
+// Add method of interface to implementation. Method gets `data` of implementation as first argument.
+bool tg_implementation_add_method(TgImplementation* implementation, uint64_t id, TgFn func);
+
+// Register implementation of interface in context before loading libraries which use it.
+// Context takes ownership of implementation, so it mustn't be used after this call.
+bool tg_context_add_implementation(TgContext* ctx, TgImplementation* implementation);
+
+// Get package by id or null if it wasn't loaded
+const TgTypeTable* tg_context_get_package(const TgContext* ctx, uint64_t id);
+
+// Check that loaded package is compatible with required version and ABI fingerprint
+bool tg_package_check(const TgTypeTable* pkg, TgVersion version, uint64_t abi);
+
+// Check that type of package is `base` class or inherits it
+bool tg_package_is_subclass(const TgTypeTable* pkg, uint64_t id, uint64_t base);
+
+// Get the most derived implementation of method for objects of type (usually got by `tg_type_get_type_of`)
+// or null if neither type nor its base classes have it
+TgFn tg_package_resolve_method(const TgTypeTable* pkg, uint64_t type_id, uint64_t id);
+
+// Get type of package by id or null if it wasn't loaded
+const TgFuncTable* tg_package_get_type(const TgTypeTable* pkg, uint64_t id);
+
+// Get concrete instantiation of generic type by id or null if it wasn't registered
+const TgFuncTable* tg_package_get_instance(const TgTypeTable* pkg, uint64_t generic_id, uint64_t id);
+
+// Get constructor of type at given index or null if it doesn't exist
+TgFn tg_type_get_ctor(const TgFuncTable* t, size_t index);
+
+// Get destructor of type or null if it wasn't set
+TgFnDtor tg_type_get_dtor(const TgFuncTable* t);
+
+// Get method of type by id or null if it doesn't exist
+TgFn tg_type_get_method(const TgFuncTable* t, uint64_t id);
+
+// Get property of type by id or null if it doesn't exist
+const TgProperty* tg_type_get_property(const TgFuncTable* t, uint64_t id);
+
+// Get static property of type by id or null if it doesn't exist
+const TgStaticProperty* tg_type_get_static(const TgFuncTable* t, uint64_t id);
+
+// Get variants of enum type or null if type isn't enum
+const TgVariants* tg_type_get_variants(const TgFuncTable* t);
+
+// Get implementation of interface by type or null if type doesn't implement it
+const TgVTable* tg_type_get_interface(const TgFuncTable* t, uint64_t id);
+
+// Get function returning id of runtime type of objects or null if type can't be inherited
+TgFnTypeOf tg_type_get_type_of(const TgFuncTable* t);
+
+// Get method of interface implementation by id or null if it doesn't exist
+TgFn tg_vtable_get_method(const TgVTable* vtable, uint64_t id);
+
+// Call function (constructor or method) with arguments written one by one in `args` buffer.
+// Returns pointer on result (null for functions returning nothing).
+TgPtr tg_call(TgFn func, size_t args_size, uint8_t* args);
+
+// Destroy object using destructor of its type
+bool tg_destroy(const TgFuncTable* t, TgPtr object);
+
+// Get bytes of Rust string. They are borrowed from string and valid while it isn't changed or destroyed.
+TgStr tg_string_as_str(const TgRustString* string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif // TANGARA_H
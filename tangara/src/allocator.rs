@@ -0,0 +1,182 @@
+use std::alloc::Layout;
+use std::ops::Deref;
+use std::ptr;
+use std::sync::OnceLock;
+use crate::context::Ptr;
+use crate::error::TangaraError;
+
+/// Functions used to allocate and free objects which cross boundary between library and host.
+/// Both sides take allocator from `Context`, so memory is always freed
+/// by the same allocator that allocated it.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Allocator {
+    /// Allocate memory with given size and alignment
+    pub alloc: extern "C" fn(usize, usize) -> Ptr,
+    /// Resize memory: pointer, old size, alignment and new size
+    pub realloc: extern "C" fn(Ptr, usize, usize, usize) -> Ptr,
+    /// Free memory: pointer, size and alignment
+    pub free: extern "C" fn(Ptr, usize, usize)
+}
+
+extern "C" fn system_alloc(size: usize, align: usize) -> Ptr {
+    match Layout::from_size_align(size, align) {
+        Ok(layout) if size > 0 => unsafe { std::alloc::alloc(layout) },
+        _ => ptr::null_mut()
+    }
+}
+
+extern "C" fn system_realloc(value: Ptr, old_size: usize, align: usize, new_size: usize) -> Ptr {
+    match Layout::from_size_align(old_size, align) {
+        Ok(layout) if old_size > 0 && new_size > 0 => unsafe { std::alloc::realloc(value, layout, new_size) },
+        _ => ptr::null_mut()
+    }
+}
+
+extern "C" fn system_free(value: Ptr, size: usize, align: usize) {
+    if let Ok(layout) = Layout::from_size_align(size, align) {
+        if size > 0 && !value.is_null() {
+            unsafe { std::alloc::dealloc(value, layout) }
+        }
+    }
+}
+
+impl Allocator {
+    /// Global allocator of Rust, compatible with [Box]
+    pub const SYSTEM: Allocator = Allocator {
+        alloc: system_alloc,
+        realloc: system_realloc,
+        free: system_free
+    };
+
+    /// Move value into memory allocated by this allocator
+    pub fn new_object<T>(self, value: T) -> Ptr {
+        let layout = Layout::new::<T>();
+        let object = if layout.size() == 0 {
+            ptr::NonNull::<T>::dangling().as_ptr() as Ptr
+        } else {
+            (self.alloc)(layout.size(), layout.align())
+        };
+        if object.is_null() {
+            std::alloc::handle_alloc_error(layout);
+        }
+        unsafe { ptr::write(object as *mut T, value) };
+        object
+    }
+
+    /// Move value out of object created by [new_object](Allocator::new_object) and free its memory
+    ///
+    /// # Safety
+    /// `object` must be created by this allocator with the same type and mustn't be used after it
+    pub unsafe fn take_object<T>(self, object: Ptr) -> T {
+        let value = ptr::read(object as *const T);
+        self.free_object::<T>(object);
+        value
+    }
+
+    /// Drop object created by [new_object](Allocator::new_object) and free its memory
+    ///
+    /// # Safety
+    /// Same as for [take_object](Allocator::take_object)
+    pub unsafe fn delete_object<T>(self, object: Ptr) {
+        ptr::drop_in_place(object as *mut T);
+        self.free_object::<T>(object);
+    }
+
+    /// Move object of class into memory after header with its runtime type, so object of derived class
+    /// knows its type when it's used as object of base class. Type is got by [class_type_of].
+    pub fn new_class_object<T>(self, value: T, type_id: u64) -> Ptr {
+        let layout = class_layout::<T>();
+        let memory = (self.alloc)(layout.size(), layout.align());
+        if memory.is_null() {
+            std::alloc::handle_alloc_error(layout);
+        }
+        unsafe {
+            // header takes whole alignment of object, so type id is right before object
+            let object = memory.add(layout.align());
+            ptr::write((object as *mut u64).sub(1), type_id);
+            ptr::write(object as *mut T, value);
+            object
+        }
+    }
+
+    /// Drop object created by [new_class_object](Allocator::new_class_object) and free its memory
+    ///
+    /// # Safety
+    /// `object` must be created by this allocator with the same type and mustn't be used after it
+    pub unsafe fn delete_class_object<T>(self, object: Ptr) {
+        ptr::drop_in_place(object as *mut T);
+        let layout = class_layout::<T>();
+        (self.free)(object.sub(layout.align()), layout.size(), layout.align());
+    }
+
+    /// Check that both allocators consist of the same functions
+    pub fn is_same(&self, other: &Allocator) -> bool {
+        self.alloc as usize == other.alloc as usize &&
+            self.realloc as usize == other.realloc as usize &&
+            self.free as usize == other.free as usize
+    }
+
+    unsafe fn free_object<T>(self, object: Ptr) {
+        let layout = Layout::new::<T>();
+        if layout.size() > 0 {
+            (self.free)(object, layout.size(), layout.align());
+        }
+    }
+}
+
+/// Layout of object of class with header before it. Header is at least as big as id of type
+/// and as alignment of object, so object stays aligned.
+fn class_layout<T>() -> Layout {
+    let header = Layout::new::<T>().align().max(size_of::<u64>());
+    Layout::from_size_align(header + size_of::<T>(), header).unwrap()
+}
+
+/// Get runtime type of object created by [Allocator::new_class_object]. Returns 0 for null object.
+/// It's set by entrypoints as type getter of classes which have base class or descendants.
+pub extern "C" fn class_type_of(object: Ptr) -> u64 {
+    if object.is_null() {
+        return 0;
+    }
+    unsafe { ptr::read((object as *const u64).sub(1)) }
+}
+
+impl Default for Allocator {
+    fn default() -> Self {
+        Allocator::SYSTEM
+    }
+}
+
+/// Allocator which is set once by first context loading library or bindings.
+/// Generated code keeps it in static, because functions of library don't get context.
+/// [System](Allocator::SYSTEM) allocator is used until it's set.
+pub struct AllocatorCell(OnceLock<Allocator>);
+
+impl AllocatorCell {
+    pub const fn new() -> Self {
+        Self(OnceLock::new())
+    }
+
+    /// Use allocator if it isn't set yet, otherwise check that the same one is already used
+    pub fn set(&self, allocator: Allocator) -> Result<(), TangaraError> {
+        if self.0.get_or_init(|| allocator).is_same(&allocator) {
+            Ok(())
+        } else {
+            Err(TangaraError::AllocatorMismatch)
+        }
+    }
+}
+
+impl Default for AllocatorCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for AllocatorCell {
+    type Target = Allocator;
+
+    fn deref(&self) -> &Allocator {
+        self.0.get().unwrap_or(&Allocator::SYSTEM)
+    }
+}
@@ -1,43 +1,178 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+use libloading::Library;
+use crate::allocator::Allocator;
+use crate::error::TangaraError;
+
+/// Version is shared with metadata, so checks of loaded packages and generators can't drift apart
+pub use tangara_highlevel::Version;
 
 pub type Ptr = *mut u8;
 pub type FnDtor = extern "C" fn(Ptr);
 pub type Fn = extern "C" fn(usize, *mut u8) -> Ptr;
+/// Get id of runtime type of object, so objects of derived classes can be used as objects of base one
+pub type FnTypeOf = extern "C" fn(Ptr) -> u64;
 
+#[repr(C)]
+#[derive(Copy, Clone)]
 pub struct Property {
     pub getter: extern "C" fn(Ptr) -> Ptr,
     pub setter: Option<extern "C" fn(Ptr, Ptr)>
 }
+
+#[repr(C)]
+#[derive(Copy, Clone)]
 pub struct StaticProperty {
     pub getter: extern "C" fn() -> Ptr,
     pub setter: Option<extern "C" fn(Ptr)>
 }
 
+/// Functions for inspecting objects of enum or enum class without copying them
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Variants {
+    /// Get id of variant which object holds
+    pub tag: extern "C" fn(Ptr) -> u64,
+    /// Get field of object's variant by its id or null if this variant has no such field.
+    /// Field is borrowed from object, so it mustn't be freed and is valid while object is alive.
+    pub field: extern "C" fn(Ptr, u64) -> Ptr
+}
+
+/// Implementation of interface by type: methods of interface by their ids
+#[derive(Clone)]
+pub struct VTable {
+    id: u64,
+    methods: HashMap<u64, Fn>
+}
+
+impl VTable {
+    pub(crate) fn new(id: u64) -> Self {
+        Self {
+            id,
+            methods: HashMap::new()
+        }
+    }
+
+    /// Get id of implemented interface
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn add_method(&mut self, id: u64, func: Fn) {
+        self.methods.insert(id, func);
+    }
+
+    pub fn get_method(&self, id: u64) -> &Fn {
+        self.try_get_method(id).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_method(&self, id: u64) -> Result<&Fn, TangaraError> {
+        self.methods.get(&id).ok_or(TangaraError::MissingMethod { type_id: self.id, id })
+    }
+}
+
+/// Object of host which implements interface for libraries.
+/// Methods of its vtable take `data` as `self` argument.
+pub struct Implementation {
+    data: Ptr,
+    dtor: Option<FnDtor>,
+    vtable: VTable
+}
+
+// host is responsible for `data` to be used from any thread like library is responsible for its objects
+unsafe impl Send for Implementation {}
+unsafe impl Sync for Implementation {}
+
+impl Implementation {
+    /// Create implementation of interface with given id for `data`.
+    /// `dtor` is called for `data` when implementation isn't used by any context.
+    ///
+    /// # Safety
+    /// Implementation is shared between threads, so methods can be called for `data` from any thread
+    /// at the same time and `dtor` is run on thread which drops the last reference to it.
+    /// `data` and `dtor` must be safe to use like this.
+    pub unsafe fn new(id: u64, data: Ptr, dtor: Option<FnDtor>) -> Self {
+        Self {
+            data,
+            dtor,
+            vtable: VTable::new(id)
+        }
+    }
+
+    /// Get id of implemented interface
+    pub fn get_id(&self) -> u64 {
+        self.vtable.get_id()
+    }
+
+    pub fn get_data(&self) -> Ptr {
+        self.data
+    }
+
+    pub fn add_method(&mut self, id: u64, func: Fn) {
+        self.vtable.add_method(id, func);
+    }
+
+    pub fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+}
+
+impl Drop for Implementation {
+    fn drop(&mut self) {
+        if let Some(dtor) = self.dtor {
+            dtor(self.data);
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct FuncTable {
+    id: u64,
     dtor: Option<FnDtor>,
     ctors: Vec<Fn>,
     methods: HashMap<u64, Fn>,
     properties: HashMap<u64, Property>,
-    statics: HashMap<u64, StaticProperty>
+    statics: HashMap<u64, StaticProperty>,
+    variants: Option<Variants>,
+    interfaces: HashMap<u64, VTable>,
+    /// Id of base class
+    parent: Option<u64>,
+    type_of: Option<FnTypeOf>,
+    /// Id of generic type which this type instantiates
+    generic: Option<u64>
 }
 
 impl FuncTable {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(id: u64) -> Self {
         Self {
+            id,
             dtor: None,
             ctors: Vec::new(),
             methods: HashMap::new(),
             properties: HashMap::new(),
-            statics: HashMap::new()
+            statics: HashMap::new(),
+            variants: None,
+            interfaces: HashMap::new(),
+            parent: None,
+            type_of: None,
+            generic: None
         }
     }
 
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
     pub fn set_dtor(&mut self, dtor: FnDtor) {
         self.dtor = Some(dtor);
     }
 
     pub fn get_dtor(&self) -> FnDtor {
-        self.dtor.expect("Destructor cannot be None on calling")
+        self.try_get_dtor().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_dtor(&self) -> Result<FnDtor, TangaraError> {
+        self.dtor.ok_or(TangaraError::MissingDestructor(self.id))
     }
 
     pub fn add_ctor(&mut self, ctor: Fn) -> usize {
@@ -46,7 +181,11 @@ impl FuncTable {
     }
 
     pub fn get_ctor(&self, index: usize) -> &Fn {
-        self.ctors.get(index).expect(format!("Constructor not found at {index} index").as_str())
+        self.try_get_ctor(index).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_ctor(&self, index: usize) -> Result<&Fn, TangaraError> {
+        self.ctors.get(index).ok_or(TangaraError::MissingConstructor { type_id: self.id, index })
     }
 
     pub fn add_method(&mut self, id: u64, func: Fn) {
@@ -54,7 +193,11 @@ impl FuncTable {
     }
 
     pub fn get_method(&self, id: u64) -> &Fn {
-        self.methods.get(&id).expect(format!("Method with id {id} is not found").as_str())
+        self.try_get_method(id).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_method(&self, id: u64) -> Result<&Fn, TangaraError> {
+        self.methods.get(&id).ok_or(TangaraError::MissingMethod { type_id: self.id, id })
     }
 
     pub fn add_property(&mut self, id: u64, property: Property) {
@@ -62,7 +205,11 @@ impl FuncTable {
     }
 
     pub fn get_property(&self, id: u64) -> &Property {
-        self.properties.get(&id).expect(&format!("Property with id {id} is not found"))
+        self.try_get_property(id).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_property(&self, id: u64) -> Result<&Property, TangaraError> {
+        self.properties.get(&id).ok_or(TangaraError::MissingProperty { type_id: self.id, id })
     }
 
     pub fn add_static(&mut self, id: u64, static_property: StaticProperty) {
@@ -70,48 +217,317 @@ impl FuncTable {
     }
 
     pub fn get_static(&self, id: u64) -> &StaticProperty {
-        self.statics.get(&id).expect(&format!("Static property with id {id} is not found"))
+        self.try_get_static(id).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_static(&self, id: u64) -> Result<&StaticProperty, TangaraError> {
+        self.statics.get(&id).ok_or(TangaraError::MissingStatic { type_id: self.id, id })
+    }
+
+    pub fn set_variants(&mut self, variants: Variants) {
+        self.variants = Some(variants);
+    }
+
+    pub fn get_variants(&self) -> &Variants {
+        self.try_get_variants().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_variants(&self) -> Result<&Variants, TangaraError> {
+        self.variants.as_ref().ok_or(TangaraError::MissingVariants(self.id))
+    }
+
+    /// Register implementation of interface by this type and get its table for adding methods
+    pub fn add_interface(&mut self, id: u64) -> &mut VTable {
+        self.interfaces.insert(id, VTable::new(id));
+        self.interfaces.get_mut(&id).unwrap()
+    }
+
+    pub fn implements(&self, id: u64) -> bool {
+        self.interfaces.contains_key(&id)
+    }
+
+    pub fn get_interface(&self, id: u64) -> &VTable {
+        self.try_get_interface(id).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_interface(&self, id: u64) -> Result<&VTable, TangaraError> {
+        self.interfaces.get(&id).ok_or(TangaraError::MissingInterface { type_id: self.id, id })
+    }
+
+    /// Set base class of this type, methods which aren't overridden by type are found in it
+    pub fn set_parent(&mut self, id: u64) {
+        self.parent = Some(id);
+    }
+
+    pub fn get_parent(&self) -> Option<u64> {
+        self.parent
+    }
+
+    /// Set function getting runtime type of objects. It must be set for classes which can be inherited.
+    pub fn set_type_of(&mut self, type_of: FnTypeOf) {
+        self.type_of = Some(type_of);
+    }
+
+    pub fn get_type_of(&self) -> FnTypeOf {
+        self.try_get_type_of().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_type_of(&self) -> Result<FnTypeOf, TangaraError> {
+        self.type_of.ok_or(TangaraError::MissingTypeOf(self.id))
+    }
+
+    pub fn get_generic(&self) -> Option<u64> {
+        self.generic
     }
 }
 
+#[derive(Clone)]
 pub struct TypeTable {
+    id: u64,
+    version: Version,
+    abi: u64,
+    /// Library which owns functions of this package. It stays loaded while package is alive.
+    library: Option<Arc<Library>>,
     types: HashMap<u64, FuncTable>
 }
 
 impl TypeTable {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(id: u64) -> Self {
         Self {
+            id,
+            version: Version::default(),
+            abi: 0,
+            library: None,
             types: HashMap::new()
         }
     }
 
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn set_version(&mut self, version: Version) {
+        self.version = version;
+    }
+
+    pub fn get_version(&self) -> Version {
+        self.version
+    }
+
+    pub fn set_abi(&mut self, abi: u64) {
+        self.abi = abi;
+    }
+
+    pub fn get_abi(&self) -> u64 {
+        self.abi
+    }
+
+    pub(crate) fn set_library(&mut self, library: Arc<Library>) {
+        self.library = Some(library);
+    }
+
+    /// Check that loaded package can be used by code generated for `version` with `abi` fingerprint
+    pub fn check_compatibility(&self, version: Version, abi: u64) -> Result<(), TangaraError> {
+        if !self.version.is_compatible_with(&version) {
+            return Err(TangaraError::IncompatibleVersion {
+                package: self.id,
+                required: version,
+                loaded: self.version
+            });
+        }
+        if self.abi != abi {
+            return Err(TangaraError::AbiMismatch {
+                package: self.id,
+                required: abi,
+                loaded: self.abi
+            });
+        }
+        Ok(())
+    }
+
     pub fn add_type(&mut self, id: u64) -> &mut FuncTable {
-        self.types.insert(id, FuncTable::new());
+        self.types.insert(id, FuncTable::new(id));
         self.types.get_mut(&id).unwrap()
     }
 
     pub fn get_type(&self, id: u64) -> &FuncTable {
-        self.types.get(&id).expect(format!("Type by id {id} not found").as_str())
+        self.try_get_type(id).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_type(&self, id: u64) -> Result<&FuncTable, TangaraError> {
+        self.types.get(&id).ok_or(TangaraError::MissingType { package: self.id, id })
+    }
+
+    /// Add concrete instantiation of generic type, for example `GenericsTest<Foo>`.
+    /// Instance is a usual type with own id, so it can be got by [get_type](TypeTable::get_type) too
+    pub fn add_instance(&mut self, generic_id: u64, id: u64) -> &mut FuncTable {
+        let instance = self.add_type(id);
+        instance.generic = Some(generic_id);
+        instance
+    }
+
+    /// Get ids of all registered instantiations of generic type
+    pub fn get_instances(&self, generic_id: u64) -> Vec<u64> {
+        self.types.values()
+            .filter(|t| t.generic == Some(generic_id))
+            .map(|t| t.id)
+            .collect()
+    }
+
+    pub fn get_instance(&self, generic_id: u64, id: u64) -> &FuncTable {
+        self.try_get_instance(generic_id, id).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_instance(&self, generic_id: u64, id: u64) -> Result<&FuncTable, TangaraError> {
+        self.types.get(&id)
+            .filter(|t| t.generic == Some(generic_id))
+            .ok_or(TangaraError::MissingInstance { generic_id, id })
+    }
+
+    /// Check that type is `base` or inherits it
+    pub fn is_subclass(&self, id: u64, base: u64) -> bool {
+        let mut current = Some(id);
+        // every type is visited once at most even if parents are cyclic
+        for _ in 0..=self.types.len() {
+            match current {
+                Some(id) if id == base => return true,
+                Some(id) => current = self.types.get(&id).and_then(|t| t.get_parent()),
+                None => return false
+            }
+        }
+        false
+    }
+
+    /// Find the most derived implementation of method for objects of type:
+    /// method of type itself or of its nearest base class which has it
+    pub fn resolve_method(&self, type_id: u64, id: u64) -> &Fn {
+        self.try_resolve_method(type_id, id).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_resolve_method(&self, type_id: u64, id: u64) -> Result<&Fn, TangaraError> {
+        let mut t = self.try_get_type(type_id)?;
+        for _ in 0..self.types.len() {
+            if let Ok(method) = t.try_get_method(id) {
+                return Ok(method);
+            }
+            match t.get_parent().and_then(|parent| self.types.get(&parent)) {
+                Some(parent) => t = parent,
+                None => break
+            }
+        }
+        Err(TangaraError::MissingMethod { type_id, id })
     }
 }
 
+#[derive(Clone)]
 pub struct Context {
-    pkgs: HashMap<u64, TypeTable>
+    allocator: Allocator,
+    pkgs: HashMap<u64, TypeTable>,
+    /// Implementations of interfaces registered by host by ids of interfaces
+    implementations: HashMap<u64, Arc<Implementation>>,
+    /// Error of `tgLoad` which refused to load library into this context
+    load_error: Option<TangaraError>
 }
 
 impl Context {
     pub(crate) fn new() -> Self {
         Self {
-            pkgs: HashMap::new()
+            allocator: Allocator::SYSTEM,
+            pkgs: HashMap::new(),
+            implementations: HashMap::new(),
+            load_error: None
         }
     }
 
+    /// Set allocator for objects created by loaded libraries. It must be set before loading them.
+    pub fn set_allocator(&mut self, allocator: Allocator) {
+        self.allocator = allocator;
+    }
+
+    pub fn get_allocator(&self) -> Allocator {
+        self.allocator
+    }
+
     pub fn add_package(&mut self, id: u64) -> &mut TypeTable {
-        self.pkgs.insert(id, TypeTable::new());
+        self.pkgs.insert(id, TypeTable::new(id));
         self.pkgs.get_mut(&id).unwrap()
     }
 
+    /// Remove package from context. Its library is unloaded when no other context uses it.
+    pub fn remove_package(&mut self, id: u64) -> Option<TypeTable> {
+        self.pkgs.remove(&id)
+    }
+
+    /// Create context with same allocator and implementations but without packages
+    pub(crate) fn without_packages(&self) -> Self {
+        Self {
+            allocator: self.allocator,
+            pkgs: HashMap::new(),
+            implementations: self.implementations.clone(),
+            load_error: None
+        }
+    }
+
+    /// Refuse to load library into this context, so loading it returns given error.
+    /// It's called by `tgLoad` instead of adding packages.
+    pub fn reject_load(&mut self, error: TangaraError) {
+        self.load_error = Some(error);
+    }
+
+    pub(crate) fn take_load_error(&mut self) -> Option<TangaraError> {
+        self.load_error.take()
+    }
+
+    /// Move packages of other context into this one, so they keep `library` loaded
+    pub(crate) fn add_library_packages(&mut self, other: Context, library: Arc<Library>) {
+        for (id, mut pkg) in other.pkgs {
+            pkg.set_library(library.clone());
+            self.pkgs.insert(id, pkg);
+        }
+    }
+
+    /// Move packages of other context into this one. Types are added to package
+    /// if it already exists, so one package can be registered by several entrypoints.
+    #[cfg(feature = "export")]
+    pub(crate) fn add_module_packages(&mut self, other: Context) {
+        for (id, pkg) in other.pkgs {
+            match self.pkgs.get_mut(&id) {
+                Some(existing) => existing.types.extend(pkg.types),
+                None => {
+                    self.pkgs.insert(id, pkg);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "export")]
+    pub(crate) fn get_package_mut(&mut self, id: u64) -> Option<&mut TypeTable> {
+        self.pkgs.get_mut(&id)
+    }
+
+    pub fn get_package_ids(&self) -> Vec<u64> {
+        self.pkgs.keys().copied().collect()
+    }
+
     pub fn get_package(&self, id: u64) -> &TypeTable {
-        self.pkgs.get(&id).expect(format!("Package by id {id} not found").as_str())
+        self.try_get_package(id).unwrap_or_else(|err| panic!("{err}"))
     }
-}
\ No newline at end of file
+
+    pub fn try_get_package(&self, id: u64) -> Result<&TypeTable, TangaraError> {
+        self.pkgs.get(&id).ok_or(TangaraError::MissingPackage(id))
+    }
+
+    /// Register implementation of interface by host, previous implementation of it is replaced.
+    /// It must be added before loading libraries which use it.
+    pub fn add_implementation(&mut self, implementation: Implementation) {
+        self.implementations.insert(implementation.get_id(), Arc::new(implementation));
+    }
+
+    pub fn get_implementation(&self, id: u64) -> &Arc<Implementation> {
+        self.try_get_implementation(id).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_implementation(&self, id: u64) -> Result<&Arc<Implementation>, TangaraError> {
+        self.implementations.get(&id).ok_or(TangaraError::MissingImplementation(id))
+    }
+}
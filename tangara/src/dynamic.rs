@@ -0,0 +1,333 @@
+//! Reflective calls of functions loaded to [Context] using high-level package metadata.
+//! It lets call any exported method without generating bindings for it.
+
+use std::fmt::{Display, Formatter};
+use std::mem::{size_of, ManuallyDrop};
+use std::ptr;
+use tangara_highlevel::primitives::Primitive;
+use tangara_highlevel::{Argument, ArgumentKind, Method, MethodKind, Package, Type, TypeKind, TypeRef, Value};
+use crate::allocator::Allocator;
+use crate::context::{Context, FuncTable, Ptr};
+use crate::error::TangaraError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InvokeError {
+    /// Package metadata doesn't contain type with given name
+    TypeNotFound(String),
+    /// Type doesn't contain method (or constructor) with given name and count of arguments
+    MethodNotFound { type_name: String, method_name: String, args_count: usize },
+    /// Method needs `this` but it wasn't passed or vice versa
+    InvalidThis { type_name: String, method_name: String },
+    /// Value can't be converted to type of argument
+    ArgumentMismatch { index: usize, expected: String },
+    /// Type or kind of argument/return value can't be marshalled dynamically
+    UnsupportedType(String),
+    /// Function is described in metadata but wasn't loaded into context
+    Missing(TangaraError),
+    /// Function returned null pointer though it must return value
+    NullResult
+}
+
+impl Display for InvokeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvokeError::TypeNotFound(name) => write!(f, "Type {name} not found in package"),
+            InvokeError::MethodNotFound { type_name, method_name, args_count } => {
+                write!(f, "Method {type_name}::{method_name} with {args_count} arguments not found")
+            }
+            InvokeError::InvalidThis { type_name, method_name } => {
+                write!(f, "Method {type_name}::{method_name} called with wrong 'this' argument")
+            }
+            InvokeError::ArgumentMismatch { index, expected } => {
+                write!(f, "Argument #{index} can't be converted to {expected}")
+            }
+            InvokeError::UnsupportedType(name) => write!(f, "Type {name} can't be marshalled dynamically"),
+            InvokeError::Missing(err) => err.fmt(f),
+            InvokeError::NullResult => write!(f, "Pointer of method result is null")
+        }
+    }
+}
+
+impl std::error::Error for InvokeError {}
+
+impl From<TangaraError> for InvokeError {
+    fn from(value: TangaraError) -> Self {
+        InvokeError::Missing(value)
+    }
+}
+
+/// Buffer of arguments in format that generated entrypoints read: values placed one by one
+struct ArgsBuffer {
+    bytes: Vec<u8>,
+    /// Strings which passed by reference must live until the end of call
+    strings: Vec<String>,
+    /// Offsets of owned strings which are moved into called function.
+    /// They are dropped with buffer if function wasn't called, for example when next argument is invalid.
+    owned: Vec<usize>,
+    /// Function was called and owns values of arguments now
+    passed: bool
+}
+
+impl ArgsBuffer {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            strings: Vec::new(),
+            owned: Vec::new(),
+            passed: false
+        }
+    }
+
+    /// Copy bytes of value into buffer. Value is moved into buffer, so it won't be dropped here.
+    fn push<T>(&mut self, value: T) {
+        let value = ManuallyDrop::new(value);
+        let bytes = unsafe {
+            std::slice::from_raw_parts(&*value as *const T as *const u8, size_of::<T>())
+        };
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    /// Move string into buffer, it's owned by called function then
+    fn push_string(&mut self, value: String) {
+        self.owned.push(self.bytes.len());
+        self.push(value);
+    }
+
+    fn push_str(&mut self, value: String) {
+        self.strings.push(value);
+        let value: &str = self.strings.last().unwrap();
+        let value: &'static str = unsafe { std::mem::transmute(value) };
+        self.push(value);
+    }
+
+    fn push_value(&mut self, index: usize, arg: &Argument, value: &Value) -> Result<(), InvokeError> {
+        let primitive = Primitive::from_typeref(&arg.1)
+            .ok_or_else(|| InvokeError::UnsupportedType(get_typeref_name(&arg.1)))?;
+        let mismatch = || InvokeError::ArgumentMismatch { index, expected: get_typeref_name(&arg.1) };
+        match &arg.3 {
+            ArgumentKind::Default | ArgumentKind::DefaultValue(_) => {
+                match primitive {
+                    Primitive::Bool => self.push(get_bool(value).ok_or_else(mismatch)?),
+                    Primitive::SByte => self.push(i8::try_from(get_integer(value).ok_or_else(mismatch)?).map_err(|_| mismatch())?),
+                    Primitive::Byte => self.push(u8::try_from(get_integer(value).ok_or_else(mismatch)?).map_err(|_| mismatch())?),
+                    Primitive::Short => self.push(i16::try_from(get_integer(value).ok_or_else(mismatch)?).map_err(|_| mismatch())?),
+                    Primitive::UShort => self.push(u16::try_from(get_integer(value).ok_or_else(mismatch)?).map_err(|_| mismatch())?),
+                    Primitive::Int => self.push(i32::try_from(get_integer(value).ok_or_else(mismatch)?).map_err(|_| mismatch())?),
+                    Primitive::UInt => self.push(u32::try_from(get_integer(value).ok_or_else(mismatch)?).map_err(|_| mismatch())?),
+                    Primitive::Long => self.push(i64::try_from(get_integer(value).ok_or_else(mismatch)?).map_err(|_| mismatch())?),
+                    Primitive::ULong => self.push(u64::try_from(get_integer(value).ok_or_else(mismatch)?).map_err(|_| mismatch())?),
+                    Primitive::USize => self.push(usize::try_from(get_integer(value).ok_or_else(mismatch)?).map_err(|_| mismatch())?),
+                    Primitive::ISize => self.push(isize::try_from(get_integer(value).ok_or_else(mismatch)?).map_err(|_| mismatch())?),
+                    Primitive::Float => self.push(get_float(value).ok_or_else(mismatch)? as f32),
+                    Primitive::Double => self.push(get_float(value).ok_or_else(mismatch)?),
+                    Primitive::String => {
+                        if let Value::String(string) = value {
+                            // ownership of string is passed into the called function
+                            self.push_string(string.clone());
+                        } else {
+                            return Err(mismatch());
+                        }
+                    }
+                    _ => return Err(InvokeError::UnsupportedType(get_typeref_name(&arg.1)))
+                }
+            }
+            ArgumentKind::In => {
+                match (primitive, value) {
+                    (Primitive::Str, Value::String(string)) => self.push_str(string.clone()),
+                    (Primitive::Str, _) => return Err(mismatch()),
+                    _ => return Err(InvokeError::UnsupportedType(format!("&{}", get_typeref_name(&arg.1))))
+                }
+            }
+            ArgumentKind::Out | ArgumentKind::Ref => {
+                return Err(InvokeError::UnsupportedType(format!("&mut {}", get_typeref_name(&arg.1))));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ArgsBuffer {
+    fn drop(&mut self) {
+        if !self.passed {
+            for offset in &self.owned {
+                unsafe {
+                    drop(ptr::read_unaligned(self.bytes.as_ptr().add(*offset) as *const String));
+                }
+            }
+        }
+    }
+}
+
+/// Name of type reference for errors: name itself or name of primitive referenced by id
+fn get_typeref_name(type_ref: &TypeRef) -> String {
+    match (type_ref, Primitive::from_typeref(type_ref)) {
+        (TypeRef::Name(name), _) => name.clone(),
+        (_, Some(primitive)) => primitive.full_name(),
+        _ => format!("{:?}", type_ref)
+    }
+}
+
+fn get_bool(value: &Value) -> Option<bool> {
+    if let Value::Bool(bool_value) = value {
+        Some(*bool_value)
+    } else {
+        None
+    }
+}
+
+fn get_integer(value: &Value) -> Option<i128> {
+    match value {
+        Value::Byte(v) => Some(*v as i128),
+        Value::Short(v) => Some(*v as i128),
+        Value::Int(v) => Some(*v as i128),
+        Value::Long(v) => Some(*v as i128),
+        Value::SByte(v) => Some(*v as i128),
+        Value::UShort(v) => Some(*v as i128),
+        Value::UInt(v) => Some(*v as i128),
+        Value::ULong(v) => Some(*v as i128),
+        _ => None
+    }
+}
+
+fn get_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(v) => Some(*v as f64),
+        Value::Double(v) => Some(*v),
+        _ => get_integer(value).map(|v| v as f64)
+    }
+}
+
+/// Take value returned by function as object of allocator and convert it to [Value]
+unsafe fn read_result(allocator: Allocator, raw_ptr: Ptr, return_type: &TypeRef) -> Result<Value, InvokeError> {
+    if raw_ptr.is_null() {
+        return Err(InvokeError::NullResult);
+    }
+    let primitive = Primitive::from_typeref(return_type)
+        .ok_or_else(|| InvokeError::UnsupportedType(get_typeref_name(return_type)))?;
+    Ok(match primitive {
+        Primitive::Bool => Value::Bool(allocator.take_object::<bool>(raw_ptr)),
+        Primitive::SByte => Value::SByte(allocator.take_object::<i8>(raw_ptr)),
+        Primitive::Byte => Value::Byte(allocator.take_object::<u8>(raw_ptr)),
+        Primitive::Short => Value::Short(allocator.take_object::<i16>(raw_ptr)),
+        Primitive::UShort => Value::UShort(allocator.take_object::<u16>(raw_ptr)),
+        Primitive::Int => Value::Int(allocator.take_object::<i32>(raw_ptr)),
+        Primitive::UInt => Value::UInt(allocator.take_object::<u32>(raw_ptr)),
+        Primitive::Long => Value::Long(allocator.take_object::<i64>(raw_ptr)),
+        Primitive::ULong => Value::ULong(allocator.take_object::<u64>(raw_ptr)),
+        Primitive::USize => Value::from(allocator.take_object::<usize>(raw_ptr)),
+        Primitive::ISize => Value::Long(allocator.take_object::<isize>(raw_ptr) as i64),
+        Primitive::Float => Value::Float(allocator.take_object::<f32>(raw_ptr)),
+        Primitive::Double => Value::Double(allocator.take_object::<f64>(raw_ptr)),
+        Primitive::String => Value::String(allocator.take_object::<String>(raw_ptr)),
+        Primitive::Str => Value::String(allocator.take_object::<&str>(raw_ptr).to_string()),
+        _ => return Err(InvokeError::UnsupportedType(get_typeref_name(return_type)))
+    })
+}
+
+fn find_type<'a>(package: &'a Package, type_name: &str) -> Result<&'a Type, InvokeError> {
+    package.types.iter()
+        .find(|t| t.name == type_name || format!("{}.{}", t.namespace, t.name) == type_name)
+        .ok_or_else(|| InvokeError::TypeNotFound(type_name.to_string()))
+}
+
+fn find_method<'a>(t: &'a Type, method_name: &str, args_count: usize) -> Result<&'a Method, InvokeError> {
+    let methods = match &t.kind {
+        TypeKind::Class { methods, .. } => methods.as_slice(),
+        TypeKind::EnumClass { methods, .. } => methods.as_slice(),
+        _ => &[]
+    };
+    methods.iter()
+        .find(|m| m.name == method_name && m.args.len() == args_count)
+        .ok_or_else(|| InvokeError::MethodNotFound {
+            type_name: t.name.clone(),
+            method_name: method_name.to_string(),
+            args_count
+        })
+}
+
+impl Context {
+    fn get_func_table(&self, package: &Package, t: &Type) -> Result<&FuncTable, InvokeError> {
+        Ok(self.try_get_package(package.id)?.try_get_type(t.id)?)
+    }
+
+    fn call_method(&self, package: &Package, this: Option<Ptr>, type_name: &str, method_name: &str,
+                   args: &[Value]) -> Result<Value, InvokeError> {
+        let t = find_type(package, type_name)?;
+        let method = find_method(t, method_name, args.len())?;
+        if this.is_some() == (method.kind == MethodKind::Static) {
+            return Err(InvokeError::InvalidThis {
+                type_name: t.name.clone(),
+                method_name: method.name.clone()
+            });
+        }
+        let func = *self.get_func_table(package, t)?.try_get_method(method.id)?;
+
+        let mut buffer = ArgsBuffer::new();
+        if let Some(this) = this {
+            buffer.push(this);
+        }
+        for (index, (arg, value)) in method.args.iter().zip(args).enumerate() {
+            buffer.push_value(index, arg, value)?;
+        }
+        let args_ptr = if buffer.bytes.is_empty() {
+            ptr::null_mut()
+        } else {
+            buffer.bytes.as_mut_ptr()
+        };
+        let raw_ptr = func(buffer.bytes.len(), args_ptr);
+        buffer.passed = true;
+        match &method.return_type {
+            Some(return_type) => unsafe { read_result(self.get_allocator(), raw_ptr, return_type) },
+            None => Ok(Value::Null)
+        }
+    }
+
+    /// Call static method of type with given name (or full name with namespace) described in `package`.
+    /// Method is chosen by name and count of arguments.
+    pub fn invoke(&self, package: &Package, type_name: &str, method_name: &str,
+                  args: &[Value]) -> Result<Value, InvokeError> {
+        self.call_method(package, None, type_name, method_name, args)
+    }
+
+    /// Call method of object `this` which type has given name (or full name with namespace).
+    /// Method is chosen by name and count of arguments.
+    pub fn invoke_method(&self, package: &Package, this: Ptr, type_name: &str, method_name: &str,
+                         args: &[Value]) -> Result<Value, InvokeError> {
+        self.call_method(package, Some(this), type_name, method_name, args)
+    }
+
+    /// Create object of type with given name (or full name with namespace) by constructor
+    /// with same count of arguments. Returns pointer on created object.
+    pub fn construct(&self, package: &Package, type_name: &str, args: &[Value]) -> Result<Ptr, InvokeError> {
+        let t = find_type(package, type_name)?;
+        let constructors = match &t.kind {
+            TypeKind::Class { constructors, .. } => constructors.as_slice(),
+            TypeKind::Struct { constructors, .. } => constructors.as_slice(),
+            _ => &[]
+        };
+        let index = constructors.iter()
+            .position(|ctor| ctor.args.len() == args.len())
+            .ok_or_else(|| InvokeError::MethodNotFound {
+                type_name: t.name.clone(),
+                method_name: "constructor".to_string(),
+                args_count: args.len()
+            })?;
+        let ctor = *self.get_func_table(package, t)?.try_get_ctor(index)?;
+
+        let mut buffer = ArgsBuffer::new();
+        for (index, (arg, value)) in constructors[index].args.iter().zip(args).enumerate() {
+            buffer.push_value(index, arg, value)?;
+        }
+        let args_ptr = if buffer.bytes.is_empty() {
+            ptr::null_mut()
+        } else {
+            buffer.bytes.as_mut_ptr()
+        };
+        let this = ctor(buffer.bytes.len(), args_ptr);
+        if this.is_null() {
+            Err(InvokeError::NullResult)
+        } else {
+            Ok(this)
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::fmt::{Display, Formatter};
+use crate::context::Version;
+
+/// Error of looking up something that wasn't registered in [Context](crate::context::Context)
+/// or of checking compatibility and loading of package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TangaraError {
+    /// Package with given id wasn't added to context
+    MissingPackage(u64),
+    /// Type with given id wasn't added to package
+    MissingType { package: u64, id: u64 },
+    /// Instantiation with given id of generic type wasn't added to package
+    MissingInstance { generic_id: u64, id: u64 },
+    /// Constructor with given index wasn't added to type
+    MissingConstructor { type_id: u64, index: usize },
+    /// Destructor wasn't set for type with given id
+    MissingDestructor(u64),
+    /// Method with given id wasn't added to type
+    MissingMethod { type_id: u64, id: u64 },
+    /// Property with given id wasn't added to type
+    MissingProperty { type_id: u64, id: u64 },
+    /// Static property with given id wasn't added to type
+    MissingStatic { type_id: u64, id: u64 },
+    /// Variants weren't set for type with given id, so it isn't enum
+    MissingVariants(u64),
+    /// Type with given id doesn't implement interface
+    MissingInterface { type_id: u64, id: u64 },
+    /// Function getting runtime type of objects wasn't set for type with given id
+    MissingTypeOf(u64),
+    /// Host didn't register implementation of interface with given id
+    MissingImplementation(u64),
+    /// Loaded package has version which isn't compatible with required one
+    IncompatibleVersion { package: u64, required: Version, loaded: Version },
+    /// Loaded package has other binary interface than required one
+    AbiMismatch { package: u64, required: u64, loaded: u64 },
+    /// Library at given path can't be loaded or hasn't required entrypoint
+    LoadLibrary { path: String, message: String },
+    /// Library at given path wasn't loaded by runtime
+    MissingLibrary(String),
+    /// Library at given path is already loaded by runtime
+    LibraryAlreadyLoaded(String),
+    /// Library at given path registers package which is already loaded
+    PackageAlreadyLoaded { path: String, package: u64 },
+    /// Metadata embedded into library can't be decoded
+    InvalidMetadata(String),
+    /// Library or bindings already use allocator of another context, so objects of this one can't be freed by it
+    AllocatorMismatch
+}
+
+impl Display for TangaraError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TangaraError::MissingPackage(id) => {
+                write!(f, "Package by id {id} not found")
+            }
+            TangaraError::MissingType { package, id } => {
+                write!(f, "Type by id {id} not found in package {package}")
+            }
+            TangaraError::MissingInstance { generic_id, id } => {
+                write!(f, "Instance by id {id} of generic type {generic_id} not found")
+            }
+            TangaraError::MissingConstructor { type_id, index } => {
+                write!(f, "Constructor not found at {index} index in type {type_id}")
+            }
+            TangaraError::MissingDestructor(type_id) => {
+                write!(f, "Destructor of type {type_id} is not set")
+            }
+            TangaraError::MissingMethod { type_id, id } => {
+                write!(f, "Method with id {id} is not found in type {type_id}")
+            }
+            TangaraError::MissingProperty { type_id, id } => {
+                write!(f, "Property with id {id} is not found in type {type_id}")
+            }
+            TangaraError::MissingStatic { type_id, id } => {
+                write!(f, "Static property with id {id} is not found in type {type_id}")
+            }
+            TangaraError::MissingVariants(type_id) => {
+                write!(f, "Variants of type {type_id} are not set")
+            }
+            TangaraError::MissingInterface { type_id, id } => {
+                write!(f, "Interface with id {id} is not implemented by type {type_id}")
+            }
+            TangaraError::MissingTypeOf(type_id) => {
+                write!(f, "Runtime type getter of type {type_id} is not set")
+            }
+            TangaraError::MissingImplementation(id) => {
+                write!(f, "Implementation of interface {id} is not registered by host")
+            }
+            TangaraError::IncompatibleVersion { package, required, loaded } => {
+                write!(f, "Package {package} has version {loaded} which is incompatible with required {required}")
+            }
+            TangaraError::AbiMismatch { package, required, loaded } => {
+                write!(f, "Package {package} has ABI fingerprint {loaded:#x} but {required:#x} is required")
+            }
+            TangaraError::LoadLibrary { path, message } => {
+                write!(f, "Failed to load library {path}: {message}")
+            }
+            TangaraError::MissingLibrary(path) => {
+                write!(f, "Library {path} wasn't loaded by runtime")
+            }
+            TangaraError::LibraryAlreadyLoaded(path) => {
+                write!(f, "Library {path} is already loaded by runtime")
+            }
+            TangaraError::PackageAlreadyLoaded { path, package } => {
+                write!(f, "Library {path} registers package {package} which is already loaded")
+            }
+            TangaraError::InvalidMetadata(message) => {
+                write!(f, "Invalid package metadata: {message}")
+            }
+            TangaraError::AllocatorMismatch => {
+                write!(f, "Allocator of context differs from allocator already used by library")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TangaraError {}
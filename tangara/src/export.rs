@@ -0,0 +1,121 @@
+//! Library combined from modules exported by `#[tangara::export]`.
+//! Every module has its own entrypoint, [export_library](crate::export_library) exports
+//! one `tgLoad` and `tgMetadata` which put types of all modules into package of library.
+
+#[doc(hidden)]
+pub use once_cell::sync::Lazy;
+#[doc(hidden)]
+pub use tangara_highlevel::binary::package_to_bytes;
+use tangara_highlevel::{generate_abi_fingerprint, Package};
+use crate::context::Context;
+use crate::error::TangaraError;
+use crate::ffi::{FnLoad, FnMetadata};
+
+/// Entrypoint of module generated by `#[tangara::export]`
+#[derive(Copy, Clone)]
+pub struct ExportedModule {
+    /// Registers types of module
+    pub load: FnLoad,
+    /// Returns package with types of module only
+    pub metadata: FnMetadata
+}
+
+/// Package of library: types and dependencies of all modules with ABI fingerprint of all types
+pub fn merge_metadata(modules: &[ExportedModule]) -> Result<Package, TangaraError> {
+    let mut library: Option<Package> = None;
+    for module in modules {
+        // metadata of module is static data of this library
+        let package = unsafe { (module.metadata)().read_package() }?;
+        match &mut library {
+            Some(library) => {
+                library.types.extend(package.types);
+                for dependency in package.dependencies {
+                    if library.dependencies.iter().all(|dep| dep.id != dependency.id) {
+                        library.dependencies.push(dependency);
+                    }
+                }
+            }
+            None => library = Some(package)
+        }
+    }
+    let mut library = library.ok_or_else(|| TangaraError::InvalidMetadata("Library has no exported modules".to_string()))?;
+    library.types.sort_by_key(|t| t.id);
+    library.abi = generate_abi_fingerprint(&library.types);
+    Ok(library)
+}
+
+/// Register types of all modules in one package
+pub fn load_modules(ctx: &mut Context, modules: &[ExportedModule]) {
+    for module in modules {
+        // entrypoint of module creates package again, so it's registered apart and merged then
+        let mut module_ctx = ctx.without_packages();
+        (module.load)(&mut module_ctx);
+        if let Some(err) = module_ctx.take_load_error() {
+            ctx.reject_load(err);
+            return;
+        }
+        ctx.add_module_packages(module_ctx);
+    }
+    let library = merge_metadata(modules).expect("Metadata of exported modules must be valid");
+    if let Some(package) = ctx.get_package_mut(library.id) {
+        // every module sets fingerprint of its own types
+        package.set_abi(library.abi);
+    }
+}
+
+/// Export `tgLoad` and `tgMetadata` of library made of modules with `#[tangara::export]`.
+/// It's invoked once in root of crate with names of all exported modules.
+///
+/// ```ignore
+/// #[tangara::export]
+/// mod shapes { ... }
+///
+/// #[tangara::export]
+/// mod colors { ... }
+///
+/// tangara::export_library!(shapes, colors);
+/// ```
+#[macro_export]
+macro_rules! export_library {
+    ($($module:ident),+ $(,)?) => {
+        /// Modules of library exported by `#[tangara::export]`
+        const TANGARA_MODULES: &[$crate::export::ExportedModule] = &[$(
+            $crate::export::ExportedModule {
+                load: $module::bindings::tgLoad,
+                metadata: $module::bindings::tgMetadata
+            }
+        ),+];
+
+        #[no_mangle]
+        pub extern "C" fn tgLoad(ctx: &mut $crate::context::Context) {
+            $crate::export::load_modules(ctx, TANGARA_MODULES);
+        }
+
+        #[no_mangle]
+        pub extern "C" fn tgMetadata() -> $crate::metadata::Metadata {
+            static METADATA: $crate::export::Lazy<Vec<u8>> = $crate::export::Lazy::new(|| {
+                let package = $crate::export::merge_metadata(TANGARA_MODULES)
+                    .expect("Metadata of exported modules must be valid");
+                $crate::export::package_to_bytes(&package)
+            });
+            $crate::metadata::Metadata::new(&METADATA)
+        }
+    };
+}
+
+/// Check at compile time that `#[tangara::export]` module is in the path its namespace was made from
+#[doc(hidden)]
+pub const fn is_module_path(path: &str, expected: &str) -> bool {
+    let (path, expected) = (path.as_bytes(), expected.as_bytes());
+    if path.len() != expected.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < path.len() {
+        if path[i] != expected[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
@@ -0,0 +1,458 @@
+//! C API of Tangara runtime for hosts written not in Rust.
+//! Header `include/tangara.h` is generated from this module by `cargo xtask header`.
+//!
+//! Every pointer passed to these functions must be null or got from this API and still alive.
+#![allow(clippy::missing_safety_doc)]
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use std::sync::Arc;
+use crate::allocator::Allocator;
+use crate::context::{Context, Fn, FnDtor, FnTypeOf, FuncTable, Implementation, Property, Ptr, StaticProperty, TypeTable, Variants, Version, VTable};
+use crate::error::TangaraError;
+use crate::metadata::Metadata;
+use crate::runtime::Runtime;
+
+/// Owned Rust string which is `Tangara.Std.String` in packages.
+/// It's opaque for C, so its bytes are got by `tg_string_as_str`.
+#[repr(transparent)]
+pub struct RustString(String);
+
+/// Borrowed UTF-8 string which is `Tangara.Std.Str` in packages. It has layout of Rust `&str`
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Str {
+    /// Pointer to the first byte, string isn't null-terminated
+    pub ptr: *const u8,
+    /// Count of bytes
+    pub len: usize
+}
+
+/// Signature of `tgLoad` function exported by libraries with Tangara entrypoint
+pub type FnLoad = extern "C" fn(&mut Context);
+/// Signature of `tgMetadata` function exported by libraries with embedded package metadata
+pub type FnMetadata = extern "C" fn() -> Metadata;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(message));
+}
+
+/// Unwrap result of lookup or save its error for `tg_last_error` and return `default`
+fn handle<T>(result: Result<T, TangaraError>, default: T) -> T {
+    result.unwrap_or_else(|err| {
+        set_last_error(err.to_string());
+        default
+    })
+}
+
+/// Get message of the last error happened in this thread or null if there were no errors.
+/// Pointer is valid until the next failed call in this thread.
+#[no_mangle]
+pub extern "C" fn tg_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Create new runtime. Free it with `tg_runtime_free`.
+#[no_mangle]
+pub extern "C" fn tg_runtime_new() -> *mut Runtime {
+    Box::into_raw(Box::new(Runtime::new()))
+}
+
+/// Create new runtime with custom allocator for objects crossing library boundary.
+/// Free it with `tg_runtime_free`.
+#[no_mangle]
+pub extern "C" fn tg_runtime_new_with_allocator(allocator: Allocator) -> *mut Runtime {
+    Box::into_raw(Box::new(Runtime::with_allocator(allocator)))
+}
+
+/// Free runtime created by `tg_runtime_new`
+#[no_mangle]
+pub unsafe extern "C" fn tg_runtime_free(runtime: *mut Runtime) {
+    if !runtime.is_null() {
+        drop(Box::from_raw(runtime));
+    }
+}
+
+/// Get context of runtime. Pass it to `tgLoad` of library to load its packages.
+#[no_mangle]
+pub unsafe extern "C" fn tg_runtime_use_context(runtime: *mut Runtime) -> *mut Context {
+    match runtime.as_mut() {
+        Some(runtime) => runtime.use_context(),
+        None => {
+            set_last_error("Runtime is null".to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+fn with_library_path(path: *const c_char, f: impl FnOnce(&str) -> Result<(), TangaraError>) -> bool {
+    if path.is_null() {
+        set_last_error("Library path is null".to_string());
+        return false;
+    }
+    match unsafe { CStr::from_ptr(path) }.to_str() {
+        Ok(path) => handle(f(path).map(|_| true), false),
+        Err(_) => {
+            set_last_error("Library path is not valid UTF-8".to_string());
+            false
+        }
+    }
+}
+
+/// Load library by path and run its `tgLoad` on context of runtime
+#[no_mangle]
+pub unsafe extern "C" fn tg_runtime_load_library(runtime: *const Runtime, path: *const c_char) -> bool {
+    match runtime.as_ref() {
+        Some(runtime) => with_library_path(path, |path| runtime.load_library(path).map(|_| ())),
+        None => {
+            set_last_error("Runtime is null".to_string());
+            false
+        }
+    }
+}
+
+/// Remove packages of library loaded by `tg_runtime_load_library` from context of runtime
+#[no_mangle]
+pub unsafe extern "C" fn tg_runtime_unload_library(runtime: *const Runtime, path: *const c_char) -> bool {
+    match runtime.as_ref() {
+        Some(runtime) => with_library_path(path, |path| runtime.unload_library(path)),
+        None => {
+            set_last_error("Runtime is null".to_string());
+            false
+        }
+    }
+}
+
+/// Unload library and load it again. All pointers got from its packages become invalid.
+#[no_mangle]
+pub unsafe extern "C" fn tg_runtime_reload_library(runtime: *const Runtime, path: *const c_char) -> bool {
+    match runtime.as_ref() {
+        Some(runtime) => with_library_path(path, |path| runtime.reload_library(path).map(|_| ())),
+        None => {
+            set_last_error("Runtime is null".to_string());
+            false
+        }
+    }
+}
+
+/// Make everything loaded into context of runtime visible for other threads and get published context.
+/// Context stays alive until it's released by `tg_context_release`.
+#[no_mangle]
+pub unsafe extern "C" fn tg_runtime_publish(runtime: *const Runtime) -> *const Context {
+    match runtime.as_ref() {
+        Some(runtime) => Arc::into_raw(runtime.publish()),
+        None => {
+            set_last_error("Runtime is null".to_string());
+            ptr::null()
+        }
+    }
+}
+
+/// Get last published context of runtime, it doesn't change when runtime publishes new one.
+/// Context stays alive until it's released by `tg_context_release`.
+#[no_mangle]
+pub unsafe extern "C" fn tg_runtime_snapshot(runtime: *const Runtime) -> *const Context {
+    match runtime.as_ref() {
+        Some(runtime) => Arc::into_raw(runtime.snapshot()),
+        None => {
+            set_last_error("Runtime is null".to_string());
+            ptr::null()
+        }
+    }
+}
+
+/// Release context got by `tg_runtime_publish` or `tg_runtime_snapshot`
+#[no_mangle]
+pub unsafe extern "C" fn tg_context_release(ctx: *const Context) {
+    if !ctx.is_null() {
+        drop(Arc::from_raw(ctx));
+    }
+}
+
+/// Get allocator of context. Objects returned by library functions must be freed by it.
+#[no_mangle]
+pub unsafe extern "C" fn tg_context_get_allocator(ctx: *const Context) -> Allocator {
+    match ctx.as_ref() {
+        Some(ctx) => ctx.get_allocator(),
+        None => {
+            set_last_error("Context is null".to_string());
+            Allocator::SYSTEM
+        }
+    }
+}
+
+/// Create implementation of interface with given id for host object `data`.
+/// `dtor` (nullable) is called for `data` when implementation isn't used anymore.
+/// Add it to context with `tg_context_add_implementation`.
+/// `data` and `dtor` must be safe to use from any thread.
+#[no_mangle]
+pub unsafe extern "C" fn tg_implementation_new(id: u64, data: Ptr, dtor: Option<FnDtor>) -> *mut Implementation {
+    Box::into_raw(Box::new(Implementation::new(id, data, dtor)))
+}
+
+/// Add method of interface to implementation. Method gets `data` of implementation as first argument.
+#[no_mangle]
+pub unsafe extern "C" fn tg_implementation_add_method(implementation: *mut Implementation, id: u64, func: Fn) -> bool {
+    match implementation.as_mut() {
+        Some(implementation) => {
+            implementation.add_method(id, func);
+            true
+        }
+        None => {
+            set_last_error("Implementation is null".to_string());
+            false
+        }
+    }
+}
+
+/// Register implementation of interface in context before loading libraries which use it.
+/// Context takes ownership of implementation, so it mustn't be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn tg_context_add_implementation(ctx: *mut Context, implementation: *mut Implementation) -> bool {
+    if implementation.is_null() {
+        set_last_error("Implementation is null".to_string());
+        return false;
+    }
+    match ctx.as_mut() {
+        Some(ctx) => {
+            ctx.add_implementation(*Box::from_raw(implementation));
+            true
+        }
+        None => {
+            set_last_error("Context is null".to_string());
+            false
+        }
+    }
+}
+
+/// Get package by id or null if it wasn't loaded
+#[no_mangle]
+pub unsafe extern "C" fn tg_context_get_package(ctx: *const Context, id: u64) -> *const TypeTable {
+    match ctx.as_ref() {
+        Some(ctx) => handle(ctx.try_get_package(id).map(|pkg| pkg as *const TypeTable), ptr::null()),
+        None => {
+            set_last_error("Context is null".to_string());
+            ptr::null()
+        }
+    }
+}
+
+/// Check that loaded package is compatible with required version and ABI fingerprint
+#[no_mangle]
+pub unsafe extern "C" fn tg_package_check(pkg: *const TypeTable, version: Version, abi: u64) -> bool {
+    match pkg.as_ref() {
+        Some(pkg) => handle(pkg.check_compatibility(version, abi).map(|_| true), false),
+        None => {
+            set_last_error("Package is null".to_string());
+            false
+        }
+    }
+}
+
+/// Check that type of package is `base` class or inherits it
+#[no_mangle]
+pub unsafe extern "C" fn tg_package_is_subclass(pkg: *const TypeTable, id: u64, base: u64) -> bool {
+    match pkg.as_ref() {
+        Some(pkg) => pkg.is_subclass(id, base),
+        None => {
+            set_last_error("Package is null".to_string());
+            false
+        }
+    }
+}
+
+/// Get the most derived implementation of method for objects of type (usually got by `tg_type_get_type_of`)
+/// or null if neither type nor its base classes have it
+#[no_mangle]
+pub unsafe extern "C" fn tg_package_resolve_method(pkg: *const TypeTable, type_id: u64, id: u64) -> Option<Fn> {
+    match pkg.as_ref() {
+        Some(pkg) => handle(pkg.try_resolve_method(type_id, id).map(|method| Some(*method)), None),
+        None => {
+            set_last_error("Package is null".to_string());
+            None
+        }
+    }
+}
+
+/// Get type of package by id or null if it wasn't loaded
+#[no_mangle]
+pub unsafe extern "C" fn tg_package_get_type(pkg: *const TypeTable, id: u64) -> *const FuncTable {
+    match pkg.as_ref() {
+        Some(pkg) => handle(pkg.try_get_type(id).map(|t| t as *const FuncTable), ptr::null()),
+        None => {
+            set_last_error("Package is null".to_string());
+            ptr::null()
+        }
+    }
+}
+
+/// Get concrete instantiation of generic type by id or null if it wasn't registered
+#[no_mangle]
+pub unsafe extern "C" fn tg_package_get_instance(pkg: *const TypeTable, generic_id: u64, id: u64) -> *const FuncTable {
+    match pkg.as_ref() {
+        Some(pkg) => handle(pkg.try_get_instance(generic_id, id).map(|t| t as *const FuncTable), ptr::null()),
+        None => {
+            set_last_error("Package is null".to_string());
+            ptr::null()
+        }
+    }
+}
+
+/// Get constructor of type at given index or null if it doesn't exist
+#[no_mangle]
+pub unsafe extern "C" fn tg_type_get_ctor(t: *const FuncTable, index: usize) -> Option<Fn> {
+    match t.as_ref() {
+        Some(t) => handle(t.try_get_ctor(index).map(|ctor| Some(*ctor)), None),
+        None => {
+            set_last_error("Type is null".to_string());
+            None
+        }
+    }
+}
+
+/// Get destructor of type or null if it wasn't set
+#[no_mangle]
+pub unsafe extern "C" fn tg_type_get_dtor(t: *const FuncTable) -> Option<FnDtor> {
+    match t.as_ref() {
+        Some(t) => handle(t.try_get_dtor().map(Some), None),
+        None => {
+            set_last_error("Type is null".to_string());
+            None
+        }
+    }
+}
+
+/// Get method of type by id or null if it doesn't exist
+#[no_mangle]
+pub unsafe extern "C" fn tg_type_get_method(t: *const FuncTable, id: u64) -> Option<Fn> {
+    match t.as_ref() {
+        Some(t) => handle(t.try_get_method(id).map(|method| Some(*method)), None),
+        None => {
+            set_last_error("Type is null".to_string());
+            None
+        }
+    }
+}
+
+/// Get property of type by id or null if it doesn't exist
+#[no_mangle]
+pub unsafe extern "C" fn tg_type_get_property(t: *const FuncTable, id: u64) -> *const Property {
+    match t.as_ref() {
+        Some(t) => handle(t.try_get_property(id).map(|prop| prop as *const Property), ptr::null()),
+        None => {
+            set_last_error("Type is null".to_string());
+            ptr::null()
+        }
+    }
+}
+
+/// Get static property of type by id or null if it doesn't exist
+#[no_mangle]
+pub unsafe extern "C" fn tg_type_get_static(t: *const FuncTable, id: u64) -> *const StaticProperty {
+    match t.as_ref() {
+        Some(t) => handle(t.try_get_static(id).map(|prop| prop as *const StaticProperty), ptr::null()),
+        None => {
+            set_last_error("Type is null".to_string());
+            ptr::null()
+        }
+    }
+}
+
+/// Get variants of enum type or null if type isn't enum
+#[no_mangle]
+pub unsafe extern "C" fn tg_type_get_variants(t: *const FuncTable) -> *const Variants {
+    match t.as_ref() {
+        Some(t) => handle(t.try_get_variants().map(|variants| variants as *const Variants), ptr::null()),
+        None => {
+            set_last_error("Type is null".to_string());
+            ptr::null()
+        }
+    }
+}
+
+/// Get implementation of interface by type or null if type doesn't implement it
+#[no_mangle]
+pub unsafe extern "C" fn tg_type_get_interface(t: *const FuncTable, id: u64) -> *const VTable {
+    match t.as_ref() {
+        Some(t) => handle(t.try_get_interface(id).map(|vtable| vtable as *const VTable), ptr::null()),
+        None => {
+            set_last_error("Type is null".to_string());
+            ptr::null()
+        }
+    }
+}
+
+/// Get function returning id of runtime type of objects or null if type can't be inherited
+#[no_mangle]
+pub unsafe extern "C" fn tg_type_get_type_of(t: *const FuncTable) -> Option<FnTypeOf> {
+    match t.as_ref() {
+        Some(t) => handle(t.try_get_type_of().map(Some), None),
+        None => {
+            set_last_error("Type is null".to_string());
+            None
+        }
+    }
+}
+
+/// Get method of interface implementation by id or null if it doesn't exist
+#[no_mangle]
+pub unsafe extern "C" fn tg_vtable_get_method(vtable: *const VTable, id: u64) -> Option<Fn> {
+    match vtable.as_ref() {
+        Some(vtable) => handle(vtable.try_get_method(id).map(|method| Some(*method)), None),
+        None => {
+            set_last_error("Interface implementation is null".to_string());
+            None
+        }
+    }
+}
+
+/// Call function (constructor or method) with arguments written one by one in `args` buffer.
+/// Returns pointer on result (null for functions returning nothing).
+#[no_mangle]
+pub unsafe extern "C" fn tg_call(func: Option<Fn>, args_size: usize, args: *mut u8) -> Ptr {
+    match func {
+        Some(func) => func(args_size, args),
+        None => {
+            set_last_error("Called function is null".to_string());
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Destroy object using destructor of its type
+#[no_mangle]
+pub unsafe extern "C" fn tg_destroy(t: *const FuncTable, object: Ptr) -> bool {
+    match tg_type_get_dtor(t) {
+        Some(dtor) => {
+            dtor(object);
+            true
+        }
+        None => false
+    }
+}
+
+/// Get bytes of Rust string. They are borrowed from string and valid while it isn't changed or destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn tg_string_as_str(string: *const RustString) -> Str {
+    match string.as_ref() {
+        Some(string) => Str {
+            ptr: string.0.as_ptr(),
+            len: string.0.len()
+        },
+        None => {
+            set_last_error("String is null".to_string());
+            Str {
+                ptr: ptr::null(),
+                len: 0
+            }
+        }
+    }
+}
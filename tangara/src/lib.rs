@@ -1,2 +1,14 @@
+pub mod allocator;
 pub mod context;
-pub mod runtime;
\ No newline at end of file
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
+pub mod error;
+#[cfg(feature = "export")]
+pub mod export;
+pub mod ffi;
+pub mod metadata;
+pub mod runtime;
+
+/// Generate package and entrypoint of inline module at compile time, see [export_library]
+#[cfg(feature = "export")]
+pub use tangara_macros::export;
@@ -0,0 +1,78 @@
+//! Package metadata embedded into library by generated entrypoint.
+//! Library which exports `tgMetadata` describes itself, so host doesn't need `.tgjson` next to it.
+
+#[cfg(feature = "metadata")]
+use std::path::Path;
+#[cfg(feature = "metadata")]
+use libloading::Library;
+#[cfg(feature = "metadata")]
+use tangara_highlevel::Package;
+#[cfg(feature = "metadata")]
+use crate::error::TangaraError;
+#[cfg(feature = "metadata")]
+use crate::ffi::FnMetadata;
+
+/// Bytes of package in binary format returned by `tgMetadata`
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Metadata {
+    /// Pointer to the first byte of package
+    pub data: *const u8,
+    /// Count of bytes
+    pub size: usize
+}
+
+impl Metadata {
+    pub const fn new(bytes: &'static [u8]) -> Self {
+        Self {
+            data: bytes.as_ptr(),
+            size: bytes.len()
+        }
+    }
+
+    /// # Safety
+    /// Metadata must be got from loaded library and library mustn't be unloaded while bytes are used
+    pub unsafe fn as_bytes(&self) -> &[u8] {
+        if self.data.is_null() {
+            &[]
+        } else {
+            std::slice::from_raw_parts(self.data, self.size)
+        }
+    }
+
+    /// Decode package from metadata
+    ///
+    /// # Safety
+    /// Same as for [as_bytes](Metadata::as_bytes)
+    #[cfg(feature = "metadata")]
+    pub unsafe fn read_package(&self) -> Result<Package, TangaraError> {
+        tangara_highlevel::binary::package_from_bytes(self.as_bytes())
+            .map_err(|err| TangaraError::InvalidMetadata(err.to_string()))
+    }
+}
+
+/// Read package embedded into library at given path without running its `tgLoad`
+///
+/// # Safety
+/// Library initialization code is run and `tgMetadata` must have [FnMetadata] signature.
+#[cfg(feature = "metadata")]
+pub unsafe fn read_library_metadata<P: AsRef<Path>>(path: P) -> Result<Package, TangaraError> {
+    read_library_metadata_with(path, "tgMetadata")
+}
+
+/// Read package embedded into library which was generated with custom `metadata_name`
+///
+/// # Safety
+/// Library initialization code is run and function `name` must have [FnMetadata] signature.
+#[cfg(feature = "metadata")]
+pub unsafe fn read_library_metadata_with<P: AsRef<Path>>(path: P, name: &str) -> Result<Package, TangaraError> {
+    let path = path.as_ref();
+    let load_error = |err: libloading::Error| TangaraError::LoadLibrary {
+        path: path.display().to_string(),
+        message: err.to_string()
+    };
+    let library = Library::new(path).map_err(load_error)?;
+    let tgmetadata = *library.get::<FnMetadata>(name.as_bytes()).map_err(load_error)?;
+    // package is decoded into owned values, so library can be unloaded after it
+    tgmetadata().read_package()
+}
@@ -1,17 +1,206 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use libloading::Library;
+use crate::allocator::Allocator;
 use crate::context::Context;
+use crate::error::TangaraError;
+use crate::ffi::FnLoad;
 
+/// Owner of loaded packages which can be shared between threads.
+///
+/// Packages are registered into staging context (by [use_context](Runtime::use_context) or
+/// [register](Runtime::register)) and become visible for readers only after [publish](Runtime::publish).
+/// Readers take [snapshot](Runtime::snapshot) and look up functions without any locks.
+///
+/// Runtime can also load libraries by itself, then every package added by library's `tgLoad`
+/// keeps that library loaded until the package is removed from all contexts.
 pub struct Runtime {
-    context: Context
+    staging: Mutex<Context>,
+    published: RwLock<Arc<Context>>,
+    /// Ids of packages added by each loaded library
+    libraries: Mutex<HashMap<PathBuf, Vec<u64>>>
 }
 
 impl Runtime {
     pub fn new() -> Self {
         Self {
-            context: Context::new()
+            staging: Mutex::new(Context::new()),
+            published: RwLock::new(Arc::new(Context::new())),
+            libraries: Mutex::new(HashMap::new())
         }
     }
 
+    /// Create runtime which contexts use given allocator for objects crossing library boundary
+    pub fn with_allocator(allocator: Allocator) -> Self {
+        let mut runtime = Self::new();
+        runtime.use_context().set_allocator(allocator);
+        runtime.publish();
+        runtime
+    }
+
+    /// Get staging context for exclusive owner of runtime
     pub fn use_context(&mut self) -> &mut Context {
-        &mut self.context
+        self.staging.get_mut().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Register packages into staging context from any thread
+    pub fn register<R, F: FnOnce(&mut Context) -> R>(&self, register: F) -> R {
+        let mut staging = self.staging.lock().unwrap_or_else(|err| err.into_inner());
+        register(&mut staging)
+    }
+
+    /// Make everything registered to staging context visible for next snapshots
+    pub fn publish(&self) -> Arc<Context> {
+        let context = Arc::new(self.staging.lock().unwrap_or_else(|err| err.into_inner()).clone());
+        *self.published.write().unwrap_or_else(|err| err.into_inner()) = context.clone();
+        context
+    }
+
+    /// Load dynamic library and run its `tgLoad` on staging context.
+    /// Returns ids of added packages.
+    ///
+    /// Library can't be loaded twice by same path and can't register packages which are already loaded,
+    /// because their functions would point into library which isn't kept loaded by them.
+    ///
+    /// # Safety
+    /// Library initialization code is run and `tgLoad` must have [FnLoad] signature.
+    pub unsafe fn load_library<P: AsRef<Path>>(&self, path: P) -> Result<Vec<u64>, TangaraError> {
+        let path = path.as_ref();
+        let mut libraries = self.libraries.lock().unwrap_or_else(|err| err.into_inner());
+        if libraries.contains_key(&library_key(path)) {
+            return Err(TangaraError::LibraryAlreadyLoaded(path.display().to_string()));
+        }
+        let load_error = |err: libloading::Error| TangaraError::LoadLibrary {
+            path: path.display().to_string(),
+            message: err.to_string()
+        };
+        let library = Arc::new(Library::new(path).map_err(load_error)?);
+        let tgload = *library.get::<FnLoad>(b"tgLoad").map_err(load_error)?;
+        let packages = self.register_library(path, library, tgload)?;
+        libraries.insert(library_key(path), packages.clone());
+        Ok(packages)
+    }
+
+    /// Run `tgload` on context without packages and move added packages to staging context,
+    /// so each of them keeps `library` loaded. Staging context isn't changed if any of them is already loaded.
+    unsafe fn register_library(&self, path: &Path, library: Arc<Library>, tgload: FnLoad) -> Result<Vec<u64>, TangaraError> {
+        self.register(|ctx| {
+            let mut loaded = ctx.without_packages();
+            tgload(&mut loaded);
+            if let Some(err) = loaded.take_load_error() {
+                return Err(err);
+            }
+            let packages = loaded.get_package_ids();
+            if let Some(package) = packages.iter().find(|id| ctx.try_get_package(**id).is_ok()) {
+                return Err(TangaraError::PackageAlreadyLoaded {
+                    path: path.display().to_string(),
+                    package: *package
+                });
+            }
+            ctx.add_library_packages(loaded, library);
+            Ok(packages)
+        })
+    }
+
+    /// Remove packages of library from staging context.
+    /// Library is unloaded after publishing when all snapshots which use it are dropped.
+    pub fn unload_library<P: AsRef<Path>>(&self, path: P) -> Result<(), TangaraError> {
+        let path = path.as_ref();
+        let packages = self.libraries.lock().unwrap_or_else(|err| err.into_inner())
+            .remove(&library_key(path))
+            .ok_or_else(|| TangaraError::MissingLibrary(path.display().to_string()))?;
+        self.register(|ctx| {
+            for id in packages {
+                ctx.remove_package(id);
+            }
+        });
+        Ok(())
+    }
+
+    /// Unload library, publish context without it and load library again, then publish it.
+    /// Host bindings must be refreshed after that by generated `reload_{package}`.
+    ///
+    /// Snapshots taken before reload must be dropped before calling it,
+    /// otherwise old library stays loaded and system loader returns it again.
+    ///
+    /// # Safety
+    /// Same as for [load_library](Runtime::load_library).
+    pub unsafe fn reload_library<P: AsRef<Path>>(&self, path: P) -> Result<Vec<u64>, TangaraError> {
+        self.unload_library(&path)?;
+        self.publish();
+        let packages = self.load_library(path)?;
+        self.publish();
+        Ok(packages)
+    }
+
+    /// Get last published context. Snapshot stays unchanged even if new packages are published.
+    pub fn snapshot(&self) -> Arc<Context> {
+        self.published.read().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+}
+
+/// Same library can be passed by different paths, so existing ones are compared in canonical form
+fn library_key(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    extern "C" fn load_first(ctx: &mut Context) {
+        ctx.add_package(0);
+    }
+
+    extern "C" fn load_second(ctx: &mut Context) {
+        ctx.add_package(1);
+        ctx.add_package(0);
+    }
+
+    fn this_library() -> Arc<Library> {
+        #[cfg(unix)]
+        let library = libloading::os::unix::Library::this();
+        #[cfg(windows)]
+        let library = libloading::os::windows::Library::this().unwrap();
+        Arc::new(library.into())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn reject_loaded_packages() {
+        let rt = Runtime::new();
+        let library = this_library();
+        let packages = unsafe { rt.register_library(Path::new("first"), library.clone(), load_first) };
+        assert_eq!(packages, Ok(vec![0]));
+        assert_eq!(Arc::strong_count(&library), 2);
+
+        // second library can't replace table which keeps first one loaded
+        let second = this_library();
+        assert_eq!(
+            unsafe { rt.register_library(Path::new("second"), second.clone(), load_second) },
+            Err(TangaraError::PackageAlreadyLoaded { path: "second".to_string(), package: 0 })
+        );
+        assert_eq!(rt.register(|ctx| ctx.get_package_ids()), vec![0]);
+        assert_eq!(Arc::strong_count(&library), 2);
+        assert_eq!(Arc::strong_count(&second), 1);
+    }
+
+    #[test]
+    fn reject_loaded_path() {
+        let rt = Runtime::new();
+        let path = std::env::current_exe().unwrap();
+        rt.libraries.lock().unwrap().insert(library_key(&path), vec![0]);
+        assert_eq!(
+            unsafe { rt.load_library(&path) },
+            Err(TangaraError::LibraryAlreadyLoaded(path.display().to_string()))
+        );
+        // package ids of loaded library are kept
+        assert_eq!(rt.libraries.lock().unwrap().get(&library_key(&path)), Some(&vec![0]));
+    }
+}
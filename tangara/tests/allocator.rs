@@ -0,0 +1,80 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tangara::allocator::{class_type_of, Allocator, AllocatorCell};
+use tangara::context::Ptr;
+use tangara::error::TangaraError;
+use tangara::runtime::Runtime;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static FREED: AtomicUsize = AtomicUsize::new(0);
+
+extern "C" fn tracking_alloc(size: usize, align: usize) -> Ptr {
+    ALLOCATED.fetch_add(size, Ordering::SeqCst);
+    (Allocator::SYSTEM.alloc)(size, align)
+}
+
+extern "C" fn tracking_realloc(value: Ptr, old_size: usize, align: usize, new_size: usize) -> Ptr {
+    (Allocator::SYSTEM.realloc)(value, old_size, align, new_size)
+}
+
+extern "C" fn tracking_free(value: Ptr, size: usize, align: usize) {
+    FREED.fetch_add(size, Ordering::SeqCst);
+    (Allocator::SYSTEM.free)(value, size, align)
+}
+
+const TRACKING: Allocator = Allocator {
+    alloc: tracking_alloc,
+    realloc: tracking_realloc,
+    free: tracking_free
+};
+
+#[test]
+fn custom_allocator() {
+    let mut rt = Runtime::with_allocator(TRACKING);
+    let allocator = rt.snapshot().get_allocator();
+    assert_eq!(allocator.alloc as usize, tracking_alloc as *const () as usize);
+    assert_eq!(rt.use_context().get_allocator().free as usize, tracking_free as *const () as usize);
+
+    let number = allocator.new_object(42u64);
+    let name = allocator.new_object(String::from("Tangara"));
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst), 8 + std::mem::size_of::<String>());
+    assert_eq!(unsafe { allocator.take_object::<u64>(number) }, 42);
+    unsafe { allocator.delete_object::<String>(name) };
+    assert_eq!(FREED.load(Ordering::SeqCst), ALLOCATED.load(Ordering::SeqCst));
+
+    // zero-sized objects don't touch allocator
+    let unit = allocator.new_object(());
+    unsafe { allocator.take_object::<()>(unit) };
+    assert_eq!(ALLOCATED.load(Ordering::SeqCst), 8 + std::mem::size_of::<String>());
+}
+
+#[test]
+fn allocator_cell() {
+    let cell = AllocatorCell::new();
+    // system allocator is used until cell is set
+    assert!(cell.is_same(&Allocator::SYSTEM));
+    assert_eq!(cell.set(TRACKING), Ok(()));
+    assert_eq!(cell.set(TRACKING), Ok(()));
+    assert!(cell.is_same(&TRACKING));
+    assert_eq!(cell.set(Allocator::SYSTEM), Err(TangaraError::AllocatorMismatch));
+    assert!(cell.is_same(&TRACKING));
+}
+
+#[test]
+fn class_objects() {
+    #[repr(align(32))]
+    struct Aligned(u8);
+
+    let small = Allocator::SYSTEM.new_class_object(7u16, 1);
+    let aligned = Allocator::SYSTEM.new_class_object(Aligned(3), 2);
+    assert_eq!(aligned as usize % 32, 0);
+    // runtime type is kept by object itself
+    assert_eq!(class_type_of(small), 1);
+    assert_eq!(class_type_of(aligned), 2);
+    assert_eq!(class_type_of(std::ptr::null_mut()), 0);
+    unsafe {
+        assert_eq!(*(small as *const u16), 7);
+        assert_eq!((*(aligned as *const Aligned)).0, 3);
+        Allocator::SYSTEM.delete_class_object::<u16>(small);
+        Allocator::SYSTEM.delete_class_object::<Aligned>(aligned);
+    }
+}
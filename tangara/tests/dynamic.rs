@@ -0,0 +1,140 @@
+#![cfg(feature = "dynamic")]
+#![allow(non_snake_case)]
+
+use std::ptr;
+use tangara::context::{Context, Ptr};
+use tangara::runtime::Runtime;
+use tangara::dynamic::InvokeError;
+use tangara_highlevel::builder::*;
+use tangara_highlevel::primitives::Primitive;
+use tangara_highlevel::{MethodKind, NamingConventions, Package, TypeKind, TypeRef, Value};
+
+struct Greeter {
+    name: String
+}
+
+fn read_args<T>(args: *mut u8, offset: &mut usize) -> T {
+    unsafe {
+        let value = ptr::read_unaligned(args.add(*offset) as *const T);
+        *offset += std::mem::size_of::<T>();
+        value
+    }
+}
+
+extern "C" fn Greeter_ctor0(_: usize, args: *mut u8) -> Ptr {
+    let mut offset = 0;
+    let name: &str = read_args(args, &mut offset);
+    Box::into_raw(Box::new(Greeter { name: name.to_string() })) as Ptr
+}
+
+extern "C" fn Greeter_dtor(this: Ptr) {
+    unsafe {
+        drop(Box::from_raw(this as *mut Greeter));
+    }
+}
+
+extern "C" fn Greeter_greet(_: usize, args: *mut u8) -> Ptr {
+    let mut offset = 0;
+    let this: Ptr = read_args(args, &mut offset);
+    let times: u32 = read_args(args, &mut offset);
+    let this = unsafe { &*(this as *const Greeter) };
+    Box::into_raw(Box::new(format!("Hello, {}", this.name).repeat(times as usize))) as Ptr
+}
+
+extern "C" fn Greeter_sum(_: usize, args: *mut u8) -> Ptr {
+    let mut offset = 0;
+    let a: i32 = read_args(args, &mut offset);
+    let b: f64 = read_args(args, &mut offset);
+    Box::into_raw(Box::new(a as f64 + b)) as Ptr
+}
+
+fn greeter_package() -> Package {
+    let builder = PackageBuilder::new("greetings", NamingConventions::rust());
+    let mut type_builder = create_class(builder.clone(), "Greeter");
+    type_builder
+        .add_constructor()
+            .arg_in(TypeRef::from("str"), "name")
+            .build()
+        .add_method("greet")
+            .arg(TypeRef::from("u32"), "times")
+            .return_type(TypeRef::from("String"))
+            .build()
+        .add_method("sum")
+            .set_kind(MethodKind::Static)
+            .arg(TypeRef::from("i32"), "a")
+            .arg(TypeRef::from("f64"), "b")
+            .return_type(TypeRef::from("f64"))
+            .build()
+        // the same function described by canonical names of Tangara.Std
+        .add_method("sum_std")
+            .set_kind(MethodKind::Static)
+            .arg(TypeRef::from("Tangara.Std.Int"), "a")
+            .arg(TypeRef::Id(Primitive::Double.type_id()), "b")
+            .return_type(TypeRef::Id(Primitive::Double.type_id()))
+            .build()
+        .add_method("label")
+            .set_kind(MethodKind::Static)
+            .arg(TypeRef::from("String"), "text")
+            .arg(TypeRef::from("u32"), "count")
+            .build();
+    type_builder.build();
+    let package = builder.borrow().build();
+    package
+}
+
+fn load(package: &Package, ctx: &mut Context) {
+    let greeter = &package.types[0];
+    let methods = match &greeter.kind {
+        TypeKind::Class { methods, .. } => methods,
+        _ => unreachable!()
+    };
+    let greeter_type = ctx.add_package(package.id).add_type(greeter.id);
+    greeter_type.add_ctor(Greeter_ctor0);
+    greeter_type.set_dtor(Greeter_dtor);
+    greeter_type.add_method(methods[0].id, Greeter_greet);
+    greeter_type.add_method(methods[1].id, Greeter_sum);
+    greeter_type.add_method(methods[2].id, Greeter_sum);
+    // it's never called, because its arguments are invalid
+    greeter_type.add_method(methods[3].id, Greeter_sum);
+}
+
+#[test]
+fn invoke() {
+    let package = greeter_package();
+    let mut runtime = Runtime::new();
+    let ctx = runtime.use_context();
+    load(&package, ctx);
+
+    let sum = ctx.invoke(&package, "Greeter", "sum", &[Value::Int(2), Value::Double(0.5)]);
+    assert!(matches!(sum, Ok(Value::Double(sum)) if sum == 2.5));
+
+    let greeter = ctx.construct(&package, "Greeter", &[Value::from("Tangara")]).unwrap();
+    let greeting = ctx.invoke_method(&package, greeter, "Greeter", "greet", &[Value::UInt(2)]);
+    assert!(matches!(greeting, Ok(Value::String(greeting)) if greeting == "Hello, TangaraHello, Tangara"));
+
+    assert_eq!(
+        ctx.invoke(&package, "Greeter", "greet", &[Value::UInt(2)]).unwrap_err(),
+        InvokeError::InvalidThis { type_name: "Greeter".to_string(), method_name: "greet".to_string() }
+    );
+    assert_eq!(
+        ctx.invoke(&package, "Greeter", "sum", &[Value::Bool(true), Value::Double(0.5)]).unwrap_err(),
+        InvokeError::ArgumentMismatch { index: 0, expected: "i32".to_string() }
+    );
+    let sum = ctx.invoke(&package, "Greeter", "sum_std", &[Value::Int(2), Value::Double(0.5)]);
+    assert!(matches!(sum, Ok(Value::Double(sum)) if sum == 2.5));
+    assert_eq!(
+        ctx.invoke(&package, "Greeter", "sum_std", &[Value::Double(0.5), Value::Double(0.5)]).unwrap_err(),
+        InvokeError::ArgumentMismatch { index: 0, expected: "Tangara.Std.Int".to_string() }
+    );
+    // string moved into arguments is dropped when the next argument is invalid
+    assert_eq!(
+        ctx.invoke(&package, "Greeter", "label", &[Value::from("label"), Value::Bool(true)]).unwrap_err(),
+        InvokeError::ArgumentMismatch { index: 1, expected: "u32".to_string() }
+    );
+    assert_eq!(
+        ctx.invoke(&package, "Farewell", "sum", &[]).unwrap_err(),
+        InvokeError::TypeNotFound("Farewell".to_string())
+    );
+
+    (ctx.get_package(package.id).get_type(package.types[0].id).get_dtor())(greeter);
+}
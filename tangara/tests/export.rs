@@ -0,0 +1,105 @@
+#![cfg(all(feature = "export", feature = "metadata"))]
+
+use std::ptr;
+use tangara::context::Ptr;
+use tangara::runtime::Runtime;
+use tangara_highlevel::{generate_member_id, TypeKind};
+
+#[tangara::export]
+mod shapes {
+    pub struct Rect {
+        pub width: u32,
+        pub height: u32
+    }
+
+    impl Rect {
+        pub fn new(width: u32, height: u32) -> Self {
+            Self { width, height }
+        }
+
+        #[tangara(ctor)]
+        pub fn square() -> Self {
+            Self::new(2, 2)
+        }
+
+        pub fn area(&self) -> u32 {
+            self.width * self.height
+        }
+
+        #[tangara(skip)]
+        pub fn hidden(&self) {}
+    }
+}
+
+#[tangara::export]
+mod colors {
+    pub struct Color {
+        pub red: u8,
+        pub green: u8,
+        pub blue: u8
+    }
+
+    impl Color {
+        pub fn gray(value: u8) -> Self {
+            Self { red: value, green: value, blue: value }
+        }
+    }
+}
+
+tangara::export_library!(shapes, colors);
+
+#[test]
+fn export_module() {
+    let package = unsafe { shapes::bindings::tgMetadata().read_package() }.unwrap();
+    assert_eq!(package.version.to_string(), env!("CARGO_PKG_VERSION"));
+    let rect = package.types.iter().find(|t| t.name == "Rect").unwrap();
+    assert!(rect.namespace.ends_with(".shapes"));
+    let mut rt = Runtime::new();
+    let ctx = rt.use_context();
+    shapes::bindings::tgLoad(ctx);
+    let rect_type = ctx.get_package(package.id).get_type(rect.id);
+    // constructors are registered in order of functions in 'impl'
+    assert!(rect_type.try_get_ctor(2).is_err());
+    assert!(rect_type.try_get_method(generate_member_id("hidden")).is_err());
+    shapes::Rect::square().hidden();
+    let mut square = rect_type.get_ctor(1)(0, ptr::null_mut());
+    let area = rect_type.get_method(generate_member_id("area"))(
+        std::mem::size_of::<Ptr>(),
+        &mut square as *mut Ptr as *mut u8
+    );
+    assert_eq!(unsafe { *(area as *const u32) }, 4);
+    rect_type.get_dtor()(square);
+}
+
+#[test]
+fn export_library() {
+    let package = unsafe { tgMetadata().read_package() }.unwrap();
+    let shapes = unsafe { shapes::bindings::tgMetadata().read_package() }.unwrap();
+    let colors = unsafe { colors::bindings::tgMetadata().read_package() }.unwrap();
+    // modules are parts of one package
+    assert_eq!(package.id, shapes.id);
+    assert_eq!(package.id, colors.id);
+    let rect = package.types.iter().find(|t| t.name == "Rect").unwrap();
+    let color = package.types.iter().find(|t| t.name == "Color").unwrap();
+    assert!(rect.namespace.ends_with(".shapes"));
+    assert!(color.namespace.ends_with(".colors"));
+
+    let mut rt = Runtime::new();
+    let ctx = rt.use_context();
+    tgLoad(ctx);
+    let pkg = ctx.get_package(package.id);
+    assert_eq!(pkg.get_abi(), package.abi);
+    let color_type = pkg.get_type(color.id);
+    let mut value = 7u8;
+    let gray_id = match &color.kind {
+        TypeKind::Class { methods, .. } => methods.iter().find(|method| method.name == "gray").unwrap().id,
+        _ => unreachable!()
+    };
+    let gray = color_type.get_method(gray_id)(
+        std::mem::size_of::<u8>(),
+        &mut value as *mut u8
+    );
+    assert_eq!(unsafe { (*(gray as *const colors::Color)).green }, 7);
+    color_type.get_dtor()(gray);
+    assert!(pkg.try_get_type(rect.id).is_ok());
+}
@@ -0,0 +1,111 @@
+#![allow(non_snake_case)]
+
+use std::ffi::CStr;
+use std::ptr;
+use tangara::context::{Context, Ptr};
+use tangara::ffi::*;
+
+extern "C" fn Counter_ctor0(_: usize, _: *mut u8) -> Ptr {
+    Box::into_raw(Box::new(0u32)) as Ptr
+}
+
+extern "C" fn Counter_dtor(value: Ptr) {
+    unsafe {
+        drop(Box::from_raw(value as *mut u32));
+    }
+}
+
+extern "C" fn Counter_add(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let this = *(args_slice.as_mut_ptr() as *mut Ptr) as *mut u32;
+        let value: u32 = ptr::read(args_slice.as_mut_ptr().add(std::mem::size_of::<Ptr>()) as *const u32);
+        *this += value;
+        Box::into_raw(Box::new(*this)) as Ptr
+    }
+}
+
+/// Entrypoint like the one `EntrypointGenerator` writes
+extern "C" fn tgLoad(ctx: &mut Context) {
+    let counter_type = ctx.add_package(7).add_type(1);
+    counter_type.add_ctor(Counter_ctor0);
+    counter_type.set_dtor(Counter_dtor);
+    counter_type.add_method(2, Counter_add);
+    counter_type.add_interface(4).add_method(5, Counter_add);
+}
+
+#[test]
+fn c_api() {
+    unsafe {
+        let runtime = tg_runtime_new();
+        let ctx = tg_runtime_use_context(runtime);
+        let load: FnLoad = tgLoad;
+        load(&mut *ctx);
+
+        assert!(tg_context_get_package(ctx, 8).is_null());
+        let message = CStr::from_ptr(tg_last_error()).to_str().unwrap();
+        assert_eq!(message, "Package by id 8 not found");
+
+        let pkg = tg_context_get_package(ctx, 7);
+        assert!(!pkg.is_null());
+        let counter_type = tg_package_get_type(pkg, 1);
+        assert!(!counter_type.is_null());
+        assert!(tg_type_get_method(counter_type, 3).is_none());
+        assert!(tg_type_get_property(counter_type, 3).is_null());
+        assert!(tg_type_get_interface(counter_type, 3).is_null());
+        let counter_vtable = tg_type_get_interface(counter_type, 4);
+        assert!(tg_vtable_get_method(counter_vtable, 2).is_none());
+
+        let object = tg_call(tg_type_get_ctor(counter_type, 0), 0, ptr::null_mut());
+        let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<u32>();
+        let mut args_buf = vec![0u8; args_size];
+        let args_ptr = args_buf.as_mut_ptr();
+        *(args_ptr as *mut Ptr) = object;
+        *(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut u32) = 5;
+        let result = tg_call(tg_type_get_method(counter_type, 2), args_size, args_ptr);
+        assert_eq!(*Box::from_raw(result as *mut u32), 5);
+        let result = tg_call(tg_vtable_get_method(counter_vtable, 5), args_size, args_ptr);
+        assert_eq!(*Box::from_raw(result as *mut u32), 10);
+
+        assert!(tg_destroy(counter_type, object));
+
+        // host object implementing interface for libraries
+        assert!(!tg_implementation_add_method(ptr::null_mut(), 5, Counter_add));
+        let implementation = tg_implementation_new(4, Counter_ctor0(0, ptr::null_mut()), Some(Counter_dtor));
+        assert!(tg_implementation_add_method(implementation, 5, Counter_add));
+        assert!(tg_context_add_implementation(ctx, implementation));
+        let implementation = (*ctx).get_implementation(4);
+        *(args_ptr as *mut Ptr) = implementation.get_data();
+        let result = tg_call(Some(*implementation.get_vtable().get_method(5)), args_size, args_ptr);
+        assert_eq!(*Box::from_raw(result as *mut u32), 5);
+
+        // published context is kept by host after runtime is freed
+        let empty = tg_runtime_snapshot(runtime);
+        assert!(tg_context_get_package(empty, 7).is_null());
+        tg_context_release(empty);
+        let published = tg_runtime_publish(runtime);
+        let snapshot = tg_runtime_snapshot(runtime);
+        tg_runtime_free(runtime);
+        assert!(!tg_context_get_package(published, 7).is_null());
+        assert!(!tg_context_get_package(snapshot, 7).is_null());
+        tg_context_release(published);
+        tg_context_release(snapshot);
+        assert!(tg_runtime_publish(ptr::null()).is_null());
+    }
+}
+
+#[test]
+fn strings() {
+    unsafe {
+        // functions get pointers to strings of Rust, so C reads them through these types
+        let owned = String::from("Tangara");
+        let bytes = tg_string_as_str(&owned as *const String as *const RustString);
+        assert_eq!(std::slice::from_raw_parts(bytes.ptr, bytes.len), owned.as_bytes());
+        let borrowed = "interop";
+        let bytes = *(&borrowed as *const &str as *const Str);
+        assert_eq!(std::slice::from_raw_parts(bytes.ptr, bytes.len), borrowed.as_bytes());
+
+        assert!(tg_string_as_str(ptr::null()).ptr.is_null());
+        assert!(!tg_last_error().is_null());
+    }
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "metadata")]
+
+use tangara::error::TangaraError;
+use tangara::metadata::{read_library_metadata, read_library_metadata_with, Metadata};
+use tangara_highlevel::binary::package_to_bytes;
+use tangara_highlevel::builder::*;
+use tangara_highlevel::{NamingConventions, TypeRef, Version};
+
+#[test]
+fn embedded_metadata() {
+    let builder = PackageBuilder::new("geometry", NamingConventions::rust());
+    builder.borrow_mut().set_version(Version::new(0, 2, 0));
+    let mut point = create_struct(builder.clone(), "Point");
+    point.add_field(TypeRef::from("f32"), "x").build();
+    point.build();
+    let package = builder.borrow().build();
+    // generated library keeps metadata in static bytes
+    let bytes: &'static [u8] = Box::leak(package_to_bytes(&package).into_boxed_slice());
+    let metadata = Metadata::new(bytes);
+
+    let decoded = unsafe { metadata.read_package() }.unwrap();
+    assert_eq!(decoded.name, "geometry");
+    assert_eq!(decoded.version, Version::new(0, 2, 0));
+    assert_eq!(decoded.abi, package.abi);
+    assert_eq!(decoded.types[0].id, package.types[0].id);
+
+    let invalid = Metadata::new(&bytes[..bytes.len() / 2]);
+    assert!(matches!(unsafe { invalid.read_package() }, Err(TangaraError::InvalidMetadata(_))));
+    assert!(matches!(
+        unsafe { read_library_metadata("not_existing_tangara_library") },
+        Err(TangaraError::LoadLibrary { .. })
+    ));
+    assert!(matches!(
+        unsafe { read_library_metadata_with("not_existing_tangara_library", "myMetadata") },
+        Err(TangaraError::LoadLibrary { .. })
+    ));
+}
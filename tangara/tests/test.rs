@@ -1,6 +1,10 @@
+#![allow(non_snake_case)]
+
 use std::alloc::{dealloc, Layout};
 use std::ptr;
-use tangara::context::{Property, Ptr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tangara::context::{Implementation, Property, Ptr, Variants, Version};
+use tangara::error::TangaraError;
 use tangara::runtime::Runtime;
 
 enum MyEnum {
@@ -57,6 +61,22 @@ extern "C" fn MyEnum_dtor(value: Ptr) {
     }
 }
 
+extern "C" fn MyEnum_tag(value: Ptr) -> u64 {
+    match unsafe { &*(value as *const MyEnum) } {
+        MyEnum::Unit => 0,
+        MyEnum::Vec3(..) => 1
+    }
+}
+
+extern "C" fn MyEnum_field(value: Ptr, id: u64) -> Ptr {
+    match (unsafe { &*(value as *const MyEnum) }, id) {
+        (MyEnum::Vec3(x, _, _), 2) => x as *const f32 as Ptr,
+        (MyEnum::Vec3(_, y, _), 3) => y as *const f32 as Ptr,
+        (MyEnum::Vec3(_, _, z), 4) => z as *const f32 as Ptr,
+        _ => ptr::null_mut()
+    }
+}
+
 extern "C" fn MyStruct_ctor0(_: usize, _: *mut u8) -> Ptr {
     let value = Box::new(MyStruct::new());
     Box::into_raw(value) as *mut u8
@@ -95,6 +115,40 @@ extern "C" fn MyStruct_get_name(this: Ptr) -> Ptr {
     }
 }
 
+/// Base class stores id of object's runtime type as its first field
+extern "C" fn Shape_type_of(value: Ptr) -> u64 {
+    unsafe { *(value as *const u64) }
+}
+
+extern "C" fn Shape_sides(_: usize, _: *mut u8) -> Ptr {
+    Box::into_raw(Box::new(0u32)) as Ptr
+}
+
+extern "C" fn Square_sides(_: usize, _: *mut u8) -> Ptr {
+    Box::into_raw(Box::new(4u32)) as Ptr
+}
+
+static LOGGER_DROPPED: AtomicBool = AtomicBool::new(false);
+
+/// Method of interface implemented by host: counts logged messages
+extern "C" fn Logger_log(args_size: usize, args: *mut u8) -> Ptr {
+    unsafe {
+        let args_slice = std::slice::from_raw_parts_mut(args, args_size);
+        let this: *mut u32 = *(args_slice.as_mut_ptr() as *mut Ptr) as *mut u32;
+        let message: String = ptr::read(args_slice.as_mut_ptr().add(std::mem::size_of::<Ptr>()) as *const String);
+        println!("[{}] {}", *this, message);
+        *this += 1;
+        Box::into_raw(Box::new(*this)) as Ptr
+    }
+}
+
+extern "C" fn Logger_dtor(value: Ptr) {
+    unsafe {
+        drop(Box::from_raw(value as *mut u32));
+    }
+    LOGGER_DROPPED.store(true, Ordering::SeqCst);
+}
+
 #[test]
 fn it_works() {
     let mut rt = Runtime::new();
@@ -200,4 +254,269 @@ fn it_works() {
         // Destroy the object
         dtor(object);
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn missing_symbols() {
+    let mut rt = Runtime::new();
+    let ctx = rt.use_context();
+    {
+        let my_pkg = ctx.add_package(0);
+        let my_struct_type = my_pkg.add_type(0);
+        my_struct_type.add_ctor(MyStruct_ctor0);
+        my_struct_type.add_method(0, MyStruct_repeat_name);
+    }
+    assert_eq!(ctx.try_get_package(1).err(), Some(TangaraError::MissingPackage(1)));
+    let my_pkg = ctx.try_get_package(0).unwrap();
+    assert_eq!(my_pkg.try_get_type(1).err(), Some(TangaraError::MissingType { package: 0, id: 1 }));
+    let my_struct_type = my_pkg.try_get_type(0).unwrap();
+    assert!(my_struct_type.try_get_ctor(0).is_ok());
+    assert_eq!(my_struct_type.try_get_ctor(1).err(), Some(TangaraError::MissingConstructor { type_id: 0, index: 1 }));
+    assert_eq!(my_struct_type.try_get_dtor().err(), Some(TangaraError::MissingDestructor(0)));
+    assert!(my_struct_type.try_get_method(0).is_ok());
+    assert_eq!(my_struct_type.try_get_method(1).err(), Some(TangaraError::MissingMethod { type_id: 0, id: 1 }));
+    assert_eq!(my_struct_type.try_get_property(1).err(), Some(TangaraError::MissingProperty { type_id: 0, id: 1 }));
+    assert_eq!(my_struct_type.try_get_static(1).err(), Some(TangaraError::MissingStatic { type_id: 0, id: 1 }));
+    assert_eq!(my_struct_type.try_get_variants().err(), Some(TangaraError::MissingVariants(0)));
+    assert_eq!(my_struct_type.try_get_interface(2).err(), Some(TangaraError::MissingInterface { type_id: 0, id: 2 }));
+    assert_eq!(ctx.try_get_implementation(2).err(), Some(TangaraError::MissingImplementation(2)));
+    assert_eq!(my_struct_type.try_get_type_of().err(), Some(TangaraError::MissingTypeOf(0)));
+    assert_eq!(
+        TangaraError::MissingMethod { type_id: 0, id: 1 }.to_string(),
+        "Method with id 1 is not found in type 0"
+    );
+}
+
+#[test]
+fn enum_variants() {
+    let mut rt = Runtime::new();
+    let ctx = rt.use_context();
+    {
+        let my_enum_type = ctx.add_package(0).add_type(1);
+        my_enum_type.set_dtor(MyEnum_dtor);
+        my_enum_type.add_method(0, MyEnum_Unit);
+        my_enum_type.set_variants(Variants {
+            tag: MyEnum_tag,
+            field: MyEnum_field
+        });
+    }
+    let my_enum_type = ctx.get_package(0).get_type(1);
+    let variants = my_enum_type.get_variants();
+
+    let unit = my_enum_type.get_method(0)(0, ptr::null_mut());
+    assert_eq!((variants.tag)(unit), 0);
+    assert!((variants.field)(unit, 2).is_null());
+    my_enum_type.get_dtor()(unit);
+
+    let vec3 = Box::into_raw(Box::new(MyEnum::Vec3(1.0, 2.0, 3.0))) as Ptr;
+    assert_eq!((variants.tag)(vec3), 1);
+    let fields = [2, 3, 4].map(|id| unsafe { *((variants.field)(vec3, id) as *const f32) });
+    assert_eq!(fields, [1.0, 2.0, 3.0]);
+    assert!((variants.field)(vec3, 5).is_null());
+    my_enum_type.get_dtor()(vec3);
+}
+
+#[test]
+fn interface_vtables() {
+    let mut rt = Runtime::new();
+    let ctx = rt.use_context();
+    {
+        let my_struct_type = ctx.add_package(0).add_type(0);
+        my_struct_type.add_ctor(MyStruct_ctor0);
+        my_struct_type.set_dtor(MyStruct_dtor);
+        // the same function can be method of type and of interface implemented by it
+        my_struct_type.add_method(0, MyStruct_repeat_name);
+        my_struct_type.add_interface(5).add_method(6, MyStruct_repeat_name);
+    }
+    let my_struct_type = ctx.get_package(0).get_type(0);
+    assert!(my_struct_type.implements(5));
+    assert!(!my_struct_type.implements(6));
+    let vtable = my_struct_type.get_interface(5);
+    assert_eq!(vtable.get_id(), 5);
+    assert_eq!(vtable.try_get_method(0).err(), Some(TangaraError::MissingMethod { type_id: 5, id: 0 }));
+
+    let object = my_struct_type.get_ctor(0)(0, ptr::null_mut());
+    let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<u32>();
+    let mut args_buf = vec![0u8; args_size];
+    let args_ptr = args_buf.as_mut_ptr();
+    unsafe {
+        *(args_ptr as *mut Ptr) = object;
+        *(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut u32) = 2;
+    }
+    assert!(vtable.get_method(6)(args_size, args_ptr).is_null());
+    my_struct_type.get_dtor()(object);
+}
+
+#[test]
+fn class_inheritance() {
+    let mut rt = Runtime::new();
+    let ctx = rt.use_context();
+    {
+        let my_pkg = ctx.add_package(0);
+        let shape_type = my_pkg.add_type(1);
+        shape_type.set_type_of(Shape_type_of);
+        shape_type.add_method(5, Shape_sides);
+        // square overrides method of shape and cube inherits it from square
+        let square_type = my_pkg.add_type(2);
+        square_type.set_parent(1);
+        square_type.set_type_of(Shape_type_of);
+        square_type.add_method(5, Square_sides);
+        my_pkg.add_type(3).set_parent(2);
+        my_pkg.add_type(4);
+    }
+    let my_pkg = ctx.get_package(0);
+    assert!(my_pkg.is_subclass(3, 1));
+    assert!(my_pkg.is_subclass(2, 2));
+    assert!(!my_pkg.is_subclass(1, 2));
+    assert!(!my_pkg.is_subclass(4, 1));
+    assert_eq!(my_pkg.get_type(3).get_parent(), Some(2));
+
+    let cube = Box::into_raw(Box::new(3u64)) as Ptr;
+    let runtime_type = my_pkg.get_type(1).get_type_of()(cube);
+    assert_eq!(runtime_type, 3);
+    let sides = my_pkg.resolve_method(runtime_type, 5)(0, ptr::null_mut());
+    assert_eq!(*unsafe { Box::from_raw(sides as *mut u32) }, 4);
+    let sides = my_pkg.resolve_method(1, 5)(0, ptr::null_mut());
+    assert_eq!(*unsafe { Box::from_raw(sides as *mut u32) }, 0);
+    assert_eq!(my_pkg.try_resolve_method(4, 5).err(), Some(TangaraError::MissingMethod { type_id: 4, id: 5 }));
+    assert_eq!(my_pkg.try_resolve_method(6, 5).err(), Some(TangaraError::MissingType { package: 0, id: 6 }));
+    drop(unsafe { Box::from_raw(cube as *mut u64) });
+}
+
+#[test]
+fn generic_instances() {
+    let mut rt = Runtime::new();
+    let ctx = rt.use_context();
+    {
+        let my_pkg = ctx.add_package(0);
+        my_pkg.add_instance(1, 2).add_ctor(MyStruct_ctor0);
+        my_pkg.add_instance(1, 3);
+        my_pkg.add_type(4);
+    }
+    let my_pkg = ctx.get_package(0);
+    let mut instances = my_pkg.get_instances(1);
+    instances.sort();
+    assert_eq!(instances, vec![2, 3]);
+    assert_eq!(my_pkg.get_type(2).get_generic(), Some(1));
+    assert_eq!(my_pkg.get_type(4).get_generic(), None);
+    let object = my_pkg.get_instance(1, 2).get_ctor(0)(0, ptr::null_mut());
+    MyStruct_dtor(object);
+    assert_eq!(my_pkg.try_get_instance(1, 4).err(), Some(TangaraError::MissingInstance { generic_id: 1, id: 4 }));
+    assert_eq!(my_pkg.try_get_instance(3, 2).err(), Some(TangaraError::MissingInstance { generic_id: 3, id: 2 }));
+}
+
+#[test]
+fn host_implementations() {
+    let rt = Runtime::new();
+    rt.register(|ctx| {
+        let mut logger = unsafe { Implementation::new(5, Box::into_raw(Box::new(0u32)) as Ptr, Some(Logger_dtor)) };
+        logger.add_method(6, Logger_log);
+        ctx.add_implementation(logger);
+    });
+    let snapshot = rt.publish();
+    let logger = snapshot.get_implementation(5).clone();
+    assert_eq!(logger.get_id(), 5);
+    for expected in 1..=2u32 {
+        let args_size = std::mem::size_of::<Ptr>() + std::mem::size_of::<String>();
+        let mut args_buf = vec![0u8; args_size];
+        let args_ptr = args_buf.as_mut_ptr();
+        unsafe {
+            *(args_ptr as *mut Ptr) = logger.get_data();
+            ptr::write(args_ptr.add(std::mem::size_of::<Ptr>()) as *mut String, "Hello".to_string());
+        }
+        let count = logger.get_vtable().get_method(6)(args_size, args_ptr);
+        assert_eq!(*unsafe { Box::from_raw(count as *mut u32) }, expected);
+    }
+    // data of implementation is destroyed only when nothing uses it
+    drop(snapshot);
+    drop(rt);
+    assert!(!LOGGER_DROPPED.load(Ordering::SeqCst));
+    drop(logger);
+    assert!(LOGGER_DROPPED.load(Ordering::SeqCst));
+}
+
+#[test]
+fn package_compatibility() {
+    let mut rt = Runtime::new();
+    let ctx = rt.use_context();
+    {
+        let my_pkg = ctx.add_package(0);
+        my_pkg.set_version(Version::new(1, 2, 0));
+        my_pkg.set_abi(42);
+    }
+    let my_pkg = ctx.get_package(0);
+    assert!(my_pkg.check_compatibility(Version::new(1, 1, 5), 42).is_ok());
+    assert_eq!(
+        my_pkg.check_compatibility(Version::new(1, 3, 0), 42).err(),
+        Some(TangaraError::IncompatibleVersion { package: 0, required: Version::new(1, 3, 0), loaded: Version::new(1, 2, 0) })
+    );
+    assert_eq!(
+        my_pkg.check_compatibility(Version::new(2, 0, 0), 42).err(),
+        Some(TangaraError::IncompatibleVersion { package: 0, required: Version::new(2, 0, 0), loaded: Version::new(1, 2, 0) })
+    );
+    assert_eq!(
+        my_pkg.check_compatibility(Version::new(1, 2, 0), 43).err(),
+        Some(TangaraError::AbiMismatch { package: 0, required: 43, loaded: 42 })
+    );
+    assert!(!Version::new(0, 2, 0).is_compatible_with(&Version::new(0, 1, 0)));
+}
+
+#[test]
+fn shared_runtime() {
+    let rt = std::sync::Arc::new(Runtime::new());
+    let empty = rt.snapshot();
+    rt.register(|ctx| {
+        let my_struct_type = ctx.add_package(0).add_type(0);
+        my_struct_type.add_ctor(MyStruct_ctor0);
+        my_struct_type.set_dtor(MyStruct_dtor);
+        my_struct_type.add_property(1, Property {
+            getter: MyStruct_get_name,
+            setter: Some(MyStruct_set_name)
+        });
+    });
+    // nothing is visible before publishing
+    assert!(rt.snapshot().try_get_package(0).is_err());
+    rt.publish();
+    assert!(empty.try_get_package(0).is_err());
+
+    let threads = (0..4).map(|_| {
+        let rt = rt.clone();
+        std::thread::spawn(move || {
+            let ctx = rt.snapshot();
+            let my_struct_type = ctx.get_package(0).get_type(0);
+            let object = my_struct_type.get_ctor(0)(0, ptr::null_mut());
+            let name = (my_struct_type.get_property(1).getter)(object);
+            let name = unsafe { Box::from_raw(name as *mut &str) }.to_string();
+            my_struct_type.get_dtor()(object);
+            name
+        })
+    }).collect::<Vec<_>>();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), "snaulX");
+    }
+}
+
+#[test]
+fn unload_packages() {
+    let rt = Runtime::new();
+    rt.register(|ctx| {
+        ctx.add_package(0).add_type(0).add_ctor(MyStruct_ctor0);
+        ctx.add_package(1);
+    });
+    let snapshot = rt.publish();
+    let removed = rt.register(|ctx| ctx.remove_package(0));
+    assert_eq!(removed.map(|pkg| pkg.get_id()), Some(0));
+    assert_eq!(rt.register(|ctx| ctx.get_package_ids()), vec![1]);
+    // published context isn't changed until next publishing
+    assert!(snapshot.try_get_package(0).is_ok());
+    assert!(rt.publish().try_get_package(0).is_err());
+
+    assert!(matches!(
+        unsafe { rt.load_library("not_existing_tangara_library") },
+        Err(TangaraError::LoadLibrary { .. })
+    ));
+    assert_eq!(
+        rt.unload_library("not_existing_tangara_library"),
+        Err(TangaraError::MissingLibrary("not_existing_tangara_library".to_string()))
+    );
+}
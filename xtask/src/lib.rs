@@ -0,0 +1,268 @@
+//! Generates `include/tangara.h` of `tangara` crate from the C API declared in its `src/ffi.rs`.
+
+use std::collections::HashSet;
+use std::path::Path;
+use syn::*;
+
+/// Files with types used by C API: opaque structs, `#[repr(C)]` structs and type aliases
+const TYPE_SOURCES: [&str; 5] = [
+    "src/allocator.rs", "src/context.rs", "src/metadata.rs", "src/runtime.rs", "src/ffi.rs"
+];
+const FFI_SOURCE: &str = "src/ffi.rs";
+/// Crate with types shared by runtime and metadata and file where they are declared
+const SHARED_CRATE: &str = "tangara_highlevel";
+const SHARED_SOURCE: &str = "../tangara-highlevel/src/lib.rs";
+/// Path of header relative to directory of `tangara` crate
+pub const HEADER_PATH: &str = "include/tangara.h";
+
+struct Names {
+    aliases: HashSet<String>,
+    structs: HashSet<String>
+}
+
+fn get_docs(attrs: &[syn::Attribute]) -> Vec<String> {
+    attrs.iter().filter_map(|attr| {
+        if let Meta::NameValue(name_value) = &attr.meta {
+            if name_value.path.is_ident("doc") {
+                if let Expr::Lit(ExprLit { lit: Lit::Str(doc), .. }) = &name_value.value {
+                    return Some(doc.value().trim().to_string());
+                }
+            }
+        }
+        None
+    }).collect()
+}
+
+fn write_docs(header: &mut String, attrs: &[syn::Attribute], indent: &str) {
+    for doc in get_docs(attrs) {
+        header.push_str(&format!("{indent}// {doc}\n"));
+    }
+}
+
+fn is_repr_c(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("repr") &&
+            attr.parse_args::<Ident>().map(|repr| repr == "C").unwrap_or(false)
+    })
+}
+
+/// Returns C type and, for function pointers, its arguments part which must be placed after the name
+fn get_c_type(t: &Type, names: &Names) -> (String, Option<String>) {
+    match t {
+        Type::Path(path_type) => {
+            let last_seg = path_type.path.segments.last().expect("Type path can't be empty");
+            let name = last_seg.ident.to_string();
+            if name == "Option" {
+                // nullable pointers are represented by pointers themselves
+                if let PathArguments::AngleBracketed(angle) = &last_seg.arguments {
+                    if let Some(GenericArgument::Type(inner)) = angle.args.first() {
+                        return get_c_type(inner, names);
+                    }
+                }
+                panic!("Option in C API must have type argument");
+            }
+            let c_name = match name.as_str() {
+                "bool" => "bool",
+                "u8" => "uint8_t",
+                "i8" => "int8_t",
+                "u16" => "uint16_t",
+                "i16" => "int16_t",
+                "u32" => "uint32_t",
+                "i32" => "int32_t",
+                "u64" => "uint64_t",
+                "i64" => "int64_t",
+                "usize" => "size_t",
+                "isize" => "ptrdiff_t",
+                "f32" => "float",
+                "f64" => "double",
+                "c_char" => "char",
+                _ => {
+                    if names.aliases.contains(&name) || names.structs.contains(&name) {
+                        return (format!("Tg{name}"), None);
+                    }
+                    panic!("Type {name} can't be used in C API");
+                }
+            };
+            (c_name.to_string(), None)
+        }
+        Type::Ptr(ptr_type) => {
+            let (elem, _) = get_c_type(&ptr_type.elem, names);
+            if ptr_type.mutability.is_some() {
+                (format!("{elem}*"), None)
+            } else {
+                (format!("const {elem}*"), None)
+            }
+        }
+        Type::Reference(ref_type) => {
+            let (elem, _) = get_c_type(&ref_type.elem, names);
+            if ref_type.mutability.is_some() {
+                (format!("{elem}*"), None)
+            } else {
+                (format!("const {elem}*"), None)
+            }
+        }
+        Type::BareFn(fn_type) => {
+            let ret = get_return_type(&fn_type.output, names);
+            let args = fn_type.inputs.iter()
+                .map(|input| get_c_type(&input.ty, names).0)
+                .collect::<Vec<String>>();
+            (ret, Some(get_args_list(args)))
+        }
+        Type::Tuple(tuple_type) if tuple_type.elems.is_empty() => ("void".to_string(), None),
+        _ => panic!("Type can't be used in C API")
+    }
+}
+
+fn get_return_type(output: &ReturnType, names: &Names) -> String {
+    match output {
+        ReturnType::Default => "void".to_string(),
+        ReturnType::Type(_, ret_type) => get_c_type(ret_type, names).0
+    }
+}
+
+fn get_args_list(args: Vec<String>) -> String {
+    if args.is_empty() {
+        "(void)".to_string()
+    } else {
+        format!("({})", args.join(", "))
+    }
+}
+
+/// Declaration of variable or field with given name
+fn get_declaration(t: &Type, name: &str, names: &Names) -> String {
+    match get_c_type(t, names) {
+        (ret, Some(args)) => format!("{ret} (*{name}){args}"),
+        (c_type, None) => format!("{c_type} {name}")
+    }
+}
+
+fn parse_file(crate_dir: &Path, path: &str) -> File {
+    let code = std::fs::read_to_string(crate_dir.join(path)).expect("Failed to read source of C API");
+    syn::parse_file(&code).expect("Failed to parse source of C API")
+}
+
+/// Replace public re-exports of shared `#[repr(C)]` structs by their declarations,
+/// so they are placed in header like own types of source
+fn resolve_shared_types(crate_dir: &Path, source: &mut File) {
+    let mut shared: Option<File> = None;
+    for item in &mut source.items {
+        let Item::Use(use_item) = item else { continue };
+        if !matches!(use_item.vis, Visibility::Public(_)) {
+            continue;
+        }
+        let UseTree::Path(path) = &use_item.tree else { continue };
+        let UseTree::Name(name) = path.tree.as_ref() else { continue };
+        if path.ident != SHARED_CRATE {
+            continue;
+        }
+        let shared = shared.get_or_insert_with(|| parse_file(crate_dir, SHARED_SOURCE));
+        let declaration = shared.items.iter().find(|shared_item| {
+            matches!(shared_item, Item::Struct(s) if s.ident == name.ident && is_repr_c(&s.attrs))
+        });
+        if let Some(declaration) = declaration {
+            *item = declaration.clone();
+        }
+    }
+}
+
+/// Generate code of header for `tangara` crate in given directory
+pub fn generate_header(crate_dir: &Path) -> String {
+    let sources = TYPE_SOURCES.map(|path| {
+        let mut source = parse_file(crate_dir, path);
+        resolve_shared_types(crate_dir, &mut source);
+        source
+    });
+    let mut names = Names {
+        aliases: HashSet::new(),
+        structs: HashSet::new()
+    };
+    for source in &sources {
+        for item in &source.items {
+            match item {
+                Item::Type(type_item) => {
+                    names.aliases.insert(type_item.ident.to_string());
+                }
+                Item::Struct(struct_item) => {
+                    names.structs.insert(struct_item.ident.to_string());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut types_block = String::new();
+    let mut structs_block = String::new();
+    for source in &sources {
+        for item in &source.items {
+            match item {
+                Item::Type(type_item) if matches!(type_item.vis, Visibility::Public(_)) => {
+                    write_docs(&mut types_block, &type_item.attrs, "");
+                    let name = format!("Tg{}", type_item.ident);
+                    types_block.push_str(&format!("typedef {};\n", get_declaration(&type_item.ty, &name, &names)));
+                }
+                Item::Struct(struct_item) if matches!(struct_item.vis, Visibility::Public(_)) => {
+                    let name = format!("Tg{}", struct_item.ident);
+                    // every struct is declared before aliases, so they can use structs by value
+                    types_block.push_str(&format!("typedef struct {name} {name};\n"));
+                    if is_repr_c(&struct_item.attrs) {
+                        write_docs(&mut structs_block, &struct_item.attrs, "");
+                        structs_block.push_str(&format!("struct {name} {{\n"));
+                        for field in &struct_item.fields {
+                            let field_name = field.ident.as_ref().expect("Field of C struct must be named");
+                            write_docs(&mut structs_block, &field.attrs, "    ");
+                            structs_block.push_str(
+                                &format!("    {};\n", get_declaration(&field.ty, &field_name.to_string(), &names))
+                            );
+                        }
+                        structs_block.push_str("};\n\n");
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut functions_block = String::new();
+    for item in &sources[TYPE_SOURCES.iter().position(|&path| path == FFI_SOURCE).unwrap()].items {
+        if let Item::Fn(fn_item) = item {
+            if fn_item.sig.abi.is_some() && matches!(fn_item.vis, Visibility::Public(_)) {
+                let args = fn_item.sig.inputs.iter().map(|input| {
+                    if let FnArg::Typed(arg) = input {
+                        if let Pat::Ident(arg_ident) = arg.pat.as_ref() {
+                            return get_declaration(&arg.ty, &arg_ident.ident.to_string(), &names);
+                        }
+                    }
+                    panic!("C API function can't have 'self' or pattern arguments")
+                }).collect::<Vec<String>>();
+                write_docs(&mut functions_block, &fn_item.attrs, "");
+                functions_block.push_str(&format!(
+                    "{} {}{};\n\n",
+                    get_return_type(&fn_item.sig.output, &names),
+                    fn_item.sig.ident,
+                    get_args_list(args)
+                ));
+            }
+        }
+    }
+
+    format!(r#"// This file was generated by `cargo xtask header`
+// All changes in this file will discard after regenerating it
+#ifndef TANGARA_H
+#define TANGARA_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {{
+#endif
+
+{types_block}
+{structs_block}{functions_block}#ifdef __cplusplus
+}}
+#endif
+
+#endif // TANGARA_H
+"#)
+}
@@ -0,0 +1,34 @@
+//! Development tasks of workspace, run them by `cargo xtask <task>`.
+//!
+//! Tasks:
+//! - `header` - generate `tangara/include/tangara.h` from C API of `tangara`
+//! - `header --check` - fail if committed `tangara.h` differs from generated one
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use xtask::{generate_header, HEADER_PATH};
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tangara");
+    let header_path = crate_dir.join(HEADER_PATH);
+    match args.iter().map(String::as_str).collect::<Vec<&str>>().as_slice() {
+        ["header"] => {
+            std::fs::write(&header_path, generate_header(&crate_dir)).expect("Failed to write tangara.h");
+            ExitCode::SUCCESS
+        }
+        ["header", "--check"] => {
+            let committed = std::fs::read_to_string(&header_path).unwrap_or_default();
+            if committed == generate_header(&crate_dir) {
+                ExitCode::SUCCESS
+            } else {
+                eprintln!("{} is outdated, run `cargo xtask header`", header_path.display());
+                ExitCode::FAILURE
+            }
+        }
+        _ => {
+            eprintln!("Usage: cargo xtask header [--check]");
+            ExitCode::FAILURE
+        }
+    }
+}
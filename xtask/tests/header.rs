@@ -0,0 +1,9 @@
+use std::path::PathBuf;
+use xtask::{generate_header, HEADER_PATH};
+
+#[test]
+fn header_is_up_to_date() {
+    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tangara");
+    let committed = std::fs::read_to_string(crate_dir.join(HEADER_PATH)).unwrap();
+    assert!(committed == generate_header(&crate_dir), "tangara.h is outdated, run `cargo xtask header`");
+}